@@ -4,6 +4,7 @@ use songbird::{
     driver::{
         bench_internals::mixer::{mix_logic, state::DecodeState},
         MixMode,
+        ResampleQuality,
     },
     input::{codecs::*, Input, LiveInput, Parsed},
     test_utils as utils,
@@ -45,6 +46,7 @@ pub fn mix_one_frame(c: &mut Criterion) {
                             input,
                             local_input,
                             black_box(1.0),
+                            ResampleQuality::default(),
                             None,
                         ));
                     },
@@ -70,6 +72,7 @@ pub fn mix_one_frame(c: &mut Criterion) {
                             input,
                             local_input,
                             black_box(1.0),
+                            ResampleQuality::default(),
                             None,
                         ));
                     },
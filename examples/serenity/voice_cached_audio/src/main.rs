@@ -150,12 +150,13 @@ async fn main() {
         let _ = song_src.raw.spawn_loader();
 
         // Compressed sources are internally stored as DCA1 format files.
-        // Because `Compressed` implements `std::io::Read`, we can save these
-        // to disk and use them again later if we want!
-        let mut creator = song_src.new_handle();
+        // We can save these to disk and use them again later if we want!
+        let creator = song_src.new_handle();
         std::thread::spawn(move || {
             let mut out_file = std::fs::File::create("ckick-dca1.dca").unwrap();
-            std::io::copy(&mut creator, &mut out_file).expect("Error writing out song!");
+            creator
+                .write_dca(&mut out_file)
+                .expect("Error writing out song!");
         });
 
         audio_map.insert("song".into(), CachedSound::Compressed(song_src));
@@ -178,7 +178,16 @@ impl VoiceEventHandler for Receiver {
             Ctx::RtcpPacket(data) => {
                 // An event which fires for every received rtcp packet,
                 // containing the call statistics and reporting information.
-                println!("RTCP packet received: {:?}", data.packet);
+                if let Some(report) = data.sender_report() {
+                    println!("RTCP sender report received: {:?}", report);
+                } else {
+                    println!("RTCP packet received: {:?}", data.packet);
+                }
+            },
+            Ctx::ClientConnect(data) => {
+                // Discord no longer announces joining users directly: this is derived
+                // from each user's first speaking state update, and so includes their SSRC.
+                println!("Client connected: user {:?} has SSRC {:?}", data.user_id, data.ssrc);
             },
             Ctx::ClientDisconnect(ClientDisconnect { user_id, .. }) => {
                 // You can implement your own logic here to handle a user who has left the
@@ -260,6 +269,7 @@ async fn join(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
         handler.add_global_event(CoreEvent::SpeakingStateUpdate.into(), evt_receiver.clone());
         handler.add_global_event(CoreEvent::RtpPacket.into(), evt_receiver.clone());
         handler.add_global_event(CoreEvent::RtcpPacket.into(), evt_receiver.clone());
+        handler.add_global_event(CoreEvent::ClientConnect.into(), evt_receiver.clone());
         handler.add_global_event(CoreEvent::ClientDisconnect.into(), evt_receiver.clone());
         handler.add_global_event(CoreEvent::VoiceTick.into(), evt_receiver);
 
@@ -22,9 +22,9 @@
 
 use futures::StreamExt;
 use songbird::{
-    input::{Compose, YoutubeDl},
+    input::YoutubeDl,
     shards::TwilightMap,
-    tracks::{PlayMode, TrackHandle},
+    tracks::{PlayMode, Track, TrackHandle},
     Songbird,
 };
 use std::{collections::HashMap, env, error::Error, future::Future, num::NonZeroU64, sync::Arc};
@@ -219,8 +219,8 @@ async fn play(msg: Message, state: State) -> Result<(), Box<dyn Error + Send + S
 
     let guild_id = msg.guild_id.unwrap();
 
-    let mut src = YoutubeDl::new(reqwest::Client::new(), msg.content.clone());
-    if let Ok(metadata) = src.aux_metadata().await {
+    let src = YoutubeDl::new(reqwest::Client::new(), msg.content.clone());
+    if let Ok((track, metadata)) = Track::with_aux_metadata(src.into()).await {
         let content = format!(
             "Playing **{:?}** by **{:?}**",
             metadata.track.as_ref().unwrap_or(&"<UNKNOWN>".to_string()),
@@ -235,7 +235,7 @@ async fn play(msg: Message, state: State) -> Result<(), Box<dyn Error + Send + S
 
         if let Some(call_lock) = state.songbird.get(guild_id) {
             let mut call = call_lock.lock().await;
-            let handle = call.play_input(src.into());
+            let handle = call.play(track);
 
             let mut store = state.trackdata.write().await;
             store.insert(guild_id, handle);
@@ -1,17 +1,32 @@
-#[cfg(feature = "receive")]
-use crate::driver::DecodeMode;
 #[cfg(feature = "driver")]
 use crate::{
+    constants::{
+        MIN_SILENCE_FRAMES_ON_STOP,
+        RTP_PROFILE_TYPE,
+        RTP_VERSION,
+        UDP_KEEPALIVE_GAP,
+        UDP_KEEPALIVE_GAP_MIN,
+    },
     driver::{
-        retry::Retry,
+        opus::Application,
+        retry::{ExponentialBackoff, Retry, Strategy},
         tasks::disposal::DisposalThread,
         CryptoMode,
         MixMode,
+        Proxy,
+        ResampleQuality,
         Scheduler,
         DEFAULT_SCHEDULER,
     },
     input::codecs::*,
 };
+#[cfg(all(feature = "driver", feature = "receive"))]
+use crate::{
+    driver::{AdaptiveBitrate, DecodeMode},
+    model::id::UserId,
+};
+#[cfg(feature = "driver")]
+use discortp::rtp::RtpType;
 
 #[cfg(test)]
 use crate::driver::test_config::*;
@@ -21,12 +36,45 @@ use crate::driver::SchedulerConfig;
 #[cfg(feature = "driver")]
 use symphonia::core::{codecs::CodecRegistry, probe::Probe};
 
+use crate::error::ConfigError;
 use derivative::Derivative;
-#[cfg(feature = "receive")]
+#[cfg(all(feature = "driver", feature = "receive"))]
+use std::collections::HashSet;
+#[cfg(feature = "driver")]
 use std::num::NonZeroUsize;
 use std::time::Duration;
 
 /// Configuration for drivers and calls.
+///
+/// Configs are built by chaining setters onto [`Config::default`], or onto one of the
+/// presets [`Config::low_latency`], [`Config::high_quality`], and
+/// [`Config::receive_focused`]. [`Config::validate`] checks for conflicting or
+/// out-of-range settings, which would otherwise be silently adjusted by the driver.
+///
+/// # Changing configuration
+///
+/// A new config may be given to a running driver via [`Driver::set_config`]. Most fields
+/// apply immediately, including those controlling mixing, encoding, and the receive
+/// playout buffer. Some only apply from a later point:
+/// * [`crypto_mode`], [`crypto_mode_fallbacks`], [`udp_recv_buffer_size`], [`driver_timeout`], [`driver_retry`],
+///   [`keepalive_interval`], and [`proxy`] apply from the next voice connection,
+/// * [`gateway_timeout`] applies from the next join attempt,
+/// * [`preallocated_tracks`] can only ever grow the track store, and
+/// * [`disposer`] and [`scheduler`] are only read when a driver is created.
+///
+/// [`Driver::set_config`]: crate::driver::Driver::set_config
+/// [`crypto_mode`]: Self::crypto_mode
+/// [`crypto_mode_fallbacks`]: Self::crypto_mode_fallbacks
+/// [`udp_recv_buffer_size`]: Self::udp_recv_buffer_size
+/// [`driver_timeout`]: Self::driver_timeout
+/// [`driver_retry`]: Self::driver_retry
+/// [`keepalive_interval`]: Self::keepalive_interval
+/// [`proxy`]: Self::proxy
+/// [`gateway_timeout`]: Self::gateway_timeout
+/// [`preallocated_tracks`]: Self::preallocated_tracks
+/// [`disposer`]: Self::disposer
+/// [`scheduler`]: Self::scheduler
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Clone, Derivative)]
 #[derivative(Debug)]
 #[non_exhaustive]
@@ -43,6 +91,21 @@ pub struct Config {
     /// [`CryptoMode::Normal`]: CryptoMode::Normal
     pub crypto_mode: CryptoMode,
 
+    #[cfg(feature = "driver")]
+    /// Tagging modes to fall back to, in order of preference, if the voice server
+    /// does not offer [`crypto_mode`].
+    ///
+    /// The first mode offered by the server is used for the session, and is
+    /// reported in [`ConnectData::crypto_mode`]. If none are offered, connection
+    /// fails with [`Error::CryptoModeUnavailable`].
+    ///
+    /// Defaults to an empty list, i.e., only [`crypto_mode`] is acceptable.
+    ///
+    /// [`crypto_mode`]: Self::crypto_mode
+    /// [`ConnectData::crypto_mode`]: crate::events::context_data::ConnectData::crypto_mode
+    /// [`Error::CryptoModeUnavailable`]: crate::error::ConnectionError::CryptoModeUnavailable
+    pub crypto_mode_fallbacks: Vec<CryptoMode>,
+
     #[cfg(all(feature = "driver", feature = "receive"))]
     /// Configures whether decoding and decryption occur for all received packets.
     ///
@@ -76,7 +139,14 @@ pub struct Config {
     ///
     /// This does not affect the arrival of raw packet events.
     ///
+    /// Changes made via [`Driver::set_config`] apply to live users without resetting
+    /// their buffers. Increasing this length briefly withholds each user's audio to
+    /// build up the added delay. Reducing it never discards buffered audio all at once:
+    /// one packet per tick is skipped until each buffer reaches the new length.
+    ///
     /// Defaults to 5 packets (100ms).
+    ///
+    /// [`Driver::set_config`]: crate::driver::Driver::set_config
     pub playout_buffer_length: NonZeroUsize,
 
     #[cfg(all(feature = "driver", feature = "receive"))]
@@ -88,6 +158,85 @@ pub struct Config {
     /// Defaults to 3 packets (thus capacity defaults to 8).
     pub playout_spike_length: usize,
 
+    #[cfg(all(feature = "driver", feature = "receive"))]
+    /// Configures whether each [`VoiceTick`] includes the decoded audio of all speaking
+    /// users mixed into a single buffer, in [`VoiceTick::mixed_voice`].
+    ///
+    /// This requires [`DecodeMode::Decode`], and costs an extra pass over every
+    /// user's audio each tick.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// [`VoiceTick`]: crate::events::context_data::VoiceTick
+    /// [`VoiceTick::mixed_voice`]: crate::events::context_data::VoiceTick::mixed_voice
+    pub mix_received_voice: bool,
+
+    #[cfg(all(feature = "driver", feature = "receive"))]
+    /// SSRCs whose audio is left out of [`VoiceTick::mixed_voice`].
+    ///
+    /// Their decoded audio is still delivered individually in [`VoiceTick::speaking`].
+    ///
+    /// Defaults to an empty set.
+    ///
+    /// [`VoiceTick::mixed_voice`]: crate::events::context_data::VoiceTick::mixed_voice
+    /// [`VoiceTick::speaking`]: crate::events::context_data::VoiceTick::speaking
+    pub mix_received_muted_ssrcs: HashSet<u32>,
+
+    #[cfg(all(feature = "driver", feature = "receive"))]
+    /// Users whose audio is left out of [`VoiceTick::mixed_voice`].
+    ///
+    /// A user's SSRC is learned from their first [`SpeakingStateUpdate`]: until then,
+    /// their audio will still be mixed unless their SSRC is also in
+    /// [`Self::mix_received_muted_ssrcs`]. Their decoded audio is still delivered
+    /// individually in [`VoiceTick::speaking`].
+    ///
+    /// Defaults to an empty set.
+    ///
+    /// [`VoiceTick::mixed_voice`]: crate::events::context_data::VoiceTick::mixed_voice
+    /// [`VoiceTick::speaking`]: crate::events::context_data::VoiceTick::speaking
+    /// [`SpeakingStateUpdate`]: crate::events::CoreEvent::SpeakingStateUpdate
+    pub mix_received_muted_users: HashSet<UserId>,
+
+    #[cfg(all(feature = "driver", feature = "receive"))]
+    /// Configures whether the driver adapts its Opus encoder bitrate to packet loss
+    /// reported by Discord's voice servers, and within which bounds.
+    ///
+    /// If set, the bitrate is lowered when sustained loss is reported in RTCP receiver
+    /// reports, and raised again once the connection recovers. Each change fires a
+    /// [`CoreEvent::BitrateChange`]. Bitrates set using [`Driver::set_bitrate`] are
+    /// clamped to these bounds, and used as a starting point. A bitrate matched from
+    /// passthrough audio (see [`Self::match_passthrough_bitrate`]) takes precedence.
+    ///
+    /// RTCP packets are decrypted for this purpose, even if [`decode_mode`] is
+    /// [`DecodeMode::Pass`].
+    ///
+    /// Defaults to `None`.
+    ///
+    /// [`CoreEvent::BitrateChange`]: crate::events::CoreEvent::BitrateChange
+    /// [`Driver::set_bitrate`]: crate::driver::Driver::set_bitrate
+    /// [`decode_mode`]: Self::decode_mode
+    /// [`DecodeMode::Pass`]: DecodeMode::Pass
+    pub adaptive_bitrate: Option<AdaptiveBitrate>,
+
+    #[cfg(all(feature = "driver", feature = "receive"))]
+    /// Configures the size of the UDP socket's receive buffer, in bytes.
+    ///
+    /// On high-latency or lossy links, packets may arrive in large bursts. Enlarging
+    /// this buffer reduces how many are dropped by the kernel before the driver can
+    /// place them into each user's playout buffer. This applies from the next
+    /// connection made by the driver.
+    ///
+    /// The operating system treats this as a request, rather than a guarantee:
+    /// * Linux doubles the requested size for bookkeeping, and caps it at
+    ///   `net.core.rmem_max`,
+    /// * macOS rejects sizes above `kern.ipc.maxsockbuf`, and
+    /// * Windows accepts any size, but may not honour very large buffers.
+    ///
+    /// If the size cannot be set, the default buffer is kept and a warning is logged.
+    ///
+    /// Defaults to `None`, using the operating system's default size.
+    pub udp_recv_buffer_size: Option<usize>,
+
     #[cfg(feature = "gateway")]
     /// Configures the amount of time to wait for Discord to reply with connection information
     /// if [`Call::join`]/[`join_gateway`] are used.
@@ -106,11 +255,25 @@ pub struct Config {
     /// Configures whether the driver will mix and output stereo or mono Opus data
     /// over a voice channel.
     ///
+    /// Audio is always mixed at 48kHz; see [`MixMode::signal_spec`] for the exact
+    /// format of mixed audio.
+    ///
     /// Defaults to [`Stereo`].
     ///
     /// [`Stereo`]: MixMode::Stereo
     pub mix_mode: MixMode,
 
+    #[cfg(feature = "driver")]
+    /// Configures the resampler used for audio sources which are not sampled at 48kHz.
+    ///
+    /// Changes to this field in a running driver apply to each track when it
+    /// is next resampled.
+    ///
+    /// Defaults to [`Balanced`].
+    ///
+    /// [`Balanced`]: ResampleQuality::Balanced
+    pub resample_quality: ResampleQuality,
+
     #[cfg(feature = "driver")]
     /// Number of concurrently active tracks to allocate memory for.
     ///
@@ -125,6 +288,22 @@ pub struct Config {
     /// the capacity of the track store.
     pub preallocated_tracks: usize,
 
+    #[cfg(feature = "driver")]
+    /// Maximum number of tracks which a driver will hold at once.
+    ///
+    /// Tracks added beyond this limit are not played: they immediately end with
+    /// [`PlayError::TrackLimit`], firing any [`TrackEvent::Error`] handlers.
+    /// Unlike [`preallocated_tracks`], this is a hard upper bound, which protects
+    /// the mixer against runaway queues. Lowering this on a running driver does not
+    /// remove any existing tracks.
+    ///
+    /// Defaults to `None` (no limit).
+    ///
+    /// [`PlayError::TrackLimit`]: crate::tracks::PlayError::TrackLimit
+    /// [`TrackEvent::Error`]: crate::events::TrackEvent::Error
+    /// [`preallocated_tracks`]: Self::preallocated_tracks
+    pub max_tracks: Option<usize>,
+
     #[cfg(feature = "driver")]
     /// Connection retry logic for the [`Driver`].
     ///
@@ -134,6 +313,39 @@ pub struct Config {
     /// [`Driver`]: crate::driver::Driver
     pub driver_retry: Retry,
 
+    #[cfg(feature = "driver")]
+    /// Retry logic for creating the audio streams of lazy [`Input`]s.
+    ///
+    /// When a track's [`Compose`] fails with [`AudioStreamError::RetryIn`] (e.g., if
+    /// a source is rate-limiting requests), creation is retried after the longer of
+    /// this strategy's delay and the requested delay, firing [`TrackEvent::Retrying`]
+    /// each time. Other errors, or exceeding the retry limit, mark the track as errored.
+    ///
+    /// Defaults to at most 3 retries, using the default exponential backoff.
+    ///
+    /// [`Input`]: crate::input::Input
+    /// [`Compose`]: crate::input::Compose
+    /// [`AudioStreamError::RetryIn`]: crate::input::AudioStreamError::RetryIn
+    /// [`TrackEvent::Retrying`]: crate::events::TrackEvent::Retrying
+    pub input_retry: Retry,
+
+    #[cfg(feature = "driver")]
+    /// Maximum time a track's [`Input`] may spend being created, parsed, or
+    /// seeked before the mixer gives up on it.
+    ///
+    /// Tracks which take longer (e.g., due to a stalled HTTP connection or a hung
+    /// child process) are marked as errored with [`PlayError::ReadyTimeout`], firing
+    /// [`TrackEvent::Error`]. Time spent waiting between [`input_retry`] attempts
+    /// is not counted.
+    ///
+    /// Defaults to `None`, allowing inputs to take any amount of time to ready.
+    ///
+    /// [`Input`]: crate::input::Input
+    /// [`PlayError::ReadyTimeout`]: crate::tracks::PlayError::ReadyTimeout
+    /// [`TrackEvent::Error`]: crate::events::TrackEvent::Error
+    /// [`input_retry`]: Self::input_retry
+    pub input_ready_timeout: Option<Duration>,
+
     #[cfg(feature = "driver")]
     /// Configures whether or not each mixed audio packet is [soft-clipped] into the
     /// [-1, 1] audio range.
@@ -149,6 +361,110 @@ pub struct Config {
     /// [soft-clipped]: https://opus-codec.org/docs/opus_api-1.3.1/group__opus__decoder.html#gaff99598b352e8939dded08d96e125e0b
     pub use_softclip: bool,
 
+    #[cfg(feature = "driver")]
+    /// Configures whether Opus audio may be passed directly through to Discord,
+    /// without being decoded and re-encoded.
+    ///
+    /// Defaults to `true`. If disabled, all audio is decoded and mixed even if
+    /// only one Opus track is playing at full volume. This is useful for checking
+    /// whether audio glitches are caused by passthrough, at the cost of CPU time.
+    pub allow_passthrough: bool,
+
+    #[cfg(feature = "driver")]
+    /// Number of Opus silence frames to send when audio stops, before the driver
+    /// stops sending packets.
+    ///
+    /// Discord's guidelines require that *at least* 5 frames of silence are sent
+    /// whenever transmission ends, to avoid Opus interpolation artefacts on
+    /// receiving clients. Larger values may help some clients avoid treating
+    /// short pauses as the end of a stream. Values below `5` are treated as `5`.
+    ///
+    /// Defaults to `5`.
+    pub silence_frames_on_stop: u8,
+
+    #[cfg(feature = "driver")]
+    /// Whether the Opus encoder should adopt the bitrate of the most recent passthrough
+    /// source when the driver returns to mixing audio.
+    ///
+    /// While a single Opus track is played via passthrough, the driver measures the
+    /// average bitrate of its frames. If enabled, the next mixed or transcoded audio is
+    /// encoded at this bitrate rather than the one set by [`Driver::set_bitrate`], so
+    /// that transitions between passthrough and mixed sources do not audibly change quality.
+    /// Calling [`Driver::set_bitrate`] overrides any measured bitrate until passthrough
+    /// next ends.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// [`Driver::set_bitrate`]: crate::driver::Driver::set_bitrate
+    pub match_passthrough_bitrate: bool,
+
+    #[cfg(feature = "driver")]
+    /// Configures whether the Opus encoder embeds in-band forward error correction (FEC)
+    /// data in mixed audio packets.
+    ///
+    /// FEC allows receiving clients to recover from the loss of individual packets,
+    /// at the cost of a lower effective bitrate for the audio itself. This has no effect
+    /// on audio sent via passthrough.
+    ///
+    /// Defaults to `false`.
+    pub opus_fec: bool,
+
+    #[cfg(feature = "driver")]
+    /// Configures whether the Opus encoder uses discontinuous transmission (DTX) when
+    /// mixed audio is silent.
+    ///
+    /// With DTX enabled, silent or near-silent audio within a playing track is sent as
+    /// minimal Opus frames, reducing bandwidth use. This is independent of the explicit
+    /// [`silence_frames_on_stop`] sent once all audio has stopped: these are always sent,
+    /// and the encoder's state is reset afterwards so that DTX does not carry over into
+    /// the next burst of audio.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// [`silence_frames_on_stop`]: Self::silence_frames_on_stop
+    pub opus_dtx: bool,
+
+    #[cfg(feature = "driver")]
+    /// Selects the application mode used by the Opus encoder for mixed audio.
+    ///
+    /// [`Application::Voip`] favours speech intelligibility, [`Application::Audio`]
+    /// favours fidelity for music and general audio, and [`Application::LowDelay`]
+    /// disables speech-specific optimisations to reduce encoding latency. Changing this
+    /// on a live driver rebuilds the encoder. This has no effect on audio sent via
+    /// passthrough.
+    ///
+    /// Defaults to [`Application::Audio`].
+    pub opus_application: Application,
+
+    #[cfg(feature = "driver")]
+    /// Sets the computational complexity of the Opus encoder for mixed audio, from `0`
+    /// (cheapest) to `10` (highest quality). Larger values are treated as `10`.
+    ///
+    /// Encoding is typically the most expensive part of mixing, so lowering this noticeably
+    /// reduces CPU use on hosts with many active calls, and allows the scheduler to fit more
+    /// calls onto each mixing thread. Changing this on a live driver rebuilds the encoder.
+    /// This has no effect on audio sent via passthrough.
+    ///
+    /// Defaults to `None`, which keeps libopus's own default complexity.
+    pub opus_complexity: Option<u8>,
+
+    #[cfg(feature = "driver")]
+    /// Configures the maximum number of core events (e.g., [`VoiceTick`], [`RtpPacket`])
+    /// which may be waiting for delivery to event handlers.
+    ///
+    /// Core events are fired in order by a single task. If handlers take longer to run
+    /// than events arrive, then pending events accumulate in memory. When this limit
+    /// is reached, the oldest pending core event is dropped to make room for each new
+    /// event: dropped events are logged, and counted by [`Driver::dropped_events`].
+    /// Track events and driver state changes are never dropped.
+    ///
+    /// Defaults to `None`, where no events are dropped.
+    ///
+    /// [`VoiceTick`]: crate::events::CoreEvent::VoiceTick
+    /// [`RtpPacket`]: crate::events::CoreEvent::RtpPacket
+    /// [`Driver::dropped_events`]: crate::driver::Driver::dropped_events
+    pub event_queue_limit: Option<NonZeroUsize>,
+
     #[cfg(feature = "driver")]
     /// Configures the maximum amount of time to wait for an attempted voice
     /// connection to Discord.
@@ -156,6 +472,53 @@ pub struct Config {
     /// Defaults to 10 seconds. If set to `None`, connections will never time out.
     pub driver_timeout: Option<Duration>,
 
+    #[cfg(feature = "driver")]
+    /// Configures the delay between UDP keepalive packets sent to Discord.
+    ///
+    /// Keepalives hold open NAT mappings for the voice socket, which is important while
+    /// no audio is being sent. Networks with aggressive NAT timeouts may need a shorter
+    /// interval, while a longer one saves a small amount of traffic per call.
+    /// Values below [`UDP_KEEPALIVE_GAP_MIN`] are raised to that minimum to avoid
+    /// flooding Discord's voice servers.
+    ///
+    /// Defaults to [`UDP_KEEPALIVE_GAP`] (5 seconds), matching Discord's own clients.
+    ///
+    /// [`UDP_KEEPALIVE_GAP`]: crate::constants::UDP_KEEPALIVE_GAP
+    /// [`UDP_KEEPALIVE_GAP_MIN`]: crate::constants::UDP_KEEPALIVE_GAP_MIN
+    pub keepalive_interval: Duration,
+
+    #[cfg(feature = "driver")]
+    /// RTP version written into, and expected from, voice packets.
+    ///
+    /// This only needs changing when talking to a voice server which does not
+    /// behave like Discord's, such as a custom relay or test harness. Received
+    /// packets with any other version are discarded.
+    ///
+    /// Defaults to [`RTP_VERSION`] (2).
+    ///
+    /// [`RTP_VERSION`]: crate::constants::RTP_VERSION
+    pub rtp_version: u8,
+
+    #[cfg(feature = "driver")]
+    /// RTP payload type written into, and expected from, voice packets.
+    ///
+    /// Received packets with any other payload type are discarded.
+    ///
+    /// Defaults to [`RTP_PROFILE_TYPE`] (dynamic type 120), as used by Discord
+    /// for Opus audio.
+    ///
+    /// [`RTP_PROFILE_TYPE`]: crate::constants::RTP_PROFILE_TYPE
+    pub rtp_payload_type: RtpType,
+
+    #[cfg(feature = "driver")]
+    /// Configures a proxy through which the voice gateway's websocket is dialed.
+    ///
+    /// Voice packets are always sent over a direct UDP socket, as neither
+    /// supported proxy type can relay UDP traffic. See [`Proxy`] for details.
+    ///
+    /// Defaults to `None`.
+    pub proxy: Option<Proxy>,
+
     #[cfg(feature = "driver")]
     #[derivative(Debug = "ignore")]
     /// Registry of the inner codecs supported by the driver, adding audiopus-based
@@ -212,6 +575,8 @@ impl Default for Config {
         Self {
             #[cfg(feature = "driver")]
             crypto_mode: CryptoMode::Normal,
+            #[cfg(feature = "driver")]
+            crypto_mode_fallbacks: Vec::new(),
             #[cfg(all(feature = "driver", feature = "receive"))]
             decode_mode: DecodeMode::Decrypt,
             #[cfg(all(feature = "driver", feature = "receive"))]
@@ -220,19 +585,64 @@ impl Default for Config {
             playout_buffer_length: NonZeroUsize::new(5).unwrap(),
             #[cfg(all(feature = "driver", feature = "receive"))]
             playout_spike_length: 3,
+            #[cfg(all(feature = "driver", feature = "receive"))]
+            mix_received_voice: false,
+            #[cfg(all(feature = "driver", feature = "receive"))]
+            mix_received_muted_ssrcs: HashSet::new(),
+            #[cfg(all(feature = "driver", feature = "receive"))]
+            mix_received_muted_users: HashSet::new(),
+            #[cfg(all(feature = "driver", feature = "receive"))]
+            adaptive_bitrate: None,
+            #[cfg(all(feature = "driver", feature = "receive"))]
+            udp_recv_buffer_size: None,
             #[cfg(feature = "gateway")]
             gateway_timeout: Some(Duration::from_secs(10)),
             #[cfg(feature = "driver")]
             mix_mode: MixMode::Stereo,
             #[cfg(feature = "driver")]
+            resample_quality: ResampleQuality::Balanced,
+            #[cfg(feature = "driver")]
             preallocated_tracks: 1,
             #[cfg(feature = "driver")]
+            max_tracks: None,
+            #[cfg(feature = "driver")]
             use_softclip: true,
             #[cfg(feature = "driver")]
+            allow_passthrough: true,
+            #[cfg(feature = "driver")]
+            silence_frames_on_stop: MIN_SILENCE_FRAMES_ON_STOP,
+            #[cfg(feature = "driver")]
+            match_passthrough_bitrate: false,
+            #[cfg(feature = "driver")]
+            opus_fec: false,
+            #[cfg(feature = "driver")]
+            opus_dtx: false,
+            #[cfg(feature = "driver")]
+            opus_application: Application::Audio,
+            #[cfg(feature = "driver")]
+            opus_complexity: None,
+            #[cfg(feature = "driver")]
             driver_retry: Retry::default(),
             #[cfg(feature = "driver")]
+            input_retry: Retry {
+                strategy: Strategy::Backoff(ExponentialBackoff::default()),
+                retry_limit: Some(3),
+            },
+            #[cfg(feature = "driver")]
+            input_ready_timeout: None,
+            #[cfg(feature = "driver")]
+            event_queue_limit: None,
+            #[cfg(feature = "driver")]
             driver_timeout: Some(Duration::from_secs(10)),
             #[cfg(feature = "driver")]
+            keepalive_interval: UDP_KEEPALIVE_GAP,
+            #[cfg(feature = "driver")]
+            rtp_version: RTP_VERSION,
+            #[cfg(feature = "driver")]
+            rtp_payload_type: RTP_PROFILE_TYPE,
+            #[cfg(feature = "driver")]
+            proxy: None,
+            #[cfg(feature = "driver")]
             codec_registry: &CODEC_REGISTRY,
             #[cfg(feature = "driver")]
             format_registry: &PROBE,
@@ -259,6 +669,14 @@ impl Config {
         self
     }
 
+    /// Sets the tagging schemes this `Config` will fall back to, in order of
+    /// preference, if its chosen scheme is unavailable.
+    #[must_use]
+    pub fn crypto_mode_fallbacks(mut self, crypto_mode_fallbacks: Vec<CryptoMode>) -> Self {
+        self.crypto_mode_fallbacks = crypto_mode_fallbacks;
+        self
+    }
+
     #[cfg(feature = "receive")]
     /// Sets this `Config`'s received packet decryption/decoding behaviour.
     #[must_use]
@@ -291,6 +709,46 @@ impl Config {
         self
     }
 
+    #[cfg(feature = "receive")]
+    /// Sets whether this `Config` mixes all received voice data into one buffer each tick.
+    #[must_use]
+    pub fn mix_received_voice(mut self, mix_received_voice: bool) -> Self {
+        self.mix_received_voice = mix_received_voice;
+        self
+    }
+
+    #[cfg(feature = "receive")]
+    /// Sets the SSRCs which this `Config` leaves out of the mix of received voice data.
+    #[must_use]
+    pub fn mix_received_muted_ssrcs(mut self, mix_received_muted_ssrcs: HashSet<u32>) -> Self {
+        self.mix_received_muted_ssrcs = mix_received_muted_ssrcs;
+        self
+    }
+
+    #[cfg(feature = "receive")]
+    /// Sets the users which this `Config` leaves out of the mix of received voice data.
+    #[must_use]
+    pub fn mix_received_muted_users(mut self, mix_received_muted_users: HashSet<UserId>) -> Self {
+        self.mix_received_muted_users = mix_received_muted_users;
+        self
+    }
+
+    #[cfg(feature = "receive")]
+    /// Sets this `Config`'s bounds for adapting the encoder bitrate to packet loss.
+    #[must_use]
+    pub fn adaptive_bitrate(mut self, adaptive_bitrate: Option<AdaptiveBitrate>) -> Self {
+        self.adaptive_bitrate = adaptive_bitrate;
+        self
+    }
+
+    #[cfg(feature = "receive")]
+    /// Sets this `Config`'s requested UDP receive buffer size, in bytes.
+    #[must_use]
+    pub fn udp_recv_buffer_size(mut self, udp_recv_buffer_size: Option<usize>) -> Self {
+        self.udp_recv_buffer_size = udp_recv_buffer_size;
+        self
+    }
+
     /// Sets this `Config`'s audio mixing channel count.
     #[must_use]
     pub fn mix_mode(mut self, mix_mode: MixMode) -> Self {
@@ -298,6 +756,13 @@ impl Config {
         self
     }
 
+    /// Sets this `Config`'s resampler quality.
+    #[must_use]
+    pub fn resample_quality(mut self, resample_quality: ResampleQuality) -> Self {
+        self.resample_quality = resample_quality;
+        self
+    }
+
     /// Sets this `Config`'s number of tracks to preallocate.
     #[must_use]
     pub fn preallocated_tracks(mut self, preallocated_tracks: usize) -> Self {
@@ -305,6 +770,13 @@ impl Config {
         self
     }
 
+    /// Sets this `Config`'s maximum number of tracks.
+    #[must_use]
+    pub fn max_tracks(mut self, max_tracks: Option<usize>) -> Self {
+        self.max_tracks = max_tracks;
+        self
+    }
+
     /// Sets this `Config`'s number to enable/disable soft-clipping sent audio.
     #[must_use]
     pub fn use_softclip(mut self, use_softclip: bool) -> Self {
@@ -312,6 +784,62 @@ impl Config {
         self
     }
 
+    /// Sets whether this `Config` allows Opus passthrough.
+    #[must_use]
+    pub fn allow_passthrough(mut self, allow_passthrough: bool) -> Self {
+        self.allow_passthrough = allow_passthrough;
+        self
+    }
+
+    /// Sets this `Config`'s number of silence frames to send when audio stops.
+    #[must_use]
+    pub fn silence_frames_on_stop(mut self, silence_frames_on_stop: u8) -> Self {
+        self.silence_frames_on_stop = silence_frames_on_stop;
+        self
+    }
+
+    /// Sets whether this `Config` matches the encoder bitrate to prior passthrough audio.
+    #[must_use]
+    pub fn match_passthrough_bitrate(mut self, match_passthrough_bitrate: bool) -> Self {
+        self.match_passthrough_bitrate = match_passthrough_bitrate;
+        self
+    }
+
+    /// Sets whether this `Config` enables Opus in-band forward error correction.
+    #[must_use]
+    pub fn opus_fec(mut self, opus_fec: bool) -> Self {
+        self.opus_fec = opus_fec;
+        self
+    }
+
+    /// Sets whether this `Config` enables Opus discontinuous transmission.
+    #[must_use]
+    pub fn opus_dtx(mut self, opus_dtx: bool) -> Self {
+        self.opus_dtx = opus_dtx;
+        self
+    }
+
+    /// Sets this `Config`'s Opus encoder application mode.
+    #[must_use]
+    pub fn opus_application(mut self, opus_application: Application) -> Self {
+        self.opus_application = opus_application;
+        self
+    }
+
+    /// Sets this `Config`'s Opus encoder complexity.
+    #[must_use]
+    pub fn opus_complexity(mut self, opus_complexity: Option<u8>) -> Self {
+        self.opus_complexity = opus_complexity;
+        self
+    }
+
+    /// Sets this `Config`'s limit on the number of undelivered core events.
+    #[must_use]
+    pub fn event_queue_limit(mut self, event_queue_limit: Option<NonZeroUsize>) -> Self {
+        self.event_queue_limit = event_queue_limit;
+        self
+    }
+
     /// Sets this `Config`'s timeout for establishing a voice connection.
     #[must_use]
     pub fn driver_timeout(mut self, driver_timeout: Option<Duration>) -> Self {
@@ -319,6 +847,34 @@ impl Config {
         self
     }
 
+    /// Sets this `Config`'s delay between UDP keepalive packets.
+    #[must_use]
+    pub fn keepalive_interval(mut self, keepalive_interval: Duration) -> Self {
+        self.keepalive_interval = keepalive_interval;
+        self
+    }
+
+    /// Sets this `Config`'s RTP version for sent and received voice packets.
+    #[must_use]
+    pub fn rtp_version(mut self, rtp_version: u8) -> Self {
+        self.rtp_version = rtp_version;
+        self
+    }
+
+    /// Sets this `Config`'s RTP payload type for sent and received voice packets.
+    #[must_use]
+    pub fn rtp_payload_type(mut self, rtp_payload_type: RtpType) -> Self {
+        self.rtp_payload_type = rtp_payload_type;
+        self
+    }
+
+    /// Sets this `Config`'s voice gateway proxy.
+    #[must_use]
+    pub fn proxy(mut self, proxy: Option<Proxy>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
     /// Sets this `Config`'s voice connection retry configuration.
     #[must_use]
     pub fn driver_retry(mut self, driver_retry: Retry) -> Self {
@@ -326,6 +882,20 @@ impl Config {
         self
     }
 
+    /// Sets this `Config`'s input creation retry configuration.
+    #[must_use]
+    pub fn input_retry(mut self, input_retry: Retry) -> Self {
+        self.input_retry = input_retry;
+        self
+    }
+
+    /// Sets the maximum time this `Config` allows an input to take to become ready.
+    #[must_use]
+    pub fn input_ready_timeout(mut self, input_ready_timeout: Option<Duration>) -> Self {
+        self.input_ready_timeout = input_ready_timeout;
+        self
+    }
+
     /// Sets this `Config`'s symphonia codec registry.
     #[must_use]
     pub fn codec_registry(mut self, codec_registry: &'static CodecRegistry) -> Self {
@@ -340,6 +910,16 @@ impl Config {
         self
     }
 
+    /// Sets both the symphonia codec registry and format registry used by this `Config`.
+    ///
+    /// All inputs played by a driver using this `Config` are parsed and decoded using
+    /// these registries, allowing codecs or formats to be added or removed on a
+    /// per-driver basis.
+    #[must_use]
+    pub fn with_codecs(self, codecs: &'static CodecRegistry, probe: &'static Probe) -> Self {
+        self.codec_registry(codecs).format_registry(probe)
+    }
+
     /// Sets this `Config`'s channel for sending disposal messages.
     #[must_use]
     pub fn disposer(mut self, disposer: DisposalThread) -> Self {
@@ -373,6 +953,59 @@ impl Config {
         }
     }
 
+    /// Creates a config tuned to reduce the delay added by the driver, at some cost
+    /// to audio quality.
+    ///
+    /// This uses the [`Application::LowDelay`] Opus mode and [`ResampleQuality::Fast`]
+    /// resampling. When receiving audio, each user's playout buffer is shortened to
+    /// 2 packets (40ms), making received audio more sensitive to network jitter.
+    #[must_use]
+    pub fn low_latency() -> Self {
+        let config = Self::default()
+            .opus_application(Application::LowDelay)
+            .resample_quality(ResampleQuality::Fast);
+
+        #[cfg(feature = "receive")]
+        let config = config.playout_buffer_length(NonZeroUsize::new(2).unwrap());
+
+        config
+    }
+
+    /// Creates a config tuned for the quality of mixed audio, at some cost to CPU use.
+    ///
+    /// This uses the highest Opus encoder complexity and [`ResampleQuality::High`]
+    /// resampling, and keeps the bitrate of passthrough audio when switching to
+    /// mixed audio (see [`Self::match_passthrough_bitrate`]).
+    #[must_use]
+    pub fn high_quality() -> Self {
+        Self::default()
+            .opus_application(Application::Audio)
+            .opus_complexity(Some(10))
+            .resample_quality(ResampleQuality::High)
+            .match_passthrough_bitrate(true)
+    }
+
+    /// Creates a config tuned for bots which record or process received voice.
+    ///
+    /// This decodes all received audio ([`DecodeMode::Decode`]), mixes it into
+    /// [`VoiceTick::mixed_voice`], and requests a 1MiB UDP receive buffer to absorb
+    /// bursts of packets. Each user's playout buffer can briefly hold up to twice its
+    /// usual length of packets.
+    ///
+    /// [`VoiceTick::mixed_voice`]: crate::events::context_data::VoiceTick::mixed_voice
+    #[cfg(feature = "receive")]
+    #[must_use]
+    pub fn receive_focused() -> Self {
+        let config = Self::default();
+        let spike_length = config.playout_buffer_length.get();
+
+        config
+            .decode_mode(DecodeMode::Decode)
+            .mix_received_voice(true)
+            .playout_spike_length(spike_length)
+            .udp_recv_buffer_size(Some(1 << 20))
+    }
+
     /// This is used to prevent changes which would invalidate the current session.
     pub(crate) fn make_safe(&mut self, previous: &Config, connected: bool) {
         if connected {
@@ -445,3 +1078,108 @@ impl Config {
         self
     }
 }
+
+impl Config {
+    /// Checks this config for settings which conflict, or which the driver would
+    /// otherwise clamp or ignore.
+    ///
+    /// Drivers accept any config, and so calling this is optional.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        #[cfg(feature = "driver")]
+        {
+            if let Some(complexity) = self.opus_complexity.filter(|c| *c > 10) {
+                return Err(ConfigError::OpusComplexity(complexity));
+            }
+
+            if self.keepalive_interval < UDP_KEEPALIVE_GAP_MIN {
+                return Err(ConfigError::KeepaliveInterval(self.keepalive_interval));
+            }
+
+            if self.driver_timeout == Some(Duration::ZERO) {
+                return Err(ConfigError::ZeroDriverTimeout);
+            }
+
+            if self.rtp_version > 3 {
+                return Err(ConfigError::RtpVersion(self.rtp_version));
+            }
+
+            if let RtpType::Illegal(code) = self.rtp_payload_type {
+                return Err(ConfigError::RtpPayloadType(code));
+            }
+        }
+
+        #[cfg(feature = "gateway")]
+        if self.gateway_timeout == Some(Duration::ZERO) {
+            return Err(ConfigError::ZeroGatewayTimeout);
+        }
+
+        #[cfg(all(feature = "driver", feature = "receive"))]
+        {
+            if self.mix_received_voice && self.decode_mode != DecodeMode::Decode {
+                return Err(ConfigError::MixRequiresDecode);
+            }
+
+            // Matches the largest playout buffer which can be allocated per user.
+            if self.playout_buffer_length.get() > 64 {
+                return Err(ConfigError::PlayoutBufferTooLarge(
+                    self.playout_buffer_length.get(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "driver"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_are_valid() {
+        assert_eq!(Config::default().validate(), Ok(()));
+        assert_eq!(Config::low_latency().validate(), Ok(()));
+        assert_eq!(Config::high_quality().validate(), Ok(()));
+        #[cfg(feature = "receive")]
+        assert_eq!(Config::receive_focused().validate(), Ok(()));
+    }
+
+    #[test]
+    fn out_of_range_settings_are_rejected() {
+        assert_eq!(
+            Config::default().opus_complexity(Some(11)).validate(),
+            Err(ConfigError::OpusComplexity(11))
+        );
+        assert_eq!(
+            Config::default()
+                .keepalive_interval(Duration::from_millis(10))
+                .validate(),
+            Err(ConfigError::KeepaliveInterval(Duration::from_millis(10)))
+        );
+        assert_eq!(
+            Config::default()
+                .driver_timeout(Some(Duration::ZERO))
+                .validate(),
+            Err(ConfigError::ZeroDriverTimeout)
+        );
+        assert_eq!(
+            Config::default().rtp_version(4).validate(),
+            Err(ConfigError::RtpVersion(4))
+        );
+        assert_eq!(
+            Config::default()
+                .rtp_payload_type(RtpType::new(200))
+                .validate(),
+            Err(ConfigError::RtpPayloadType(200))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "receive")]
+    fn mixing_received_voice_requires_decoding() {
+        assert_eq!(
+            Config::default().mix_received_voice(true).validate(),
+            Err(ConfigError::MixRequiresDecode)
+        );
+    }
+}
@@ -33,6 +33,11 @@ pub(crate) const RESAMPLE_OUTPUT_FRAME_SIZE: usize = MONO_FRAME_SIZE / 2;
 /// The maximum number of bad frames to allow in an Opus source before blocking passthrough.
 pub(crate) const OPUS_PASSTHROUGH_STRIKE_LIMIT: u8 = 3;
 
+#[cfg(feature = "driver")]
+/// The maximum number of consecutive undecodable packets a track may skip in one mix,
+/// before it is ended with an error.
+pub(crate) const DECODE_SKIP_LIMIT: usize = 5;
+
 /// Number of samples in one complete frame of audio per channel.
 ///
 /// This is equally the number of stereo (joint) samples in an audio frame.
@@ -58,21 +63,32 @@ pub const CHILD_BUFFER_LEN: usize = AUDIO_FRAME_RATE / 2;
 /// Set a safe amount below the Ethernet MTU to avoid fragmentation/rejection.
 pub const VOICE_PACKET_MAX: usize = 1460;
 
-/// Delay between sends of UDP keepalive frames.
+/// Default delay between sends of UDP keepalive frames.
 ///
 /// Passive monitoring of Discord itself shows that these fire every 5 seconds
 /// irrespective of outgoing UDP traffic.
 pub const UDP_KEEPALIVE_GAP_MS: u64 = 5_000;
 
-/// Type-converted delay between sends of UDP keepalive frames.
+/// Type-converted default delay between sends of UDP keepalive frames.
 ///
 /// Passive monitoring of Discord itself shows that these fire every 5 seconds
 /// irrespective of outgoing UDP traffic.
 pub const UDP_KEEPALIVE_GAP: Duration = Duration::from_millis(UDP_KEEPALIVE_GAP_MS);
 
+/// Smallest delay between sends of UDP keepalive frames which the driver will use.
+pub const UDP_KEEPALIVE_GAP_MIN: Duration = Duration::from_secs(1);
+
+/// Number of consecutive voice packets which may fail to send (i.e., roughly
+/// one second of audio) before the driver treats its connection as broken
+/// and reconnects.
+pub const UDP_SEND_FAILURE_LIMIT: usize = AUDIO_FRAME_RATE;
+
 /// Opus silent frame, used to signal speech start and end (and prevent audio glitching).
 pub const SILENT_FRAME: [u8; 3] = [0xf8, 0xff, 0xfe];
 
+/// Fewest Opus silence frames which the driver will send when audio stops.
+pub const MIN_SILENCE_FRAMES_ON_STOP: u8 = 5;
+
 /// The one (and only) RTP version.
 pub const RTP_VERSION: u8 = 2;
 
@@ -0,0 +1,166 @@
+use audiopus::Bitrate;
+
+/// Fraction of packets lost (out of 256) above which a receiver report counts
+/// as lossy: roughly 5%.
+const LOSSY_FRACTION: u8 = 13;
+/// Fraction of packets lost (out of 256) below which a receiver report counts
+/// as clean: roughly 1%.
+const CLEAN_FRACTION: u8 = 3;
+/// Number of consecutive lossy reports needed before the bitrate is lowered.
+const LOSSY_REPORTS_TO_LOWER: u32 = 2;
+/// Number of consecutive clean reports needed before the bitrate is raised.
+const CLEAN_REPORTS_TO_RAISE: u32 = 5;
+
+/// Bounds on the Opus encoder bitrate chosen by the driver in response to
+/// packet loss reported by Discord's voice servers.
+///
+/// When sustained loss is seen in RTCP receiver reports, the bitrate is lowered
+/// by a quarter, down to `min`. Once the link has been clean for several reports,
+/// it is raised by an eighth, up to `max`.
+///
+/// See [`Config::adaptive_bitrate`].
+///
+/// [`Config::adaptive_bitrate`]: crate::Config::adaptive_bitrate
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct AdaptiveBitrate {
+    /// Lowest bitrate which the encoder may be lowered to, in bits per second.
+    pub min: i32,
+    /// Highest bitrate which the encoder may be raised to, in bits per second.
+    pub max: i32,
+}
+
+impl AdaptiveBitrate {
+    /// Creates a new set of bitrate bounds, in bits per second.
+    ///
+    /// The bounds are swapped if `min` is greater than `max`.
+    #[must_use]
+    pub fn new(min: i32, max: i32) -> Self {
+        Self {
+            min: min.min(max),
+            max: max.max(min),
+        }
+    }
+}
+
+impl Default for AdaptiveBitrate {
+    fn default() -> Self {
+        Self::new(24_000, 128_000)
+    }
+}
+
+/// Tracks recent packet loss to choose the encoder's bitrate within [`AdaptiveBitrate`] bounds.
+#[derive(Clone, Debug)]
+pub(crate) struct AdaptiveState {
+    bounds: AdaptiveBitrate,
+    current: i32,
+    lossy_reports: u32,
+    clean_reports: u32,
+}
+
+impl AdaptiveState {
+    pub(crate) fn new(bounds: AdaptiveBitrate, start: Bitrate) -> Self {
+        let current = match start {
+            Bitrate::BitsPerSecond(bps) => bps.clamp(bounds.min, bounds.max),
+            _ => bounds.max,
+        };
+
+        Self {
+            bounds,
+            current,
+            lossy_reports: 0,
+            clean_reports: 0,
+        }
+    }
+
+    pub(crate) fn bits_per_second(&self) -> i32 {
+        self.current
+    }
+
+    pub(crate) fn bitrate(&self) -> Bitrate {
+        Bitrate::BitsPerSecond(self.current)
+    }
+
+    /// Accounts for one receiver report's fraction of lost packets (out of 256).
+    ///
+    /// Returns the new bitrate if it should change.
+    pub(crate) fn report(&mut self, fraction_lost: u8) -> Option<i32> {
+        if fraction_lost >= LOSSY_FRACTION {
+            self.clean_reports = 0;
+            self.lossy_reports += 1;
+
+            if self.lossy_reports >= LOSSY_REPORTS_TO_LOWER {
+                self.lossy_reports = 0;
+                return self.change((self.current / 4 * 3).max(self.bounds.min));
+            }
+        } else if fraction_lost <= CLEAN_FRACTION {
+            self.lossy_reports = 0;
+            self.clean_reports += 1;
+
+            if self.clean_reports >= CLEAN_REPORTS_TO_RAISE {
+                self.clean_reports = 0;
+                return self.change((self.current + self.current / 8).min(self.bounds.max));
+            }
+        } else {
+            self.lossy_reports = 0;
+            self.clean_reports = 0;
+        }
+
+        None
+    }
+
+    fn change(&mut self, bitrate: i32) -> Option<i32> {
+        (bitrate != self.current).then(|| {
+            self.current = bitrate;
+            bitrate
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sustained_loss_lowers_bitrate_to_min() {
+        let bounds = AdaptiveBitrate::new(64_000, 128_000);
+        let mut state = AdaptiveState::new(bounds, Bitrate::BitsPerSecond(128_000));
+
+        // A single lossy report, or moderate loss, is not enough.
+        assert_eq!(state.report(40), None);
+        assert_eq!(state.report(8), None);
+        assert_eq!(state.report(40), None);
+
+        assert_eq!(state.report(40), Some(96_000));
+        assert_eq!(state.report(40), None);
+        assert_eq!(state.report(40), Some(72_000));
+        state.report(40);
+        assert_eq!(state.report(40), Some(64_000));
+        state.report(40);
+        assert_eq!(state.report(40), None);
+    }
+
+    #[test]
+    fn clean_reports_raise_bitrate_to_max() {
+        let bounds = AdaptiveBitrate::new(64_000, 80_000);
+        let state = AdaptiveState::new(bounds, Bitrate::Max);
+        assert_eq!(state.bitrate(), Bitrate::BitsPerSecond(80_000));
+
+        let mut state = AdaptiveState::new(bounds, Bitrate::BitsPerSecond(16_000));
+        assert_eq!(state.bitrate(), Bitrate::BitsPerSecond(64_000));
+
+        for _ in 0..4 {
+            assert_eq!(state.report(0), None);
+        }
+        assert_eq!(state.report(0), Some(72_000));
+
+        for _ in 0..4 {
+            state.report(0);
+        }
+        assert_eq!(state.report(0), Some(80_000));
+
+        for _ in 0..4 {
+            state.report(0);
+        }
+        assert_eq!(state.report(0), None);
+    }
+}
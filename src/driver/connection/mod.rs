@@ -1,4 +1,5 @@
 pub mod error;
+pub mod stats;
 
 #[cfg(feature = "receive")]
 use super::tasks::udp_rx;
@@ -25,17 +26,21 @@ use discortp::discord::{IpDiscoveryPacket, IpDiscoveryType, MutableIpDiscoveryPa
 use error::{Error, Result};
 use flume::Sender;
 use socket2::Socket;
-#[cfg(feature = "receive")]
-use std::sync::Arc;
-use std::{net::IpAddr, str::FromStr};
+use stats::ConnectionStatBlock;
+use std::{net::IpAddr, str::FromStr, sync::Arc};
 use tokio::{net::UdpSocket, spawn, time::timeout};
+#[cfg(feature = "receive")]
+use tracing::warn;
 use tracing::{debug, info, instrument};
 use url::Url;
 
 pub(crate) struct Connection {
     pub(crate) info: ConnectionInfo,
     pub(crate) ssrc: u32,
+    pub(crate) crypto_mode: CryptoMode,
     pub(crate) ws: Sender<WsMessage>,
+    idx: usize,
+    stats: Arc<ConnectionStatBlock>,
 }
 
 impl Connection {
@@ -44,11 +49,16 @@ impl Connection {
         interconnect: &Interconnect,
         config: &Config,
         idx: usize,
+        stats: &Arc<ConnectionStatBlock>,
     ) -> Result<Connection> {
         if let Some(t) = config.driver_timeout {
-            timeout(t, Connection::new_inner(info, interconnect, config, idx)).await?
+            timeout(
+                t,
+                Connection::new_inner(info, interconnect, config, idx, stats),
+            )
+            .await?
         } else {
-            Connection::new_inner(info, interconnect, config, idx).await
+            Connection::new_inner(info, interconnect, config, idx, stats).await
         }
     }
 
@@ -57,10 +67,11 @@ impl Connection {
         interconnect: &Interconnect,
         config: &Config,
         idx: usize,
+        stats: &Arc<ConnectionStatBlock>,
     ) -> Result<Connection> {
         let url = generate_url(&mut info.endpoint)?;
 
-        let mut client = WsStream::connect(url).await?;
+        let mut client = WsStream::connect(url, config.proxy.as_ref()).await?;
 
         let mut hello = None;
         let mut ready = None;
@@ -103,16 +114,17 @@ impl Connection {
         let ready =
             ready.expect("Ready packet expected in connection initialisation, but not found.");
 
-        if !has_valid_mode(&ready.modes, config.crypto_mode) {
-            return Err(Error::CryptoModeUnavailable);
-        }
+        let crypto_mode = select_mode(
+            &ready.modes,
+            std::iter::once(config.crypto_mode).chain(config.crypto_mode_fallbacks.iter().copied()),
+        )
+        .ok_or(Error::CryptoModeUnavailable)?;
 
         let udp = UdpSocket::bind("0.0.0.0:0").await?;
 
         // Optimisation for non-receive case: set rx buffer size to zero.
-        let udp = if cfg!(feature = "receive") {
-            udp
-        } else {
+        #[cfg(not(feature = "receive"))]
+        let udp = {
             let socket = Socket::from(udp.into_std()?);
 
             #[cfg(not(target_os = "macos"))]
@@ -121,6 +133,19 @@ impl Connection {
             UdpSocket::from_std(socket.into())?
         };
 
+        #[cfg(feature = "receive")]
+        let udp = if let Some(size) = config.udp_recv_buffer_size {
+            let socket = Socket::from(udp.into_std()?);
+
+            if let Err(e) = socket.set_recv_buffer_size(size) {
+                warn!("Failed to set UDP receive buffer size to {size}B: {e:?}");
+            }
+
+            UdpSocket::from_std(socket.into())?
+        } else {
+            udp
+        };
+
         udp.connect((ready.ip, ready.port)).await?;
 
         // Follow Discord's IP Discovery procedures, in case NAT tunnelling is needed.
@@ -168,14 +193,14 @@ impl Connection {
                     protocol: "udp".into(),
                     data: ProtocolData {
                         address,
-                        mode: config.crypto_mode.to_request_str().into(),
+                        mode: crypto_mode.to_request_str().into(),
                         port: view.get_port(),
                     },
                 }))
                 .await?;
         }
 
-        let cipher = init_cipher(&mut client, config.crypto_mode).await?;
+        let cipher = init_cipher(&mut client, crypto_mode).await?;
 
         info!("Connected to: {}", info.endpoint);
 
@@ -208,10 +233,11 @@ impl Connection {
             cipher: cipher.clone(),
             #[cfg(not(feature = "receive"))]
             cipher,
-            crypto_state: config.crypto_mode.into(),
+            crypto_state: crypto_mode.into(),
             #[cfg(feature = "receive")]
             udp_rx: udp_receiver_msg_tx,
             udp_tx,
+            stats: stats.clone(),
         };
 
         interconnect
@@ -224,6 +250,8 @@ impl Connection {
 
         #[cfg(feature = "receive")]
         let ssrc_tracker = Arc::new(SsrcTracker::default());
+        #[cfg(feature = "receive")]
+        stats.set_ssrc_tracker(ssrc_tracker.clone());
 
         let ws_state = AuxNetwork::new(
             ws_msg_rx,
@@ -232,6 +260,7 @@ impl Connection {
             hello.heartbeat_interval,
             idx,
             info.clone(),
+            stats.clone(),
             #[cfg(feature = "receive")]
             ssrc_tracker.clone(),
         );
@@ -243,34 +272,65 @@ impl Connection {
             interconnect.clone(),
             udp_receiver_msg_rx,
             cipher,
-            config.clone(),
+            Config {
+                crypto_mode,
+                ..config.clone()
+            },
             udp_rx,
             ssrc_tracker,
+            stats.clone(),
         ));
 
+        stats.mark_connected(idx);
+
         Ok(Connection {
             info,
             ssrc,
+            crypto_mode,
             ws: ws_msg_tx,
+            idx,
+            stats: stats.clone(),
         })
     }
 
     #[instrument(skip(self))]
     pub async fn reconnect(&mut self, config: &Config) -> Result<()> {
         if let Some(t) = config.driver_timeout {
-            timeout(t, self.reconnect_inner()).await?
+            timeout(t, self.reconnect_inner(config)).await?
         } else {
-            self.reconnect_inner().await
+            self.reconnect_inner(config).await
         }
     }
 
-    #[instrument(skip(self))]
-    pub async fn reconnect_inner(&mut self) -> Result<()> {
+    /// Move this connection onto new connection info from the same session and voice
+    /// server (e.g., after the bot is moved between channels), by resuming its voice
+    /// gateway session.
+    ///
+    /// The UDP session and mixer are left untouched, so audio continues without
+    /// interruption. On failure, this connection's info is left unchanged.
+    /// Callers must first check [`ConnectionInfo::can_resume_onto`].
+    pub async fn handover(&mut self, info: ConnectionInfo, config: &Config) -> Result<()> {
+        let old_info = std::mem::replace(&mut self.info, info);
+
+        match self.reconnect(config).await {
+            Ok(()) => {
+                self.ws.send(WsMessage::SetInfo(self.info.clone()))?;
+                Ok(())
+            },
+            Err(e) => {
+                self.info = old_info;
+                Err(e)
+            },
+        }
+    }
+
+    #[instrument(skip(self, config))]
+    pub async fn reconnect_inner(&mut self, config: &Config) -> Result<()> {
         let url = generate_url(&mut self.info.endpoint)?;
 
         // Thread may have died, we want to send to prompt a clean exit
         // (if at all possible) and then proceed as normal.
-        let mut client = WsStream::connect(url).await?;
+        let mut client = WsStream::connect(url, config.proxy.as_ref()).await?;
 
         client
             .send_json(&GatewayEvent::from(Resume {
@@ -321,16 +381,13 @@ impl Connection {
 
 impl Drop for Connection {
     fn drop(&mut self) {
+        self.stats.mark_disconnected(self.idx);
         info!("Disconnected");
     }
 }
 
 fn generate_url(endpoint: &mut String) -> Result<Url> {
-    if endpoint.ends_with(":80") {
-        let len = endpoint.len();
-
-        endpoint.truncate(len - 3);
-    }
+    crate::info::strip_default_port(endpoint);
 
     Url::parse(&format!("wss://{endpoint}/?v={VOICE_GATEWAY_VERSION}")).or(Err(Error::EndpointUrl))
 }
@@ -362,11 +419,42 @@ async fn init_cipher(client: &mut WsStream, mode: CryptoMode) -> Result<Cipher>
     }
 }
 
+/// Returns the first mode in `preference` which the server offers in `modes`.
 #[inline]
-fn has_valid_mode<T, It>(modes: It, mode: CryptoMode) -> bool
+fn select_mode<T>(
+    modes: &[T],
+    preference: impl IntoIterator<Item = CryptoMode>,
+) -> Option<CryptoMode>
 where
     T: for<'a> PartialEq<&'a str>,
-    It: IntoIterator<Item = T>,
 {
-    modes.into_iter().any(|s| s == mode.to_request_str())
+    preference
+        .into_iter()
+        .find(|mode| modes.iter().any(|s| *s == mode.to_request_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mode_selection_follows_preference_order() {
+        let modes = vec![
+            "xsalsa20_poly1305_lite".to_string(),
+            "xsalsa20_poly1305_suffix".into(),
+        ];
+
+        assert_eq!(select_mode(&modes, [CryptoMode::Normal]), None);
+        assert_eq!(
+            select_mode(
+                &modes,
+                [CryptoMode::Normal, CryptoMode::Suffix, CryptoMode::Lite]
+            ),
+            Some(CryptoMode::Suffix)
+        );
+        assert_eq!(
+            select_mode(&modes, [CryptoMode::Lite, CryptoMode::Suffix]),
+            Some(CryptoMode::Lite)
+        );
+    }
 }
@@ -0,0 +1,406 @@
+use crate::constants::TIMESTEP_LENGTH;
+#[cfg(feature = "receive")]
+use crate::{driver::tasks::message::SsrcTracker, model::id::UserId};
+#[cfg(feature = "receive")]
+use parking_lot::Mutex;
+#[cfg(feature = "receive")]
+use std::{collections::HashMap, sync::Arc};
+use std::{
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+const UNSET: u64 = u64::MAX;
+
+/// Weight given to each new packet in the rolling average payload size,
+/// covering roughly the last second of audio.
+const PAYLOAD_AVERAGE_WEIGHT: f64 = 1.0 / 50.0;
+
+/// A snapshot of the health of a [`Driver`]'s active voice connection.
+///
+/// [`Driver`]: crate::driver::Driver
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ConnectionStats {
+    /// Round-trip time of the most recently acknowledged voice gateway heartbeat.
+    ///
+    /// This is `None` until the first heartbeat of a connection is acknowledged.
+    pub heartbeat_latency: Option<Duration>,
+    /// The time at which the last UDP keepalive packet was successfully sent.
+    ///
+    /// This is `None` until the mixer sends its first keepalive on a connection.
+    pub last_udp_keepalive: Option<Instant>,
+    /// RTP counters of the most recent voice packet sent on this connection.
+    ///
+    /// This is `None` until the mixer sends its first voice packet on a connection.
+    pub last_sent_rtp: Option<RtpCounters>,
+    /// Number of voice packets sent on this connection.
+    pub packets_sent: u64,
+    /// Total size of all voice packets sent on this connection, in bytes.
+    ///
+    /// This includes RTP headers and encryption overhead, and so reflects
+    /// the bandwidth used by outgoing audio.
+    pub bytes_sent: u64,
+    /// Rolling average size of the Opus audio in each voice packet, rounded to the
+    /// nearest byte, over roughly the last second of sent packets.
+    ///
+    /// This covers both freshly encoded and passthrough audio, excluding RTP headers
+    /// and encryption overhead. This is `None` until the mixer prepares its first voice
+    /// packet on a connection.
+    pub average_payload_size: Option<u32>,
+}
+
+impl ConnectionStats {
+    /// Returns the recent bitrate of sent Opus audio, in bits per second.
+    ///
+    /// This is estimated from [`average_payload_size`], assuming one packet every 20ms,
+    /// and so can be compared against the bitrate set via [`Driver::set_bitrate`]
+    /// while audio is playing.
+    ///
+    /// [`average_payload_size`]: Self::average_payload_size
+    /// [`Driver::set_bitrate`]: crate::driver::Driver::set_bitrate
+    #[must_use]
+    pub fn average_bitrate(&self) -> Option<u32> {
+        let packets_per_sec =
+            (Duration::from_secs(1).as_millis() / TIMESTEP_LENGTH.as_millis()) as u32;
+        self.average_payload_size
+            .map(|size| size * 8 * packets_per_sec)
+    }
+}
+
+/// The RTP sequence number and timestamp carried by a sent voice packet.
+///
+/// Within a connection, each voice packet increments the sequence number by one
+/// and the timestamp by 960 (20ms at 48kHz), both wrapping on overflow. This holds
+/// across periods of silence and any rescheduling of the call onto another mixer
+/// thread, and so gaps between successive snapshots indicate a counter jump.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct RtpCounters {
+    /// RTP sequence number.
+    pub sequence: u16,
+    /// RTP timestamp, in samples.
+    pub timestamp: u32,
+}
+
+impl RtpCounters {
+    fn pack(self) -> u64 {
+        (u64::from(self.sequence) << 32) | u64::from(self.timestamp)
+    }
+
+    fn unpack(packed: u64) -> Self {
+        Self {
+            sequence: (packed >> 32) as u16,
+            timestamp: packed as u32,
+        }
+    }
+}
+
+/// Health of the audio stream received from a single SSRC.
+///
+/// Counters start from zero whenever songbird begins tracking an SSRC, i.e., on the
+/// first voice packet received from a user, or after they have been cleaned up
+/// following a disconnect or long silence.
+#[cfg(feature = "receive")]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct SsrcStats {
+    /// Number of frames which were missing from the playout buffer when due, and
+    /// so were reported as lost.
+    ///
+    /// When decoding audio, each lost frame is concealed by the Opus decoder.
+    /// This counts genuine network loss or excessive jitter, rather than bad audio.
+    pub lost_frames: u64,
+    /// Number of received frames which could not be decoded at all, e.g., due to
+    /// malformed or oversized Opus packets.
+    ///
+    /// These frames are reported as silent in their [`VoiceTick`]. This is always `0`
+    /// unless the driver is configured to decode audio.
+    ///
+    /// [`VoiceTick`]: crate::events::context_data::VoiceTick
+    pub decode_errors: u64,
+}
+
+/// Connection statistics shared between a `Driver` and its background tasks.
+///
+/// Values are stored atomically so that reading them never blocks on, or
+/// waits for, the driver's tasks.
+#[derive(Debug)]
+pub struct ConnectionStatBlock {
+    epoch: Instant,
+    /// Connection attempt index (plus one) of the live connection, or `0`.
+    live_conn: AtomicUsize,
+    heartbeat_ns: AtomicU64,
+    keepalive_ns: AtomicU64,
+    /// Packed [`RtpCounters`] of the last sent voice packet.
+    last_rtp: AtomicU64,
+    packets_sent: AtomicU64,
+    bytes_sent: AtomicU64,
+    /// Bits of an `f64` rolling average Opus payload size.
+    payload_average: AtomicU64,
+    dropped_events: AtomicU64,
+    dropped_packets: AtomicU64,
+    #[cfg(feature = "receive")]
+    ssrc_stats: Mutex<HashMap<u32, SsrcStats>>,
+    /// User-SSRC mappings learned by the live (or last) connection.
+    #[cfg(feature = "receive")]
+    ssrc_tracker: Mutex<Arc<SsrcTracker>>,
+}
+
+impl Default for ConnectionStatBlock {
+    fn default() -> Self {
+        Self {
+            epoch: Instant::now(),
+            live_conn: AtomicUsize::new(0),
+            heartbeat_ns: AtomicU64::new(UNSET),
+            keepalive_ns: AtomicU64::new(UNSET),
+            last_rtp: AtomicU64::new(UNSET),
+            packets_sent: AtomicU64::new(0),
+            bytes_sent: AtomicU64::new(0),
+            payload_average: AtomicU64::new(UNSET),
+            dropped_events: AtomicU64::new(0),
+            dropped_packets: AtomicU64::new(0),
+            #[cfg(feature = "receive")]
+            ssrc_stats: Mutex::default(),
+            #[cfg(feature = "receive")]
+            ssrc_tracker: Mutex::default(),
+        }
+    }
+}
+
+impl ConnectionStatBlock {
+    pub(crate) fn mark_connected(&self, idx: usize) {
+        self.heartbeat_ns.store(UNSET, Ordering::Relaxed);
+        self.keepalive_ns.store(UNSET, Ordering::Relaxed);
+        self.last_rtp.store(UNSET, Ordering::Relaxed);
+        self.packets_sent.store(0, Ordering::Relaxed);
+        self.bytes_sent.store(0, Ordering::Relaxed);
+        self.payload_average.store(UNSET, Ordering::Relaxed);
+        #[cfg(feature = "receive")]
+        self.ssrc_stats.lock().clear();
+        self.live_conn.store(idx.wrapping_add(1), Ordering::Release);
+    }
+
+    pub(crate) fn mark_disconnected(&self, idx: usize) {
+        // A newer connection may have replaced this one before it was dropped.
+        _ = self.live_conn.compare_exchange(
+            idx.wrapping_add(1),
+            0,
+            Ordering::AcqRel,
+            Ordering::Relaxed,
+        );
+    }
+
+    pub(crate) fn record_heartbeat(&self, latency: Duration) {
+        let ns = u64::try_from(latency.as_nanos()).unwrap_or(UNSET - 1);
+        self.heartbeat_ns.store(ns, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_keepalive(&self, at: Instant) {
+        let ns =
+            u64::try_from(at.saturating_duration_since(self.epoch).as_nanos()).unwrap_or(UNSET - 1);
+        self.keepalive_ns.store(ns, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_sent_rtp(&self, sequence: u16, timestamp: u32) {
+        let counters = RtpCounters {
+            sequence,
+            timestamp,
+        };
+        self.last_rtp.store(counters.pack(), Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_sent_packet(&self, len: usize) {
+        self.packets_sent.fetch_add(1, Ordering::Relaxed);
+        self.bytes_sent.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    /// Folds the Opus payload size of one voice packet into the rolling average.
+    ///
+    /// Only the mixer records payload sizes, so this need not be a single atomic update.
+    pub(crate) fn record_payload_size(&self, len: usize) {
+        let len = len as f64;
+        let average = match self.payload_average.load(Ordering::Relaxed) {
+            UNSET => len,
+            bits => {
+                let old = f64::from_bits(bits);
+                old + (len - old) * PAYLOAD_AVERAGE_WEIGHT
+            },
+        };
+        self.payload_average
+            .store(average.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Counts one core event dropped from a full event queue, returning the new total.
+    pub(crate) fn record_dropped_event(&self) -> u64 {
+        self.dropped_events.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    pub(crate) fn dropped_events(&self) -> u64 {
+        self.dropped_events.load(Ordering::Relaxed)
+    }
+
+    /// Counts one voice packet which could not be sent on the UDP socket.
+    pub(crate) fn record_dropped_packet(&self) {
+        self.dropped_packets.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn dropped_packets(&self) -> u64 {
+        self.dropped_packets.load(Ordering::Relaxed)
+    }
+
+    /// Replaces the stream health of each given SSRC.
+    #[cfg(feature = "receive")]
+    pub(crate) fn record_ssrc_stats(&self, stats: impl IntoIterator<Item = (u32, SsrcStats)>) {
+        self.ssrc_stats.lock().extend(stats);
+    }
+
+    /// Removes the stream health of any SSRC which is no longer tracked.
+    #[cfg(feature = "receive")]
+    pub(crate) fn retain_ssrc_stats(&self, mut keep: impl FnMut(u32) -> bool) {
+        self.ssrc_stats.lock().retain(|ssrc, _| keep(*ssrc));
+    }
+
+    #[cfg(feature = "receive")]
+    pub(crate) fn ssrc_stats(&self) -> HashMap<u32, SsrcStats> {
+        self.ssrc_stats.lock().clone()
+    }
+
+    #[cfg(feature = "receive")]
+    pub(crate) fn set_ssrc_tracker(&self, tracker: Arc<SsrcTracker>) {
+        *self.ssrc_tracker.lock() = tracker;
+    }
+
+    #[cfg(feature = "receive")]
+    pub(crate) fn ssrc_map(&self) -> HashMap<u32, UserId> {
+        self.ssrc_tracker
+            .lock()
+            .user_ssrc_map
+            .iter()
+            .map(|entry| (*entry.value(), *entry.key()))
+            .collect()
+    }
+
+    pub(crate) fn snapshot(&self) -> Option<ConnectionStats> {
+        if self.live_conn.load(Ordering::Acquire) == 0 {
+            return None;
+        }
+
+        let heartbeat_latency = match self.heartbeat_ns.load(Ordering::Relaxed) {
+            UNSET => None,
+            ns => Some(Duration::from_nanos(ns)),
+        };
+
+        let last_udp_keepalive = match self.keepalive_ns.load(Ordering::Relaxed) {
+            UNSET => None,
+            ns => Some(self.epoch + Duration::from_nanos(ns)),
+        };
+
+        let last_sent_rtp = match self.last_rtp.load(Ordering::Relaxed) {
+            UNSET => None,
+            packed => Some(RtpCounters::unpack(packed)),
+        };
+
+        let average_payload_size = match self.payload_average.load(Ordering::Relaxed) {
+            UNSET => None,
+            bits => Some(f64::from_bits(bits).round() as u32),
+        };
+
+        Some(ConnectionStats {
+            heartbeat_latency,
+            last_udp_keepalive,
+            last_sent_rtp,
+            packets_sent: self.packets_sent.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            average_payload_size,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_connection_does_not_clear_stats() {
+        let block = ConnectionStatBlock::default();
+        assert!(block.snapshot().is_none());
+
+        block.mark_connected(1);
+        block.record_heartbeat(Duration::from_millis(42));
+        block.mark_connected(2);
+        block.record_heartbeat(Duration::from_millis(40));
+        block.mark_disconnected(1);
+
+        let stats = block
+            .snapshot()
+            .expect("Connection 2 should still be live.");
+        assert_eq!(stats.heartbeat_latency, Some(Duration::from_millis(40)));
+        assert_eq!(stats.last_udp_keepalive, None);
+        assert_eq!(stats.last_sent_rtp, None);
+
+        block.mark_disconnected(2);
+        assert!(block.snapshot().is_none());
+    }
+
+    #[test]
+    fn sent_rtp_counters_survive_packing() {
+        let block = ConnectionStatBlock::default();
+        block.mark_connected(0);
+        block.record_sent_rtp(u16::MAX, u32::MAX);
+
+        let rtp = block.snapshot().unwrap().last_sent_rtp.unwrap();
+        assert_eq!(rtp.sequence, u16::MAX);
+        assert_eq!(rtp.timestamp, u32::MAX);
+
+        // Counters belong to a single connection.
+        block.mark_connected(1);
+        assert_eq!(block.snapshot().unwrap().last_sent_rtp, None);
+    }
+
+    #[test]
+    fn payload_sizes_are_averaged() {
+        let block = ConnectionStatBlock::default();
+        block.mark_connected(0);
+        assert_eq!(block.snapshot().unwrap().average_payload_size, None);
+
+        // 80B every 20ms: 32kbps.
+        block.record_payload_size(80);
+        let stats = block.snapshot().unwrap();
+        assert_eq!(stats.average_bitrate(), Some(32_000));
+
+        // The average moves gradually towards larger packets.
+        for _ in 0..50 {
+            block.record_payload_size(160);
+        }
+        let size = block.snapshot().unwrap().average_payload_size.unwrap();
+        assert!(size > 120 && size < 160);
+
+        block.record_sent_packet(100);
+        block.record_sent_packet(120);
+        let stats = block.snapshot().unwrap();
+        assert_eq!(stats.packets_sent, 2);
+        assert_eq!(stats.bytes_sent, 220);
+
+        block.mark_connected(1);
+        let stats = block.snapshot().unwrap();
+        assert_eq!(stats.packets_sent, 0);
+        assert_eq!(stats.average_payload_size, None);
+    }
+
+    #[test]
+    #[cfg(feature = "receive")]
+    fn ssrc_map_follows_latest_connection() {
+        let block = ConnectionStatBlock::default();
+        let user = UserId(1);
+
+        let old = Arc::new(SsrcTracker::default());
+        block.set_ssrc_tracker(old.clone());
+        old.user_ssrc_map.insert(user, 123);
+        assert_eq!(block.ssrc_map(), HashMap::from([(123, user)]));
+
+        block.set_ssrc_tracker(Arc::default());
+        old.user_ssrc_map.insert(user, 456);
+        assert!(block.ssrc_map().is_empty());
+    }
+}
@@ -9,7 +9,12 @@ pub enum DecodeMode {
     Pass,
     /// Decrypts the body of each received packet.
     ///
+    /// Reordered Opus frames for each user are made available every tick
+    /// via [`VoiceData::opus_frame`], without being decoded.
+    ///
     /// Small per-packet CPU use.
+    ///
+    /// [`VoiceData::opus_frame`]: crate::events::context_data::VoiceData::opus_frame
     Decrypt,
     /// Decrypts and decodes each received packet, correctly accounting for losses.
     ///
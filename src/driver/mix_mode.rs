@@ -1,47 +1,108 @@
 use audiopus::Channels;
-use symphonia_core::audio::Layout;
+use symphonia_core::audio::{AudioBuffer, Layout, SignalSpec};
 
-use crate::constants::{MONO_FRAME_SIZE, STEREO_FRAME_SIZE};
+use crate::constants::{MONO_FRAME_SIZE, SAMPLE_RATE_RAW, STEREO_FRAME_SIZE};
 
 /// Mixing behaviour for sent audio sources processed within the driver.
 ///
-/// This has no impact on Opus packet passthrough, which will pass packets
-/// irrespective of their channel count.
+/// [`Mono`] and [`Stereo`] have no impact on Opus packet passthrough, which will
+/// pass packets irrespective of their channel count. Modes which rearrange channels
+/// after mixing disable passthrough, as they must re-encode all audio.
+///
+/// [`Mono`]: Self::Mono
+/// [`Stereo`]: Self::Stereo
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum MixMode {
     /// Audio sources will be downmixed into a mono buffer.
     Mono,
     /// Audio sources will be mixed into into a stereo buffer, where mono sources
     /// will be duplicated into both channels.
     Stereo,
+    /// Audio sources will be mixed as in [`Stereo`], after which the left and
+    /// right channels are swapped.
+    ///
+    /// [`Stereo`]: Self::Stereo
+    StereoSwapped,
+    /// Audio sources will be mixed as in [`Stereo`], after which the left channel
+    /// is duplicated into both channels.
+    ///
+    /// This is useful for sources which only carry audio on their left channel.
+    ///
+    /// [`Stereo`]: Self::Stereo
+    DualMonoLeft,
+    /// Audio sources will be mixed as in [`Stereo`], after which the right channel
+    /// is duplicated into both channels.
+    ///
+    /// This is useful for sources which only carry audio on their right channel.
+    ///
+    /// [`Stereo`]: Self::Stereo
+    DualMonoRight,
 }
 
 impl MixMode {
     pub(crate) const fn to_opus(self) -> Channels {
         match self {
             Self::Mono => Channels::Mono,
-            Self::Stereo => Channels::Stereo,
+            _ => Channels::Stereo,
         }
     }
 
     pub(crate) const fn sample_count_in_frame(self) -> usize {
         match self {
             Self::Mono => MONO_FRAME_SIZE,
-            Self::Stereo => STEREO_FRAME_SIZE,
+            _ => STEREO_FRAME_SIZE,
         }
     }
 
     pub(crate) const fn channels(self) -> usize {
         match self {
             Self::Mono => 1,
-            Self::Stereo => 2,
+            _ => 2,
         }
     }
 
     pub(crate) const fn symph_layout(self) -> Layout {
         match self {
             Self::Mono => Layout::Mono,
-            Self::Stereo => Layout::Stereo,
+            _ => Layout::Stereo,
+        }
+    }
+
+    /// Returns the format of audio mixed by the driver in this mode.
+    ///
+    /// The sample rate is always fixed at 48kHz, as required by Discord's
+    /// Opus framing. Only the channel layout varies between modes.
+    #[must_use]
+    pub fn signal_spec(self) -> SignalSpec {
+        SignalSpec::new_with_layout(SAMPLE_RATE_RAW as u32, self.symph_layout())
+    }
+}
+
+impl MixMode {
+    /// Returns whether this mode rearranges channels after mixing.
+    ///
+    /// Opus passthrough must be disabled in these modes, as the
+    /// rearrangement cannot be applied to an encoded frame.
+    pub(crate) const fn remaps_channels(self) -> bool {
+        matches!(
+            self,
+            Self::StereoSwapped | Self::DualMonoLeft | Self::DualMonoRight
+        )
+    }
+
+    /// Rearranges the channels of a mixed (planar) audio buffer according to this mode.
+    pub(crate) fn remap_channels(self, buf: &mut AudioBuffer<f32>) {
+        let mut planes = buf.planes_mut();
+        let [left, right, ..] = planes.planes() else {
+            return;
+        };
+
+        match self {
+            Self::StereoSwapped => left.swap_with_slice(right),
+            Self::DualMonoLeft => right.copy_from_slice(left),
+            Self::DualMonoRight => left.copy_from_slice(right),
+            Self::Mono | Self::Stereo => {},
         }
     }
 }
@@ -57,3 +118,31 @@ impl From<MixMode> for Channels {
         val.to_opus()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use symphonia_core::audio::{Signal, SignalSpec};
+
+    fn remapped(mode: MixMode) -> (Vec<f32>, Vec<f32>) {
+        let mut buf = AudioBuffer::<f32>::new(4, SignalSpec::new_with_layout(48_000, mode.into()));
+        buf.render_reserved(Some(4));
+        buf.chan_mut(0).copy_from_slice(&[1.0, 2.0, 3.0, 4.0]);
+        buf.chan_mut(1).copy_from_slice(&[-1.0, -2.0, -3.0, -4.0]);
+
+        mode.remap_channels(&mut buf);
+
+        (buf.chan(0).to_vec(), buf.chan(1).to_vec())
+    }
+
+    #[test]
+    fn channel_remapping() {
+        let l = vec![1.0, 2.0, 3.0, 4.0];
+        let r = vec![-1.0, -2.0, -3.0, -4.0];
+
+        assert_eq!(remapped(MixMode::Stereo), (l.clone(), r.clone()));
+        assert_eq!(remapped(MixMode::StereoSwapped), (r.clone(), l.clone()));
+        assert_eq!(remapped(MixMode::DualMonoLeft), (l.clone(), l));
+        assert_eq!(remapped(MixMode::DualMonoRight), (r.clone(), r));
+    }
+}
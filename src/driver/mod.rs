@@ -11,11 +11,16 @@
 #[cfg(feature = "internals")]
 pub mod bench_internals;
 
+#[cfg(feature = "receive")]
+mod adaptive_bitrate;
 pub(crate) mod connection;
 mod crypto;
 #[cfg(feature = "receive")]
 mod decode_mode;
 mod mix_mode;
+mod preload;
+mod proxy;
+mod resample_quality;
 pub mod retry;
 mod scheduler;
 pub(crate) mod tasks;
@@ -24,12 +29,25 @@ pub(crate) mod test_config;
 #[cfg(any(test, feature = "internals"))]
 mod test_impls;
 
-use connection::error::{Error, Result};
+#[cfg(feature = "receive")]
+pub use adaptive_bitrate::AdaptiveBitrate;
+#[cfg(feature = "receive")]
+pub(crate) use adaptive_bitrate::AdaptiveState;
+#[cfg(feature = "receive")]
+pub use connection::stats::SsrcStats;
+pub use connection::stats::{ConnectionStats, RtpCounters};
+use connection::{
+    error::{Error, Result},
+    stats::ConnectionStatBlock,
+};
 pub use crypto::CryptoMode;
 pub(crate) use crypto::CryptoState;
 #[cfg(feature = "receive")]
 pub use decode_mode::DecodeMode;
 pub use mix_mode::MixMode;
+pub use preload::PreloadHandle;
+pub use proxy::Proxy;
+pub use resample_quality::ResampleQuality;
 pub use scheduler::{
     Config as SchedulerConfig,
     Error as SchedulerError,
@@ -43,11 +61,15 @@ pub use test_config::*;
 #[cfg(any(test, feature = "internals"))]
 pub use test_impls::*;
 
+#[cfg(feature = "receive")]
+use crate::model::id::UserId;
 #[cfg(feature = "builtin-queue")]
 use crate::tracks::TrackQueue;
 use crate::{
+    constants::DEFAULT_BITRATE,
     events::EventData,
     input::Input,
+    model::SpeakingState,
     tracks::{Track, TrackHandle},
     Config,
     ConnectionInfo,
@@ -56,14 +78,16 @@ use crate::{
 };
 /// Opus encoder bitrate settings.
 pub use audiopus::{self as opus, Bitrate};
+use bytes::Bytes;
 use core::{
     future::Future,
     pin::Pin,
     task::{Context, Poll},
 };
 use flume::{r#async::RecvFut, SendError, Sender};
-#[cfg(feature = "builtin-queue")]
-use std::time::Duration;
+#[cfg(feature = "receive")]
+use std::collections::HashMap;
+use std::{sync::Arc, time::Duration};
 use tasks::message::CoreMessage;
 use tracing::instrument;
 
@@ -77,6 +101,8 @@ pub struct Driver {
     config: Config,
     self_mute: bool,
     sender: Sender<CoreMessage>,
+    speaking_flags: SpeakingState,
+    stats: Arc<ConnectionStatBlock>,
     // Making this an Option is an abhorrent hack to coerce the borrow checker
     // into letting us have an &TrackQueue at the same time as an &mut Driver.
     // This is probably preferable to cloning the driver: Arc<...> should be nonzero
@@ -93,35 +119,80 @@ impl Driver {
     #[inline]
     #[must_use]
     pub fn new(config: Config) -> Self {
-        let sender = Self::start_inner(config.clone());
+        let stats = Arc::new(ConnectionStatBlock::default());
+        let sender = Self::start_inner(config.clone(), stats.clone());
 
         Driver {
             config,
             self_mute: false,
             sender,
+            speaking_flags: SpeakingState::MICROPHONE,
+            stats,
             #[cfg(feature = "builtin-queue")]
             queue: Some(TrackQueue::default()),
         }
     }
 
-    fn start_inner(config: Config) -> Sender<CoreMessage> {
+    /// Creates up to `count` idle Opus encoders for future drivers using `config`.
+    ///
+    /// Encoders are returned to a process-wide pool when a driver is dropped or
+    /// its encoder is rebuilt, and are reused by new drivers with the same mix mode
+    /// and Opus application. Pre-warming this pool before a burst of calls avoids
+    /// creating an encoder on each driver's mixer thread. At most 32 encoders are
+    /// kept for each set of encoder parameters.
+    ///
+    /// # Errors
+    ///
+    /// Fails if libopus could not create an encoder.
+    pub fn prewarm_encoders(config: &Config, count: usize) -> opus::Result<()> {
+        tasks::mixer::prewarm_encoders(DEFAULT_BITRATE, config, count)
+    }
+
+    fn start_inner(config: Config, stats: Arc<ConnectionStatBlock>) -> Sender<CoreMessage> {
         let (tx, rx) = flume::unbounded();
 
-        tasks::start(config, rx, tx.clone());
+        tasks::start(config, rx, tx.clone(), stats);
 
         tx
     }
 
     fn restart_inner(&mut self) {
-        self.sender = Self::start_inner(self.config.clone());
+        self.sender = Self::start_inner(self.config.clone(), self.stats.clone());
 
         self.mute(self.self_mute);
+        self.set_speaking_flags(self.speaking_flags);
     }
 
     /// Connects to a voice channel using the specified server.
     ///
     /// This method instantly contacts the driver tasks, and its
     /// does not need to be `await`ed to start the actual connection.
+    ///
+    /// Connection info can come from any gateway implementation, so the driver
+    /// can be used without serenity or twilight:
+    ///
+    /// ```rust,no_run
+    /// use songbird::{id::{ChannelId, GuildId, UserId}, Config, ConnectionInfo, Driver};
+    /// use std::num::NonZeroU64;
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let guild_id = GuildId(NonZeroU64::new(1).unwrap());
+    /// let user_id = UserId(NonZeroU64::new(2).unwrap());
+    ///
+    /// // Taken from this bot's `VOICE_STATE_UPDATE` and `VOICE_SERVER_UPDATE`
+    /// // gateway events, after sending a voice state update to join a channel.
+    /// let info = ConnectionInfo::builder(guild_id, user_id)
+    ///     .channel_id(ChannelId(NonZeroU64::new(3).unwrap()))
+    ///     .endpoint("c-lhr01.discord.media:443")
+    ///     .session_id("<session_id>")
+    ///     .token("<token>")
+    ///     .build()?;
+    ///
+    /// let mut driver = Driver::new(Config::default());
+    /// driver.connect(info).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     #[instrument(skip(self))]
     pub fn connect(&mut self, info: ConnectionInfo) -> Connect {
         let (tx, rx) = flume::bounded(1);
@@ -165,6 +236,136 @@ impl Driver {
         self.self_mute
     }
 
+    /// Sends the peak level of each transmitted 20ms frame to `meter`, for use
+    /// in level meters and similar UIs.
+    ///
+    /// Levels are measured after mixing and soft-clipping, on a linear scale where
+    /// `1.0` is full scale. Silent frames (including all frames while muted) are
+    /// reported as `0.0`. Levels are dropped if `meter` is full, and metering stops
+    /// if its receiver is dropped. This replaces any existing meter.
+    ///
+    /// While a meter is set, the mixer will not pass through Opus data directly
+    /// from a single track, as its level cannot be measured without decoding.
+    #[instrument(skip(self, meter))]
+    pub fn set_meter(&mut self, meter: Sender<f32>) {
+        self.send(CoreMessage::SetMeter(Some(meter)));
+    }
+
+    /// Stops sending frame levels to any meter set by [`Self::set_meter`].
+    #[instrument(skip(self))]
+    pub fn remove_meter(&mut self) {
+        self.send(CoreMessage::SetMeter(None));
+    }
+
+    /// Sends a copy of each Opus frame transmitted to Discord to `sink`, for
+    /// recording or rebroadcasting elsewhere.
+    ///
+    /// Frames contain raw Opus data without any RTP header or encryption, and
+    /// include the silence frames sent when audio stops. Frames are only produced
+    /// while connected, and are dropped if `sink` is full: a bounded channel
+    /// is recommended. The sink is removed once its receiver is dropped.
+    #[instrument(skip(self, sink))]
+    pub fn add_opus_sink(&mut self, sink: Sender<Bytes>) {
+        self.send(CoreMessage::AddOpusSink(sink));
+    }
+
+    /// Stops sending frames to all sinks added by [`Self::add_opus_sink`].
+    #[instrument(skip(self))]
+    pub fn remove_opus_sinks(&mut self) {
+        self.send(CoreMessage::RemoveOpusSinks);
+    }
+
+    /// Sets the speaking flags sent to Discord whenever this driver begins sending audio.
+    ///
+    /// For instance, [`SpeakingState::PRIORITY`] marks the bot as a priority speaker,
+    /// if it has permission to be one. If audio is currently being sent, then the new
+    /// flags are sent immediately. Flags should usually include
+    /// [`SpeakingState::MICROPHONE`], and an empty set is treated by Discord as
+    /// not speaking.
+    ///
+    /// Defaults to [`SpeakingState::MICROPHONE`].
+    #[instrument(skip(self))]
+    pub fn set_speaking_flags(&mut self, flags: SpeakingState) {
+        self.speaking_flags = flags;
+        self.send(CoreMessage::SetSpeakingFlags(flags));
+    }
+
+    /// Returns heartbeat latency, UDP keepalive, and RTP counter information about
+    /// the current voice connection.
+    ///
+    /// This does not contact the driver's tasks, and will not block. Returns `None`
+    /// if there is no active connection.
+    #[must_use]
+    pub fn connection_info(&self) -> Option<ConnectionStats> {
+        self.stats.snapshot()
+    }
+
+    /// Returns the health of the audio received from each SSRC on the current
+    /// voice connection, such as counts of lost and undecodable frames.
+    ///
+    /// SSRCs can be matched to users via [`CoreEvent::SpeakingStateUpdate`]. Like
+    /// [`Self::connection_info`], this does not contact the driver's tasks, and
+    /// values are updated on each [`VoiceTick`].
+    ///
+    /// [`CoreEvent::SpeakingStateUpdate`]: crate::events::CoreEvent::SpeakingStateUpdate
+    /// [`VoiceTick`]: crate::events::context_data::VoiceTick
+    #[cfg(feature = "receive")]
+    #[must_use]
+    pub fn ssrc_stats(&self) -> HashMap<u32, SsrcStats> {
+        self.stats.ssrc_stats()
+    }
+
+    /// Returns the SSRC used by each user in the current voice connection, as
+    /// learned from [`CoreEvent::SpeakingStateUpdate`] events.
+    ///
+    /// Users are removed once they leave the call, and all mappings are replaced
+    /// when the driver (re)connects. Like [`Self::ssrc_stats`], this does not
+    /// contact the driver's tasks.
+    ///
+    /// [`CoreEvent::SpeakingStateUpdate`]: crate::events::CoreEvent::SpeakingStateUpdate
+    #[cfg(feature = "receive")]
+    #[must_use]
+    pub fn ssrc_map(&self) -> HashMap<u32, UserId> {
+        self.stats.ssrc_map()
+    }
+
+    /// Returns the number of core events which have been dropped by this driver
+    /// because too many were awaiting delivery to event handlers.
+    ///
+    /// This is always `0` unless [`Config::event_queue_limit`] is set.
+    #[must_use]
+    pub fn dropped_events(&self) -> u64 {
+        self.stats.dropped_events()
+    }
+
+    /// Returns the number of voice packets which this driver has failed to send,
+    /// e.g., because the UDP socket's send buffer was full.
+    ///
+    /// Individual failures only drop the affected packet, but if
+    /// [`UDP_SEND_FAILURE_LIMIT`] packets in a row fail to send, then the driver
+    /// assumes its connection is broken and reconnects.
+    ///
+    /// [`UDP_SEND_FAILURE_LIMIT`]: crate::constants::UDP_SEND_FAILURE_LIMIT
+    #[must_use]
+    pub fn dropped_packets(&self) -> u64 {
+        self.stats.dropped_packets()
+    }
+
+    /// Begins readying an input in the background, without adding it to the mixer.
+    ///
+    /// This uses the codec and format registries of this driver's [`Config`]. Playing
+    /// the resulting input avoids the delay of creating and parsing it in the mixer,
+    /// which is otherwise incurred before the first audio packet can be sent.
+    ///
+    /// See [`PreloadHandle`] for more details.
+    #[must_use]
+    pub fn preload(&self, input: Input) -> PreloadHandle {
+        let codecs = self.config.codec_registry;
+        let probe = self.config.format_registry;
+
+        PreloadHandle::new(tokio::spawn(input.make_playable_async(codecs, probe)))
+    }
+
     /// Plays audio from an input, returning a handle for further control.
     #[instrument(skip(self, input))]
     pub fn play_input(&mut self, input: Input) -> TrackHandle {
@@ -206,6 +407,24 @@ impl Driver {
         handle
     }
 
+    /// Returns handles to every track currently held by the mixer.
+    ///
+    /// Tracks are listed in the order they are mixed. This matches the order they
+    /// were added, except that when a track is removed the most recently added track
+    /// takes its place. The result is a snapshot: handles to tracks which have
+    /// since ended will return [`ControlError::Finished`] when used.
+    ///
+    /// Returns an empty list if the driver's tasks could not be reached.
+    ///
+    /// [`ControlError::Finished`]: crate::tracks::ControlError::Finished
+    #[instrument(skip(self))]
+    pub async fn tracks(&mut self) -> Vec<TrackHandle> {
+        let (tx, rx) = flume::bounded(1);
+        self.send(CoreMessage::GetTracks(tx));
+
+        rx.recv_async().await.unwrap_or_default()
+    }
+
     /// Sets the bitrate for encoding Opus packets sent along
     /// the channel being managed.
     ///
@@ -218,12 +437,145 @@ impl Driver {
         self.send(CoreMessage::SetBitrate(bitrate));
     }
 
+    /// Resets the Opus decoder state held for a received SSRC, if one exists.
+    ///
+    /// This may be used to clear artifacts produced by a decoder after a
+    /// discontinuity in a user's audio, e.g., following a network blip.
+    /// Decoder state is otherwise reset automatically:
+    /// * whenever an SSRC's playout buffer fully drains and then begins to refill
+    ///   (i.e., after every pause in speech longer than the playout buffer),
+    /// * when an SSRC has sent no audio for [`Config::decode_state_timeout`], as its
+    ///   state is then removed entirely, and
+    /// * when the driver reconnects.
+    ///
+    /// Requires the `"receive"` feature.
+    #[cfg(feature = "receive")]
+    #[instrument(skip(self))]
+    pub fn reset_decoder(&mut self, ssrc: u32) {
+        self.send(CoreMessage::ResetDecoder(ssrc));
+    }
+
+    /// Pauses all playing tracks, without removing them from the driver.
+    ///
+    /// Unlike [`Self::mute`], this halts the playback position of each track,
+    /// and fires a [`TrackEvent::Pause`] for every track which was playing.
+    ///
+    /// [`TrackEvent::Pause`]: crate::events::TrackEvent::Pause
+    #[instrument(skip(self))]
+    pub fn pause_all(&mut self) {
+        self.send(CoreMessage::SetPaused(true));
+    }
+
+    /// Resumes all paused tracks, including those paused individually via
+    /// [`TrackHandle::pause`].
+    ///
+    /// This fires a [`TrackEvent::Play`] for every track which was paused.
+    ///
+    /// [`TrackEvent::Play`]: crate::events::TrackEvent::Play
+    #[instrument(skip(self))]
+    pub fn resume_all(&mut self) {
+        self.send(CoreMessage::SetPaused(false));
+    }
+
     /// Stops playing audio from all sources, if any are set.
     #[instrument(skip(self))]
     pub fn stop(&mut self) {
         self.send(CoreMessage::SetTrack(None));
     }
 
+    /// Fades out all audio over `fade`, then stops playing all sources.
+    ///
+    /// This avoids the audible click caused by stopping playback mid-waveform. The
+    /// returned future completes once the fade has finished and all tracks are removed.
+    /// Any track added during the fade is faded and stopped along with the rest.
+    ///
+    /// A zero `fade`, or a driver which is not connected, stops all tracks immediately
+    /// as in [`Self::stop`].
+    #[instrument(skip(self))]
+    pub async fn stop_with_fade(&mut self, fade: Duration) {
+        if fade.is_zero() {
+            self.stop();
+            return;
+        }
+
+        let (tx, rx) = flume::bounded(1);
+        self.send(CoreMessage::FadeOut(fade, tx));
+
+        // A closed channel means that the fade was superseded, or the driver restarted:
+        // either way, the tracks it was fading are gone.
+        _ = rx.recv_async().await;
+    }
+
+    /// Fades out and stops all audio as in [`Self::stop_with_fade`], and then leaves
+    /// the current voice channel.
+    #[instrument(skip(self))]
+    pub async fn leave_with_fade(&mut self, fade: Duration) {
+        self.stop_with_fade(fade).await;
+        self.leave();
+    }
+
+    /// Stops all audio and shuts down this driver, leaving the current voice channel.
+    ///
+    /// Unlike dropping a `Driver`, this waits for any trailing silence frames to be
+    /// sent after the last audio (see [`Config::silence_frames_on_stop`]), and for the
+    /// voice gateway to be told that this driver is no longer speaking. This
+    /// prevents the end of the audio being cut off, and other clients from seeing the
+    /// speaking indicator stuck on.
+    ///
+    /// [`Config::silence_frames_on_stop`]: crate::Config::silence_frames_on_stop
+    #[instrument(skip(self))]
+    pub async fn shutdown(mut self) {
+        let (tx, rx) = flume::bounded(1);
+        self.send(CoreMessage::Shutdown(tx));
+
+        // A closed channel means that the mixer has already exited, or the driver
+        // restarted: either way, there is no audio left to flush.
+        _ = rx.recv_async().await;
+
+        // Dropping `self` now tears down all background tasks.
+    }
+
+    /// Sets whether audio from `ssrc` is left out of [`VoiceTick::mixed_voice`].
+    ///
+    /// This updates [`Config::mix_received_muted_ssrcs`]. Audio from `ssrc` is
+    /// still delivered individually in [`VoiceTick::speaking`].
+    ///
+    /// [`VoiceTick::mixed_voice`]: crate::events::context_data::VoiceTick::mixed_voice
+    /// [`VoiceTick::speaking`]: crate::events::context_data::VoiceTick::speaking
+    #[cfg(feature = "receive")]
+    #[instrument(skip(self))]
+    pub fn set_received_ssrc_muted(&mut self, ssrc: u32, muted: bool) {
+        let mut config = self.config.clone();
+        if muted {
+            config.mix_received_muted_ssrcs.insert(ssrc);
+        } else {
+            config.mix_received_muted_ssrcs.remove(&ssrc);
+        }
+        self.set_config(config);
+    }
+
+    /// Sets whether audio from `user` is left out of [`VoiceTick::mixed_voice`].
+    ///
+    /// This updates [`Config::mix_received_muted_users`]. A user may be muted before
+    /// their SSRC is known, but their audio is only excluded once they have sent a
+    /// [`SpeakingStateUpdate`]. Their audio is still delivered individually in
+    /// [`VoiceTick::speaking`].
+    ///
+    /// [`VoiceTick::mixed_voice`]: crate::events::context_data::VoiceTick::mixed_voice
+    /// [`VoiceTick::speaking`]: crate::events::context_data::VoiceTick::speaking
+    /// [`SpeakingStateUpdate`]: crate::events::CoreEvent::SpeakingStateUpdate
+    #[cfg(feature = "receive")]
+    #[instrument(skip(self))]
+    pub fn set_received_user_muted(&mut self, user: UserId, muted: bool) {
+        let mut config = self.config.clone();
+        if muted {
+            config.mix_received_muted_users.insert(user);
+        } else {
+            config.mix_received_muted_users.remove(&user);
+        }
+        self.set_config(config);
+    }
+
     /// Sets the configuration for this driver (and parent `Call`, if applicable).
     #[instrument(skip(self))]
     pub fn set_config(&mut self, config: Config) {
@@ -244,6 +596,12 @@ impl Driver {
     /// so long as the bot is connected to a voice channel, and have no tracks.
     /// [`TrackEvent`]s will respond to all relevant tracks, giving some audio elements.
     ///
+    /// Adding several handlers for the same `event` does not replace earlier handlers:
+    /// all are called when the event fires, each following its own lifecycle.
+    ///
+    /// Handlers may be added before the driver first connects, and remain registered
+    /// across reconnections and channel moves until removed.
+    ///
     /// Users **must** ensure that no costly work or blocking occurs
     /// within the supplied function or closure. *Taking excess time could prevent
     /// timely sending of packets, causing audio glitches and delays*.
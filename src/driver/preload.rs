@@ -0,0 +1,54 @@
+use crate::input::{Input, MakePlayableError};
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use tokio::task::JoinHandle;
+
+/// Handle to an [`Input`] which is being readied in the background, created
+/// by [`Driver::preload`].
+///
+/// Once ready, the [`Input`] is fully parsed: passing it to [`Driver::play_input`]
+/// (or converting it into a [`Track`]) skips the mixer's readying stage, so that
+/// audio begins on the next tick.
+///
+/// Dropping this handle cancels any outstanding work. Blocking I/O which has
+/// already begun on a worker thread will run to completion, after which its
+/// result is discarded.
+///
+/// [`Driver::preload`]: super::Driver::preload
+/// [`Driver::play_input`]: super::Driver::play_input
+/// [`Track`]: crate::tracks::Track
+pub struct PreloadHandle {
+    task: JoinHandle<Result<Input, MakePlayableError>>,
+}
+
+impl PreloadHandle {
+    pub(crate) fn new(task: JoinHandle<Result<Input, MakePlayableError>>) -> Self {
+        Self { task }
+    }
+
+    /// Returns whether the background work has finished, successfully or not.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// Waits for the input to be made playable, returning it once ready.
+    pub async fn ready(mut self) -> Result<Input, MakePlayableError> {
+        (&mut self.task)
+            .await
+            .map_err(|_| MakePlayableError::Panicked)?
+    }
+}
+
+impl Debug for PreloadHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("PreloadHandle")
+            .field("finished", &self.is_finished())
+            .finish()
+    }
+}
+
+impl Drop for PreloadHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -0,0 +1,191 @@
+use std::io::{Error as IoError, ErrorKind, Result as IoResult};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+/// Largest HTTP `CONNECT` response header which will be read from a proxy.
+const MAX_HTTP_RESPONSE: usize = 8 * 1024;
+
+/// An outbound proxy used to reach Discord's voice gateway.
+///
+/// Only the websocket connection is proxied: voice data is still sent and received
+/// over a direct UDP socket, as neither proxy type can relay it. Networks which block
+/// direct UDP traffic to Discord cannot carry voice, even if a proxy is configured.
+///
+/// Proxy addresses are given as `host:port` strings. In both cases, the voice server's
+/// hostname is resolved by the proxy rather than locally.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Proxy {
+    /// An HTTP proxy supporting the `CONNECT` method.
+    Http(String),
+    /// A SOCKS5 proxy which does not require authentication.
+    Socks5(String),
+}
+
+impl Proxy {
+    /// Opens a TCP stream to `host:port` through this proxy.
+    pub(crate) async fn connect(&self, host: &str, port: u16) -> IoResult<TcpStream> {
+        match self {
+            Self::Http(addr) => {
+                let mut stream = TcpStream::connect(addr.as_str()).await?;
+                http_connect(&mut stream, host, port).await?;
+                Ok(stream)
+            },
+            Self::Socks5(addr) => {
+                let mut stream = TcpStream::connect(addr.as_str()).await?;
+                socks5_connect(&mut stream, host, port).await?;
+                Ok(stream)
+            },
+        }
+    }
+}
+
+fn proxy_error(msg: impl Into<String>) -> IoError {
+    IoError::new(ErrorKind::ConnectionRefused, msg.into())
+}
+
+async fn http_connect(stream: &mut TcpStream, host: &str, port: u16) -> IoResult<()> {
+    let request = format!("CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    // Read byte-by-byte so that no data belonging to the tunnelled
+    // stream is consumed along with the response header.
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_HTTP_RESPONSE {
+            return Err(proxy_error("HTTP proxy response header was too long"));
+        }
+        response.push(stream.read_u8().await?);
+    }
+
+    let status_line = response
+        .split(|b| *b == b'\n')
+        .next()
+        .map(String::from_utf8_lossy)
+        .unwrap_or_default();
+
+    match status_line.split_whitespace().nth(1) {
+        Some(code) if code.starts_with('2') => Ok(()),
+        _ => Err(proxy_error(format!(
+            "HTTP proxy refused CONNECT: {}",
+            status_line.trim_end()
+        ))),
+    }
+}
+
+async fn socks5_connect(stream: &mut TcpStream, host: &str, port: u16) -> IoResult<()> {
+    const VERSION: u8 = 5;
+    const NO_AUTH: u8 = 0;
+    const CMD_CONNECT: u8 = 1;
+    const ATYP_V4: u8 = 1;
+    const ATYP_DOMAIN: u8 = 3;
+    const ATYP_V6: u8 = 4;
+
+    let host_len = u8::try_from(host.len())
+        .map_err(|_| proxy_error("hostname is too long for a SOCKS5 request"))?;
+
+    stream.write_all(&[VERSION, 1, NO_AUTH]).await?;
+
+    let mut choice = [0u8; 2];
+    stream.read_exact(&mut choice).await?;
+    if choice != [VERSION, NO_AUTH] {
+        return Err(proxy_error(
+            "SOCKS5 proxy requires unsupported authentication",
+        ));
+    }
+
+    let mut request = vec![VERSION, CMD_CONNECT, 0, ATYP_DOMAIN, host_len];
+    request.extend_from_slice(host.as_bytes());
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await?;
+
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != VERSION {
+        return Err(proxy_error("SOCKS5 proxy sent a malformed reply"));
+    }
+    if reply[1] != 0 {
+        return Err(proxy_error(format!(
+            "SOCKS5 proxy refused CONNECT (code {})",
+            reply[1]
+        )));
+    }
+
+    // Skip the bound address and port, whose length depends on its type.
+    let addr_len = match reply[3] {
+        ATYP_V4 => 4,
+        ATYP_V6 => 16,
+        ATYP_DOMAIN => usize::from(stream.read_u8().await?),
+        _ => return Err(proxy_error("SOCKS5 proxy sent a malformed reply")),
+    };
+    let mut bound = vec![0u8; addr_len + 2];
+    stream.read_exact(&mut bound).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::serve_http;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn http_proxy_tunnels_after_connect() {
+        let (addr, requests) =
+            serve_http(|_| b"HTTP/1.1 200 Connection established\r\n\r\nhi".to_vec()).await;
+        let proxy = Proxy::Http(addr);
+
+        let mut stream = proxy.connect("voice.example", 443).await.unwrap();
+        let mut tunnelled = [0u8; 2];
+        stream.read_exact(&mut tunnelled).await.unwrap();
+
+        assert_eq!(&tunnelled, b"hi");
+        assert!(requests
+            .recv_async()
+            .await
+            .unwrap()
+            .starts_with("CONNECT voice.example:443 HTTP/1.1\r\n"));
+    }
+
+    #[tokio::test]
+    async fn http_proxy_rejection_is_an_error() {
+        let (addr, _requests) = serve_http(|_| b"HTTP/1.1 403 Forbidden\r\n\r\n".to_vec()).await;
+        let proxy = Proxy::Http(addr);
+
+        assert!(proxy.connect("voice.example", 443).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn socks5_proxy_requests_domain() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy = Proxy::Socks5(listener.local_addr().unwrap().to_string());
+
+        let server = tokio::spawn(async move {
+            let (mut conn, _) = listener.accept().await.unwrap();
+            let mut greeting = [0u8; 3];
+            conn.read_exact(&mut greeting).await.unwrap();
+            conn.write_all(&[5, 0]).await.unwrap();
+
+            let mut request = vec![0u8; 5 + "voice.example".len() + 2];
+            conn.read_exact(&mut request).await.unwrap();
+            conn.write_all(&[5, 0, 0, 1, 127, 0, 0, 1, 0, 80, b'h', b'i'])
+                .await
+                .unwrap();
+            (greeting, request)
+        });
+
+        let mut stream = proxy.connect("voice.example", 443).await.unwrap();
+        let mut tunnelled = [0u8; 2];
+        stream.read_exact(&mut tunnelled).await.unwrap();
+        assert_eq!(&tunnelled, b"hi");
+
+        let (greeting, request) = server.await.unwrap();
+        assert_eq!(greeting, [5, 1, 0]);
+        assert_eq!(&request[..5], &[5, 1, 0, 3, 13]);
+        assert_eq!(&request[5..18], b"voice.example");
+        assert_eq!(&request[18..], &443u16.to_be_bytes());
+    }
+}
@@ -0,0 +1,22 @@
+/// Quality of the resampler used to convert audio sources to Discord's 48kHz
+/// sample rate.
+///
+/// Sources which are already sampled at 48kHz are never resampled, whatever
+/// this setting. Higher quality resamplers remove more aliasing and high-frequency
+/// artefacts, at the cost of additional CPU time per mixed track.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ResampleQuality {
+    /// Linear interpolation between input samples, without anti-aliasing.
+    ///
+    /// This is the cheapest option, but introduces audible artefacts in
+    /// high-frequency content.
+    Fast,
+    /// FFT-based synchronous resampling.
+    #[default]
+    Balanced,
+    /// Windowed sinc interpolation with a long filter.
+    ///
+    /// This offers the flattest frequency response, but is the most expensive option.
+    High,
+}
@@ -290,10 +290,7 @@ impl Live {
         });
 
         match mixer {
-            None | Some((_, TickStyle::Timed)) => {
-                std::thread::sleep(self.deadline.saturating_duration_since(Instant::now()));
-                self.deadline += TIMESTEP_LENGTH;
-            },
+            None | Some((_, TickStyle::Timed)) => self.sleep_until_deadline(),
             Some((m, TickStyle::UntimedWithExecLimit(rx))) => {
                 if m.remaining_loops.is_none() {
                     if let Ok(new_val) = rx.recv() {
@@ -316,7 +313,14 @@ impl Live {
     #[inline(always)]
     #[allow(clippy::inline_always)]
     fn _march_deadline(&mut self) {
+        self.sleep_until_deadline();
+    }
+
+    #[inline]
+    fn sleep_until_deadline(&mut self) {
         std::thread::sleep(self.deadline.saturating_duration_since(Instant::now()));
+        self.stats
+            .store_deadline_slip(Instant::now().saturating_duration_since(self.deadline));
         self.deadline += TIMESTEP_LENGTH;
     }
 
@@ -820,4 +824,22 @@ mod test {
         assert!(sched.core.excess_buffer_cull_time.is_none());
         assert!(!sched.core.has_excess_blocks());
     }
+
+    #[test]
+    fn deadline_slip_is_recorded() {
+        let mut sched = MockScheduler::new(None);
+        let slip = Duration::from_millis(30);
+
+        // An overrun tick wakes up late by however long it overran.
+        sched.core.deadline = Instant::now().checked_sub(slip).unwrap();
+        sched.core.march_deadline();
+        assert!(sched.local.last_deadline_slip_ns() >= slip.as_nanos() as u64);
+
+        // A tick which sleeps until its deadline is barely late, but
+        // the worst slip seen so far is kept.
+        sched.core.deadline = Instant::now() + Duration::from_millis(5);
+        sched.core.march_deadline();
+        assert!(sched.local.last_deadline_slip_ns() < slip.as_nanos() as u64);
+        assert!(sched.local.max_deadline_slip_ns() >= slip.as_nanos() as u64);
+    }
 }
@@ -86,6 +86,8 @@ impl StatBlock {
 pub struct LiveStatBlock {
     live: AtomicU64,
     last_ns: AtomicU64,
+    last_slip_ns: AtomicU64,
+    max_slip_ns: AtomicU64,
 }
 
 impl LiveStatBlock {
@@ -119,6 +121,33 @@ impl LiveStatBlock {
         self.last_ns.load(Ordering::Relaxed)
     }
 
+    #[inline]
+    pub(crate) fn store_deadline_slip(&self, slip: Duration) {
+        let slip = slip.as_nanos() as u64;
+        self.last_slip_ns.store(slip, Ordering::Relaxed);
+        self.max_slip_ns.fetch_max(slip, Ordering::Relaxed);
+    }
+
+    /// Returns the number of nanoseconds by which this worker thread woke up
+    /// after its intended 20ms deadline in the last tick.
+    ///
+    /// Unlike [`Self::last_compute_cost_ns`], this captures delays caused by
+    /// OS scheduling and by overrunning a tick, rather than work done. Values
+    /// which stay above zero indicate that the host is too heavily loaded to
+    /// maintain a steady packet cadence.
+    #[inline]
+    pub fn last_deadline_slip_ns(&self) -> u64 {
+        self.last_slip_ns.load(Ordering::Relaxed)
+    }
+
+    /// Returns the largest deadline slippage seen by this worker thread, in nanoseconds.
+    ///
+    /// See [`Self::last_deadline_slip_ns`].
+    #[inline]
+    pub fn max_deadline_slip_ns(&self) -> u64 {
+        self.max_slip_ns.load(Ordering::Relaxed)
+    }
+
     #[inline]
     pub(crate) fn has_room(&self, strategy: &Mode, task: &ParkedMixer) -> bool {
         let task_room = strategy
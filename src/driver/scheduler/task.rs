@@ -145,6 +145,12 @@ impl ParkedMixer {
 
                 Ok(false)
             },
+            MixerMessage::SetSpeakingFlags(flags) => {
+                // Parked mixers aren't speaking, so these are only sent once promoted.
+                self.mixer.speaking_flags = flags;
+
+                Ok(false)
+            },
             MixerMessage::Ws(ws) => {
                 // Overridden so that we don't mistakenly tell Discord we're speaking.
                 self.mixer.ws = ws;
@@ -1,6 +1,7 @@
 use super::message::*;
 use flume::{Receiver, Sender};
-use tracing::{instrument, trace};
+use std::panic::{self, AssertUnwindSafe};
+use tracing::{error, instrument, trace};
 
 #[derive(Debug, Clone)]
 pub struct DisposalThread(Sender<DisposalMessage>);
@@ -33,7 +34,65 @@ impl DisposalThread {
 ///
 /// The mixer uses this to offload heavy and expensive drop operations
 /// to prevent deadline misses.
+///
+/// Tracks with a disposal hook have it run here, once their input has been dropped.
 #[instrument(skip(mix_rx))]
 fn runner(mix_rx: Receiver<DisposalMessage>) {
-    while mix_rx.recv().is_ok() {}
+    while let Ok(msg) = mix_rx.recv() {
+        if let DisposalMessage::Track(mut track, reason) = msg {
+            if let Some(hook) = track.disposal_hook.take() {
+                let state = track.state();
+                drop(track);
+
+                // One thread is shared by every driver made by a `Songbird` instance:
+                // a user's panic must not stop disposal for all of them.
+                if panic::catch_unwind(AssertUnwindSafe(|| hook(state, reason))).is_err() {
+                    error!("Track disposal hook panicked.");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::test_data::FILE_WAV_TARGET,
+        driver::tasks::mixer::InternalTrack,
+        input::File,
+        tracks::{RemovalReason, Track},
+    };
+    use std::time::Duration;
+
+    fn dispose_track(disposer: &DisposalThread, track: Track) {
+        let (_handle, context) = track.into_context();
+        let (track, ..) = InternalTrack::decompose_track(context);
+        disposer.dispose(DisposalMessage::Track(
+            Box::new(track),
+            RemovalReason::Finished,
+        ));
+    }
+
+    #[test]
+    fn panicking_hooks_do_not_stop_disposal() {
+        let disposer = DisposalThread::run();
+        let (tx, rx) = flume::unbounded();
+
+        dispose_track(
+            &disposer,
+            Track::from(File::new(FILE_WAV_TARGET)).on_dispose(|_, _| panic!("hook failed")),
+        );
+        dispose_track(
+            &disposer,
+            Track::from(File::new(FILE_WAV_TARGET)).on_dispose(move |_, reason| {
+                _ = tx.send(reason);
+            }),
+        );
+
+        assert_eq!(
+            rx.recv_timeout(Duration::from_secs(5)),
+            Ok(RemovalReason::Finished)
+        );
+    }
 }
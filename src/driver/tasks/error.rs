@@ -26,6 +26,8 @@ pub enum Error {
     InterconnectFailure(Recipient),
     Io(IoError),
     Opus(OpusError),
+    /// Too many consecutive voice packets failed to send on the UDP socket.
+    SendStalled,
     Ws(WsError),
 }
 
@@ -35,6 +37,7 @@ impl Error {
             Error::InterconnectFailure(Recipient::AuxNetwork) => true,
             #[cfg(feature = "receive")]
             Error::InterconnectFailure(Recipient::UdpRx) => true,
+            Error::SendStalled => true,
             _ => false,
         }
     }
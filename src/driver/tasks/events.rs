@@ -1,15 +1,18 @@
 use super::message::*;
 use crate::{
-    events::{EventStore, GlobalEvents, TrackEvent},
-    tracks::{ReadyState, TrackHandle, TrackState},
+    events::{CoreContext, EventStore, GlobalEvents, TrackEvent},
+    tracks::{LoopState, Passthrough, ReadyState, TrackHandle, TrackState},
 };
 use flume::Receiver;
+use std::sync::Arc;
 use tracing::{debug, info, instrument, trace};
 
-#[instrument(skip(evt_rx))]
-pub(crate) async fn runner(evt_rx: Receiver<EventMessage>) {
-    let mut global = GlobalEvents::default();
-
+#[instrument(skip(evt_rx, core_queue, global))]
+pub(crate) async fn runner(
+    evt_rx: Receiver<EventMessage>,
+    core_queue: Option<Arc<CoreEventQueue>>,
+    mut global: GlobalEvents,
+) {
     let mut events: Vec<EventStore> = vec![];
     let mut states: Vec<TrackState> = vec![];
     let mut handles: Vec<TrackHandle> = vec![];
@@ -32,22 +35,19 @@ pub(crate) async fn runner(evt_rx: Receiver<EventMessage>) {
 
                 event_store.add_event(data, state.position);
             },
-            EventMessage::FireCoreEvent(ctx) => {
-                let ctx = ctx.to_user_context();
-                let evt = ctx
-                    .to_core_event()
-                    .expect("Event thread was passed a non-core event in FireCoreEvent.");
-
-                trace!("Firing core event {:?}.", evt);
-
-                global.fire_core_event(evt, ctx).await;
-            },
+            EventMessage::FireCoreEvent(ctx) => fire_core_event(&mut global, ctx).await,
+            EventMessage::FireQueuedCoreEvents =>
+                if let Some(queue) = &core_queue {
+                    while let Some(ctx) = queue.pop() {
+                        fire_core_event(&mut global, ctx).await;
+                    }
+                },
             EventMessage::RemoveGlobalEvents => {
                 global.remove_handlers();
             },
             EventMessage::AddTrack(store, state, handle) => {
                 events.push(store);
-                states.push(state);
+                states.push(*state);
                 handles.push(handle);
 
                 info!("Event state for track {} added", events.len());
@@ -86,6 +86,9 @@ pub(crate) async fn runner(evt_rx: Receiver<EventMessage>) {
                         state.loops = loops;
                         if !user_set {
                             global.fire_track_event(TrackEvent::Loop, i);
+                            if loops == LoopState::Finite(0) {
+                                global.fire_track_event(TrackEvent::FinalLoop, i);
+                            }
                         }
                     },
                     TrackStateChange::Total(new) => {
@@ -93,18 +96,30 @@ pub(crate) async fn runner(evt_rx: Receiver<EventMessage>) {
                         *state = new;
                     },
                     TrackStateChange::Ready(ready_state) => {
-                        state.ready = ready_state;
-
-                        match ready_state {
-                            ReadyState::Playable => {
-                                global.fire_track_event(TrackEvent::Playable, i);
-                            },
-                            ReadyState::Preparing => {
-                                global.fire_track_event(TrackEvent::Preparing, i);
-                            },
-                            ReadyState::Uninitialised => {},
+                        let old_ready = std::mem::replace(&mut state.ready, ready_state);
+
+                        // Seeks and queued seeks may re-send an identical state:
+                        // only fire on true transitions.
+                        if old_ready != ready_state {
+                            match ready_state {
+                                ReadyState::Playable => {
+                                    global.fire_track_event(TrackEvent::Playable, i);
+                                },
+                                ReadyState::Preparing => {
+                                    global.fire_track_event(TrackEvent::Preparing, i);
+                                },
+                                ReadyState::Uninitialised => {},
+                            }
                         }
                     },
+                    TrackStateChange::Retrying => {
+                        global.fire_track_event(TrackEvent::Retrying, i);
+                    },
+                    TrackStateChange::PassthroughBlocked(block) => {
+                        state.passthrough = Passthrough::Blocked;
+                        state.passthrough_block = Some(block);
+                        global.fire_track_event(TrackEvent::PassthroughBlocked, i);
+                    },
                 }
             },
             EventMessage::RemoveAllTracks => {
@@ -131,9 +146,84 @@ pub(crate) async fn runner(evt_rx: Receiver<EventMessage>) {
                     }
                 }
             },
+            EventMessage::Handover(tx) => {
+                // Pending track events are not passed on, as track indices are
+                // meaningless to the next event thread: the mixer re-registers its tracks.
+                drop(tx.send((global.store, global.time)));
+                break;
+            },
             EventMessage::Poison => break,
         }
     }
 
     trace!("Event thread exited.");
 }
+
+async fn fire_core_event(global: &mut GlobalEvents, ctx: CoreContext) {
+    let ctx = ctx.to_user_context();
+    let evt = ctx
+        .to_core_event()
+        .expect("Event thread was passed a non-core event in FireCoreEvent.");
+
+    trace!("Firing core event {:?}.", evt);
+
+    global.fire_core_event(evt, ctx).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        driver::{
+            retry::{Retry, Strategy},
+            Driver,
+            Scheduler,
+            SchedulerConfig,
+        },
+        events::{context_data::DisconnectKind, CoreEvent, Event, EventContext, EventHandler},
+        Config,
+        ConnectionInfo,
+    };
+    use flume::Sender;
+    use std::{num::NonZeroU64, time::Duration};
+
+    struct NotifyDisconnect(Sender<DisconnectKind>);
+
+    #[async_trait::async_trait]
+    impl EventHandler for NotifyDisconnect {
+        async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+            if let EventContext::DriverDisconnect(data) = ctx {
+                _ = self.0.send(data.kind);
+            }
+            None
+        }
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn global_events_survive_interconnect_rebuild() {
+        let config = Config::default()
+            .driver_retry(Retry {
+                strategy: Strategy::Every(Duration::ZERO),
+                retry_limit: Some(0),
+            })
+            .scheduler(Scheduler::new(SchedulerConfig::default()));
+        let mut driver = Driver::new(config);
+        let (tx, rx) = flume::unbounded();
+        driver.add_global_event(CoreEvent::DriverDisconnect.into(), NotifyDisconnect(tx));
+
+        driver.send(CoreMessage::RebuildInterconnect);
+
+        // Nothing listens here, so the connection attempt fails immediately.
+        let info =
+            ConnectionInfo::builder(NonZeroU64::new(1).unwrap(), NonZeroU64::new(2).unwrap())
+                .endpoint("127.0.0.1:1")
+                .session_id("session")
+                .token("token")
+                .build()
+                .unwrap();
+        assert!(driver.connect(info).await.is_err());
+
+        assert_eq!(rx.recv_async().await.unwrap(), DisconnectKind::Connect);
+    }
+}
@@ -3,10 +3,13 @@
 use crate::{
     driver::{connection::error::Error, Bitrate, Config},
     events::{context_data::DisconnectReason, EventData},
+    model::SpeakingState,
     tracks::{Track, TrackCommand, TrackHandle},
     ConnectionInfo,
 };
+use bytes::Bytes;
 use flume::{Receiver, Sender};
+use std::time::Duration;
 
 pub enum CoreMessage {
     ConnectWithResult(ConnectionInfo, Sender<Result<(), Error>>),
@@ -20,6 +23,16 @@ pub enum CoreMessage {
     RemoveGlobalEvents,
     SetConfig(Config),
     Mute(bool),
+    SetMeter(Option<Sender<f32>>),
+    AddOpusSink(Sender<Bytes>),
+    RemoveOpusSinks,
+    SetSpeakingFlags(SpeakingState),
+    SetPaused(bool),
+    GetTracks(Sender<Vec<TrackHandle>>),
+    FadeOut(Duration, Sender<()>),
+    Shutdown(Sender<()>),
+    #[cfg(feature = "receive")]
+    ResetDecoder(u32),
     Reconnect,
     FullReconnect,
     RebuildInterconnect,
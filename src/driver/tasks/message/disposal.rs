@@ -1,8 +1,12 @@
 #![allow(missing_docs)]
 
-use crate::{driver::tasks::mixer::InternalTrack, tracks::TrackHandle};
+use crate::{
+    driver::tasks::mixer::{state::InputState, InternalTrack},
+    tracks::{RemovalReason, TrackHandle},
+};
 
 pub enum DisposalMessage {
-    Track(Box<InternalTrack>),
+    Track(Box<InternalTrack>, RemovalReason),
     Handle(TrackHandle),
+    Input(Box<InputState>),
 }
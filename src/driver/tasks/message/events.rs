@@ -1,10 +1,21 @@
 #![allow(missing_docs)]
 
 use crate::{
+    driver::connection::stats::ConnectionStatBlock,
     events::{CoreContext, EventData, EventStore},
-    tracks::{LoopState, PlayMode, ReadyState, TrackHandle, TrackState},
+    tracks::{LoopState, PassthroughBlock, PlayMode, ReadyState, TrackHandle, TrackState},
 };
-use std::time::Duration;
+use flume::{SendError, Sender};
+use parking_lot::Mutex;
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tracing::warn;
 
 pub enum EventMessage {
     // Event related.
@@ -12,13 +23,17 @@ pub enum EventMessage {
     AddGlobalEvent(EventData),
     AddTrackEvent(usize, EventData),
     FireCoreEvent(CoreContext),
+    /// Core events are waiting in a bounded [`CoreEventQueue`].
+    FireQueuedCoreEvents,
     RemoveGlobalEvents,
 
-    AddTrack(EventStore, TrackState, TrackHandle),
+    AddTrack(EventStore, Box<TrackState>, TrackHandle),
     ChangeState(usize, TrackStateChange),
     RemoveAllTracks,
     Tick,
 
+    /// Stop, passing global event handlers (and their clock) on to a replacement event thread.
+    Handover(Sender<(EventStore, Duration)>),
     Poison,
 }
 
@@ -31,4 +46,134 @@ pub enum TrackStateChange {
     Loops(LoopState, bool),
     Total(TrackState),
     Ready(ReadyState),
+    Retrying,
+    PassthroughBlocked(PassthroughBlock),
+}
+
+/// Sending half of the event thread's channel.
+///
+/// When the driver is configured with an event queue limit, core events are held in a
+/// [`CoreEventQueue`] rather than the channel itself, so that the oldest can be dropped.
+#[derive(Clone, Debug)]
+pub struct EventSender {
+    tx: Sender<EventMessage>,
+    core_queue: Option<Arc<CoreEventQueue>>,
+}
+
+impl EventSender {
+    pub fn new(tx: Sender<EventMessage>, core_queue: Option<Arc<CoreEventQueue>>) -> Self {
+        Self { tx, core_queue }
+    }
+
+    pub fn send(&self, msg: EventMessage) -> Result<(), SendError<EventMessage>> {
+        match (msg, &self.core_queue) {
+            (EventMessage::FireCoreEvent(ctx), Some(queue)) =>
+                if queue.push(ctx) {
+                    self.tx.send(EventMessage::FireQueuedCoreEvents)
+                } else {
+                    Ok(())
+                },
+            (msg, _) => self.tx.send(msg),
+        }
+    }
+
+    pub fn core_queue(&self) -> Option<Arc<CoreEventQueue>> {
+        self.core_queue.clone()
+    }
+}
+
+/// Bounded queue of core events awaiting delivery, which drops the oldest event when full.
+#[derive(Debug)]
+pub struct CoreEventQueue {
+    events: Mutex<VecDeque<CoreContext>>,
+    limit: usize,
+    /// Set once an event has been dropped, until the queue is next emptied.
+    overflowing: AtomicBool,
+    stats: Arc<ConnectionStatBlock>,
+}
+
+impl CoreEventQueue {
+    pub fn new(limit: usize, stats: Arc<ConnectionStatBlock>) -> Self {
+        Self {
+            events: Mutex::new(VecDeque::with_capacity(limit)),
+            limit,
+            overflowing: AtomicBool::new(false),
+            stats,
+        }
+    }
+
+    /// Adds an event to the queue, returning whether the event thread must be woken.
+    fn push(&self, ctx: CoreContext) -> bool {
+        let mut events = self.events.lock();
+        let was_empty = events.is_empty();
+
+        if events.len() >= self.limit {
+            events.pop_front();
+            let dropped = self.stats.record_dropped_event();
+
+            // Log once per overflow, rather than for every dropped event.
+            if !self.overflowing.swap(true, Ordering::Relaxed) {
+                warn!(
+                    "Event handlers are too slow: dropped a core event ({dropped} dropped in total)."
+                );
+            }
+        }
+
+        events.push_back(ctx);
+
+        was_empty
+    }
+
+    pub fn pop(&self) -> Option<CoreContext> {
+        let out = self.events.lock().pop_front();
+        if out.is_none() {
+            self.overflowing.store(false, Ordering::Relaxed);
+        }
+        out
+    }
+
+    pub fn clear(&self) {
+        self.events.lock().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{id::UserId, payload::ClientDisconnect};
+
+    fn disconnect(id: u64) -> EventMessage {
+        EventMessage::FireCoreEvent(CoreContext::ClientDisconnect(ClientDisconnect {
+            user_id: UserId(id),
+        }))
+    }
+
+    #[test]
+    fn full_core_queue_drops_oldest() {
+        let stats = Arc::new(ConnectionStatBlock::default());
+        let queue = Arc::new(CoreEventQueue::new(2, stats.clone()));
+        let (tx, rx) = flume::unbounded();
+        let sender = EventSender::new(tx, Some(queue.clone()));
+
+        for i in 0..5 {
+            sender.send(disconnect(i)).unwrap();
+        }
+
+        // Only the first event needs to wake the event thread.
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(EventMessage::FireQueuedCoreEvents)
+        ));
+        assert!(rx.is_empty());
+        assert_eq!(stats.dropped_events(), 3);
+
+        for i in 3..5 {
+            assert!(matches!(
+                queue.pop(),
+                Some(CoreContext::ClientDisconnect(ClientDisconnect { user_id: UserId(id) }))
+                    if id == i
+            ));
+        }
+        assert!(queue.pop().is_none());
+    }
 }
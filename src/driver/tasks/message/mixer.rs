@@ -5,12 +5,17 @@ use super::UdpRxMessage;
 use super::{Interconnect, TrackContext, WsMessage};
 
 use crate::{
-    driver::{Bitrate, Config, CryptoState},
+    driver::{connection::stats::ConnectionStatBlock, Bitrate, Config, CryptoState},
     input::{AudioStreamError, Compose, Parsed},
+    model::SpeakingState,
+    tracks::TrackHandle,
 };
+use bytes::Bytes;
 use crypto_secretbox::XSalsa20Poly1305 as Cipher;
+#[cfg(feature = "receive")]
+use discortp::rtcp::report::ReportBlock;
 use flume::Sender;
-use std::{net::UdpSocket, sync::Arc};
+use std::{net::UdpSocket, sync::Arc, time::Duration};
 use symphonia_core::{errors::Error as SymphoniaError, formats::SeekedTo};
 
 pub struct MixerConnection {
@@ -19,6 +24,7 @@ pub struct MixerConnection {
     #[cfg(feature = "receive")]
     pub udp_rx: Sender<UdpRxMessage>,
     pub udp_tx: UdpSocket,
+    pub stats: Arc<ConnectionStatBlock>,
 }
 
 pub enum MixerMessage {
@@ -28,6 +34,14 @@ pub enum MixerMessage {
     SetBitrate(Bitrate),
     SetConfig(Config),
     SetMute(bool),
+    SetMeter(Option<Sender<f32>>),
+    AddOpusSink(Sender<Bytes>),
+    RemoveOpusSinks,
+    SetSpeakingFlags(SpeakingState),
+    SetPaused(bool),
+    GetTracks(Sender<Vec<TrackHandle>>),
+    FadeOut(Duration, Sender<()>),
+    Shutdown(Sender<()>),
 
     SetConn(MixerConnection, u32),
     Ws(Option<Sender<WsMessage>>),
@@ -36,6 +50,11 @@ pub enum MixerMessage {
     ReplaceInterconnect(Interconnect),
     RebuildEncoder,
 
+    #[cfg(feature = "receive")]
+    ReceiverReport(Vec<ReportBlock>),
+    #[cfg(feature = "receive")]
+    ResetDecoder(u32),
+
     Poison,
 }
 
@@ -49,7 +68,7 @@ impl MixerMessage {
 }
 
 pub enum MixerInputResultMessage {
-    CreateErr(Arc<AudioStreamError>),
+    CreateErr(Arc<AudioStreamError>, Option<Box<dyn Compose>>),
     ParseErr(Arc<SymphoniaError>),
     Seek(
         Parsed,
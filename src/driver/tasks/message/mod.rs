@@ -12,6 +12,7 @@ mod ws;
 pub use self::udp_rx::*;
 pub use self::{core::*, disposal::*, events::*, mixer::*, ws::*};
 
+use crate::events::GlobalEvents;
 use flume::Sender;
 use tokio::spawn;
 use tracing::trace;
@@ -19,7 +20,7 @@ use tracing::trace;
 #[derive(Clone, Debug)]
 pub struct Interconnect {
     pub core: Sender<CoreMessage>,
-    pub events: Sender<EventMessage>,
+    pub events: EventSender,
     pub mixer: Sender<MixerMessage>,
 }
 
@@ -34,15 +35,33 @@ impl Interconnect {
     }
 
     pub fn restart_volatile_internals(&mut self) {
-        self.poison();
+        // Global handlers are kept if the old event processor is still alive to
+        // hand them over: they were registered by the user, not the connection.
+        let (global_tx, global_rx) = flume::bounded(1);
+        drop(self.events.send(EventMessage::Handover(global_tx)));
 
         let (evt_tx, evt_rx) = flume::unbounded();
 
-        self.events = evt_tx;
+        // Queued events will not be announced to the new event processor.
+        let core_queue = self.events.core_queue();
+        if let Some(queue) = &core_queue {
+            queue.clear();
+        }
+
+        self.events = EventSender::new(evt_tx, core_queue.clone());
 
         spawn(async move {
+            let global = global_rx
+                .recv_async()
+                .await
+                .map(|(store, time)| GlobalEvents {
+                    store,
+                    time,
+                    ..Default::default()
+                })
+                .unwrap_or_default();
             trace!("Event processor restarted.");
-            super::events::runner(evt_rx).await;
+            super::events::runner(evt_rx, core_queue, global).await;
             trace!("Event processor finished.");
         });
 
@@ -2,12 +2,19 @@
 
 use super::Interconnect;
 use crate::driver::Config;
+#[cfg(test)]
+use bytes::BytesMut;
 use dashmap::{DashMap, DashSet};
 use serenity_voice_model::id::UserId;
 
 pub enum UdpRxMessage {
-    SetConfig(Config),
+    SetConfig(Box<Config>),
     ReplaceInterconnect(Interconnect),
+    /// Reset the Opus decoder state for the given SSRC.
+    ResetDecoder(u32),
+    /// Process a packet as though it had been received on the UDP socket.
+    #[cfg(test)]
+    InjectPacket(BytesMut),
 }
 
 #[derive(Debug, Default)]
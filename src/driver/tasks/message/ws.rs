@@ -1,11 +1,12 @@
 #![allow(missing_docs)]
 
 use super::Interconnect;
-use crate::ws::WsStream;
+use crate::{model::SpeakingState, ws::WsStream, ConnectionInfo};
 
 pub enum WsMessage {
     Ws(Box<WsStream>),
     ReplaceInterconnect(Interconnect),
     SetKeepalive(f64),
-    Speaking(bool),
+    Speaking(SpeakingState),
+    SetInfo(ConnectionInfo),
 }
@@ -0,0 +1,182 @@
+use super::configure_encoder;
+
+use crate::{constants::SAMPLE_RATE, Config};
+use audiopus::{
+    coder::{Encoder as OpusEncoder, GenericCtl},
+    Application,
+    Bitrate,
+    Channels,
+    Result as OpusResult,
+};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    ops::{Deref, DerefMut},
+};
+
+/// Maximum number of idle encoders kept for reuse with any one set of parameters.
+const MAX_POOLED_ENCODERS: usize = 32;
+
+type EncoderKey = (Bitrate, Channels, Application);
+
+/// Idle encoders, each of which has been returned to its default settings.
+static ENCODER_POOL: Lazy<Mutex<HashMap<EncoderKey, Vec<IdleEncoder>>>> = Lazy::new(Mutex::default);
+
+struct IdleEncoder {
+    encoder: OpusEncoder,
+    base_complexity: u8,
+}
+
+impl IdleEncoder {
+    fn new(key: EncoderKey) -> OpusResult<Self> {
+        let (bitrate, channels, application) = key;
+        let mut encoder = OpusEncoder::new(SAMPLE_RATE, channels, application)?;
+        encoder.set_bitrate(bitrate)?;
+        let base_complexity = encoder.complexity()?;
+
+        Ok(Self {
+            encoder,
+            base_complexity,
+        })
+    }
+
+    /// Clears all state and options which a [`Config`] may have applied.
+    fn reset(&mut self, bitrate: Bitrate) -> OpusResult<()> {
+        self.encoder.reset_state()?;
+        self.encoder.set_bitrate(bitrate)?;
+        self.encoder.set_inband_fec(false)?;
+        self.encoder.set_dtx(false)?;
+        self.encoder.set_complexity(self.base_complexity)
+    }
+}
+
+/// An Opus encoder which is returned to a process-wide pool when dropped.
+///
+/// Reusing encoders avoids allocating and initialising a new encoder each time
+/// a call is created or its encoder is rebuilt, which adds up for deployments
+/// with many short-lived calls.
+pub struct PooledEncoder {
+    key: EncoderKey,
+    inner: Option<IdleEncoder>,
+}
+
+impl PooledEncoder {
+    /// Takes an idle encoder from the pool (or creates one), applying the
+    /// settings in `config`.
+    pub fn new(bitrate: Bitrate, config: &Config) -> OpusResult<Self> {
+        let key = encoder_key(bitrate, config);
+        let pooled = ENCODER_POOL.lock().get_mut(&key).and_then(Vec::pop);
+        let mut inner = match pooled {
+            Some(inner) => inner,
+            None => IdleEncoder::new(key)?,
+        };
+        configure_encoder(&mut inner.encoder, config)?;
+
+        Ok(Self {
+            key,
+            inner: Some(inner),
+        })
+    }
+}
+
+impl Deref for PooledEncoder {
+    type Target = OpusEncoder;
+
+    fn deref(&self) -> &Self::Target {
+        &self
+            .inner
+            .as_ref()
+            .expect("Encoder only taken on drop.")
+            .encoder
+    }
+}
+
+impl DerefMut for PooledEncoder {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self
+            .inner
+            .as_mut()
+            .expect("Encoder only taken on drop.")
+            .encoder
+    }
+}
+
+impl Drop for PooledEncoder {
+    fn drop(&mut self) {
+        let Some(mut inner) = self.inner.take() else {
+            return;
+        };
+
+        if inner.reset(self.key.0).is_ok() {
+            let mut pool = ENCODER_POOL.lock();
+            let idle = pool.entry(self.key).or_default();
+            if idle.len() < MAX_POOLED_ENCODERS {
+                idle.push(inner);
+            }
+        }
+    }
+}
+
+fn encoder_key(bitrate: Bitrate, config: &Config) -> EncoderKey {
+    (bitrate, config.mix_mode.to_opus(), config.opus_application)
+}
+
+/// Creates idle encoders for new calls using `config`, until `count` are pooled
+/// (or the pool is full).
+pub fn prewarm_encoders(bitrate: Bitrate, config: &Config, count: usize) -> OpusResult<()> {
+    let key = encoder_key(bitrate, config);
+    let target = count.min(MAX_POOLED_ENCODERS);
+
+    loop {
+        if ENCODER_POOL.lock().get(&key).map_or(0, Vec::len) >= target {
+            return Ok(());
+        }
+
+        // Encoders are created outside the lock to avoid stalling mixers.
+        let inner = IdleEncoder::new(key)?;
+        let mut pool = ENCODER_POOL.lock();
+        let idle = pool.entry(key).or_default();
+        if idle.len() >= target {
+            return Ok(());
+        }
+        idle.push(inner);
+    }
+}
+
+/// Returns the number of idle encoders pooled for `config`.
+#[cfg(test)]
+pub fn idle_encoders(bitrate: Bitrate, config: &Config) -> usize {
+    ENCODER_POOL
+        .lock()
+        .get(&encoder_key(bitrate, config))
+        .map_or(0, Vec::len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoders_are_reset_and_reused() {
+        // A bitrate unused by other tests keeps this pool entry to ourselves.
+        let bitrate = Bitrate::BitsPerSecond(77_000);
+        let config = Config::default().opus_fec(true).opus_complexity(Some(2));
+
+        prewarm_encoders(bitrate, &config, 2).unwrap();
+        assert_eq!(idle_encoders(bitrate, &config), 2);
+
+        let encoder = PooledEncoder::new(bitrate, &config).unwrap();
+        assert_eq!(idle_encoders(bitrate, &config), 1);
+        assert!(encoder.inband_fec().unwrap());
+        assert_eq!(encoder.complexity().unwrap(), 2);
+
+        drop(encoder);
+        assert_eq!(idle_encoders(bitrate, &config), 2);
+
+        let encoder = PooledEncoder::new(bitrate, &Config::default()).unwrap();
+        assert!(!encoder.inband_fec().unwrap());
+        assert_ne!(encoder.complexity().unwrap(), 2);
+        assert_eq!(encoder.bitrate().unwrap(), bitrate);
+    }
+}
@@ -1,4 +1,6 @@
 use super::*;
+use symphonia_core::errors::Error as SymphoniaError;
+use tracing::warn;
 
 /// Mix a track's audio stream into either the shared mixing buffer, or directly into the output
 /// packet ("passthrough") when possible.
@@ -44,6 +46,8 @@ pub fn mix_symph_indiv(
     local_state: &mut DecodeState,
     // volume of this source
     volume: f32,
+    // resampler to build if this source is not at 48kHz
+    resample_quality: ResampleQuality,
     // window into the output UDP buffer to copy opus frames into.
     // This is set to `Some` IF passthrough is possible (i.e., one live source).
     mut opus_slot: Option<&mut [u8]>,
@@ -51,7 +55,7 @@ pub fn mix_symph_indiv(
     let mut samples_written = 0;
     let mut resample_in_progress = false;
     let mut track_status = MixStatus::Live;
-    let codec_type = input.decoder.codec_params().codec;
+    let mut skipped_packets = 0;
 
     resample_scratch.clear();
 
@@ -65,9 +69,11 @@ pub fn mix_symph_indiv(
             }
 
             let buf = pkt.buf();
+            // Checked per-packet, as a `ConcatInput` may switch codecs between segments.
+            let codec_type = input.decoder.codec_params().codec;
 
             // Opus packet passthrough special case.
-            if codec_type == CODEC_TYPE_OPUS && local_state.passthrough != Passthrough::Block {
+            if codec_type == CODEC_TYPE_OPUS && local_state.passthrough != Passthrough::Blocked {
                 if let Some(slot) = opus_slot.as_mut() {
                     let sample_ct = buf
                         .try_into()
@@ -79,10 +85,16 @@ pub fn mix_symph_indiv(
                     // However, a frame that's bigger than the target buffer is an instant block.
                     let buf_size_fatal = buf.len() >= slot.len();
 
-                    if match sample_ct {
-                        Ok(MONO_FRAME_SIZE) => true,
-                        _ => !local_state.record_and_check_passthrough_strike_final(buf_size_fatal),
-                    } {
+                    let samples = sample_ct.ok();
+                    let frame = PassthroughBlock {
+                        len: buf.len(),
+                        samples,
+                    };
+
+                    if samples == Some(MONO_FRAME_SIZE)
+                        || !local_state
+                            .record_and_check_passthrough_strike_final(frame, buf_size_fatal)
+                    {
                         slot.write_all(buf)
                             .expect("Bounds check performed, and failure will block passthrough.");
 
@@ -91,13 +103,23 @@ pub fn mix_symph_indiv(
                 }
             }
 
-            input
-                .decoder
-                .decode(&pkt)
-                .map_err(|e| {
+            match input.decoder.decode(&pkt) {
+                Ok(decoded) => {
+                    skipped_packets = 0;
+                    Some(decoded)
+                },
+                // A malformed packet need not end the track: skip to the next.
+                // A long run of them must, to keep this mix within its deadline.
+                Err(SymphoniaError::DecodeError(e)) if skipped_packets < DECODE_SKIP_LIMIT => {
+                    warn!("Skipping undecodable packet: {e}");
+                    skipped_packets += 1;
+                    continue;
+                },
+                Err(e) => {
                     track_status = e.into();
-                })
-                .ok()
+                    None
+                },
+            }
         } else {
             track_status = MixStatus::Ended;
             None
@@ -107,9 +129,9 @@ pub fn mix_symph_indiv(
         if source_packet.is_none() {
             if resample_in_progress {
                 // fill up remainder of buf with zeroes, resample, mix
-                let (chan_c, resampler, rs_out_buf) = local_state.resampler.as_mut().unwrap();
+                let rs = local_state.resampler.as_mut().unwrap();
                 let in_len = resample_scratch.frames();
-                let to_render = resampler.input_frames_next().saturating_sub(in_len);
+                let to_render = rs.resampler.input_frames_next().saturating_sub(in_len);
 
                 if to_render != 0 {
                     resample_scratch.render_reserved(Some(to_render));
@@ -121,19 +143,18 @@ pub fn mix_symph_indiv(
                 }
 
                 // Luckily, we make use of the WHOLE input buffer here.
-                resampler
+                rs.resampler
                     .process_into_buffer(
-                        &resample_scratch.planes().planes()[..*chan_c],
-                        rs_out_buf,
-                        None,
+                        &resample_scratch.planes().planes()[..rs.channels],
+                        &mut rs.output,
                     )
                     .unwrap();
 
                 // Calculate true end position using sample rate math
-                let ratio = (rs_out_buf[0].len() as f32) / (resample_scratch.frames() as f32);
+                let ratio = (rs.output[0].len() as f32) / (resample_scratch.frames() as f32);
                 let out_samples = (ratio * (in_len as f32)).round() as usize;
 
-                mix_resampled(rs_out_buf, symph_mix, samples_written, volume);
+                mix_resampled(&rs.output, symph_mix, samples_written, volume);
 
                 samples_written += out_samples;
             }
@@ -146,10 +167,25 @@ pub fn mix_symph_indiv(
         let in_rate = source_packet.spec().rate;
         let pkt_frames = source_packet.frames();
 
-        if pkt_frames == 0 {
+        // Empty packets carry no audio to mix (and would break position/resampler maths).
+        if pkt_frames == 0 || in_rate == 0 {
             continue;
         }
 
+        // Rate and channel count only change between the segments of a `ConcatInput`,
+        // and quality only changes with the driver's config.
+        // Any partial resampler chunk from the previous resampler is dropped.
+        let chan_c = source_packet.spec().channels.count();
+        if local_state
+            .resampler
+            .as_ref()
+            .is_some_and(|rs| !rs.matches(chan_c, in_rate, resample_quality))
+        {
+            local_state.resampler = None;
+            resample_scratch.clear();
+            resample_in_progress = false;
+        }
+
         if in_rate == SAMPLE_RATE_RAW as u32 {
             // No need to resample: mix as standard.
             let samples_marched = mix_over_ref(
@@ -165,22 +201,13 @@ pub fn mix_symph_indiv(
             local_state.inner_pos += samples_marched;
             local_state.inner_pos %= pkt_frames;
         } else {
-            // NOTE: this should NEVER change in one stream.
-            let chan_c = source_packet.spec().channels.count();
-            let (_, resampler, rs_out_buf) = local_state.resampler.get_or_insert_with(|| {
-                // TODO: integ. error handling here.
-                let resampler = FftFixedOut::new(
-                    in_rate as usize,
-                    SAMPLE_RATE_RAW,
-                    RESAMPLE_OUTPUT_FRAME_SIZE,
-                    4,
-                    chan_c,
-                )
-                .expect("Failed to create resampler.");
-                let out_buf = resampler.output_buffer_allocate(true);
-
-                (chan_c, resampler, out_buf)
-            });
+            let ResampleState {
+                resampler,
+                output: rs_out_buf,
+                ..
+            } = local_state
+                .resampler
+                .get_or_insert_with(|| ResampleState::new(chan_c, in_rate, resample_quality));
 
             let inner_pos = local_state.inner_pos;
 
@@ -206,9 +233,7 @@ pub fn mix_symph_indiv(
                     local_state.inner_pos += needed_in_frames;
                     local_state.inner_pos %= pkt_frames;
 
-                    resampler
-                        .process_into_buffer(&refs, rs_out_buf, None)
-                        .unwrap();
+                    resampler.process_into_buffer(&refs, rs_out_buf).unwrap();
                 } else {
                     unreachable!()
                 }
@@ -236,7 +261,6 @@ pub fn mix_symph_indiv(
                         .process_into_buffer(
                             &resample_scratch.planes().planes()[..chan_c],
                             rs_out_buf,
-                            None,
                         )
                         .unwrap();
                     resample_scratch.clear();
@@ -257,6 +281,42 @@ pub fn mix_symph_indiv(
     (MixType::MixedPcm(samples_written), track_status)
 }
 
+/// Computes the RMS level of a decoded packet across all channels, relative to full-scale.
+pub(crate) fn rms_of_ref(source: &AudioBufferRef<'_>) -> f32 {
+    match source {
+        AudioBufferRef::U8(v) => rms_of_buffer(v),
+        AudioBufferRef::U16(v) => rms_of_buffer(v),
+        AudioBufferRef::U24(v) => rms_of_buffer(v),
+        AudioBufferRef::U32(v) => rms_of_buffer(v),
+        AudioBufferRef::S8(v) => rms_of_buffer(v),
+        AudioBufferRef::S16(v) => rms_of_buffer(v),
+        AudioBufferRef::S24(v) => rms_of_buffer(v),
+        AudioBufferRef::S32(v) => rms_of_buffer(v),
+        AudioBufferRef::F32(v) => rms_of_buffer(v),
+        AudioBufferRef::F64(v) => rms_of_buffer(v),
+    }
+}
+
+fn rms_of_buffer<S>(source: &AudioBuffer<S>) -> f32
+where
+    S: Sample + IntoSample<f32>,
+{
+    let planes = source.planes();
+    let (count, sum_sq) = planes.planes().iter().flat_map(|plane| plane.iter()).fold(
+        (0usize, 0.0f32),
+        |(count, sum_sq), s| {
+            let s: f32 = (*s).into_sample();
+            (count + 1, sum_sq + s * s)
+        },
+    );
+
+    if count == 0 {
+        0.0
+    } else {
+        (sum_sq / count as f32).sqrt()
+    }
+}
+
 #[inline]
 fn mix_over_ref(
     source: &AudioBufferRef<'_>,
@@ -395,6 +455,20 @@ fn mix_resampled(
     mix_ct
 }
 
+/// Scales the first `len` samples of each plane by a gain which moves linearly
+/// from `start` to `end` across the whole frame.
+#[inline]
+pub(crate) fn apply_gain_ramp(target: &mut AudioBuffer<f32>, len: usize, start: f32, end: f32) {
+    let frames = target.frames();
+    let step = (end - start) / frames as f32;
+
+    for plane in &mut (*target.planes_mut().planes()) {
+        for (i, s) in plane[..len.min(frames)].iter_mut().enumerate() {
+            *s *= start + step * i as f32;
+        }
+    }
+}
+
 #[inline]
 pub(crate) fn copy_into_resampler(
     source: &AudioBufferRef<'_>,
@@ -442,3 +516,275 @@ where
 
     len
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::test_data::FILE_WAV_TARGET,
+        input::{
+            codecs::{CODEC_REGISTRY, PROBE},
+            File,
+        },
+    };
+    use std::io::Cursor;
+    use symphonia_core::{
+        audio::AsAudioBufferRef,
+        codecs::{CodecDescriptor, CodecParameters, Decoder, DecoderOptions, FinalizeResult},
+        errors::Result as SymphResult,
+        formats::Packet,
+    };
+
+    /// Wraps a real decoder, replacing some (or, if `corrupt`, all) of its output with
+    /// empty or undecodable packets.
+    struct FaultyDecoder {
+        inner: Box<dyn Decoder>,
+        empty: AudioBuffer<f32>,
+        count: usize,
+        corrupt: bool,
+    }
+
+    impl Decoder for FaultyDecoder {
+        fn try_new(_params: &CodecParameters, _options: &DecoderOptions) -> SymphResult<Self> {
+            Err(SymphoniaError::Unsupported(
+                "test decoder only wraps others",
+            ))
+        }
+
+        fn supported_codecs() -> &'static [CodecDescriptor] {
+            &[]
+        }
+
+        fn reset(&mut self) {
+            self.inner.reset();
+        }
+
+        fn codec_params(&self) -> &CodecParameters {
+            self.inner.codec_params()
+        }
+
+        fn decode(&mut self, packet: &Packet) -> SymphResult<AudioBufferRef<'_>> {
+            self.count += 1;
+            if self.corrupt {
+                return Err(SymphoniaError::DecodeError("crafted bad packet"));
+            }
+            match self.count % 3 {
+                0 => Ok(self.empty.as_audio_buffer_ref()),
+                1 => Err(SymphoniaError::DecodeError("crafted bad packet")),
+                _ => self.inner.decode(packet),
+            }
+        }
+
+        fn finalize(&mut self) -> FinalizeResult {
+            self.inner.finalize()
+        }
+
+        fn last_decoded(&self) -> AudioBufferRef<'_> {
+            self.inner.last_decoded()
+        }
+    }
+
+    async fn faulty_source(corrupt: bool) -> Parsed {
+        let input = Input::from(File::new(FILE_WAV_TARGET))
+            .make_playable_async(&CODEC_REGISTRY, &PROBE)
+            .await
+            .unwrap();
+        let Input::Live(LiveInput::Parsed(mut parsed), _) = input else {
+            panic!("Input was not parsed.");
+        };
+
+        let spec = SignalSpec::new_with_layout(SAMPLE_RATE_RAW as u32, Layout::Stereo);
+        parsed.decoder = Box::new(FaultyDecoder {
+            inner: parsed.decoder,
+            empty: AudioBuffer::new(0, spec),
+            count: 0,
+            corrupt,
+        });
+
+        parsed
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn empty_and_bad_packets_are_skipped() {
+        let mut parsed = faulty_source(false).await;
+
+        let spec = SignalSpec::new_with_layout(SAMPLE_RATE_RAW as u32, Layout::Stereo);
+
+        let mut symph_mix = AudioBuffer::<f32>::new(MONO_FRAME_SIZE as u64, spec);
+        let mut resample_scratch = AudioBuffer::<f32>::new(MONO_FRAME_SIZE as u64, spec);
+        let mut state = DecodeState::default();
+
+        for _ in 0..10 {
+            symph_mix.clear();
+            symph_mix.render_reserved(Some(MONO_FRAME_SIZE));
+
+            let (mix_type, status) = mix_symph_indiv(
+                &mut symph_mix,
+                &mut resample_scratch,
+                &mut parsed,
+                &mut state,
+                1.0,
+                ResampleQuality::default(),
+                None,
+            );
+
+            assert!(matches!(status, MixStatus::Live));
+            assert_eq!(mix_type, MixType::MixedPcm(MONO_FRAME_SIZE));
+        }
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn runs_of_bad_packets_end_the_track() {
+        let mut parsed = faulty_source(true).await;
+
+        let spec = SignalSpec::new_with_layout(SAMPLE_RATE_RAW as u32, Layout::Stereo);
+        let mut symph_mix = AudioBuffer::<f32>::new(MONO_FRAME_SIZE as u64, spec);
+        let mut resample_scratch = AudioBuffer::<f32>::new(MONO_FRAME_SIZE as u64, spec);
+        symph_mix.render_reserved(Some(MONO_FRAME_SIZE));
+
+        let (_, status) = mix_symph_indiv(
+            &mut symph_mix,
+            &mut resample_scratch,
+            &mut parsed,
+            &mut DecodeState::default(),
+            1.0,
+            ResampleQuality::default(),
+            None,
+        );
+
+        assert!(matches!(
+            status,
+            MixStatus::Errored(SymphoniaError::DecodeError(_))
+        ));
+    }
+
+    fn sine_source(rate: u32) -> Parsed {
+        let floats = crate::test_utils::make_sine(50 * STEREO_FRAME_SIZE, true);
+        let input: Input = crate::input::RawAdapter::new(Cursor::new(floats), rate, 2).into();
+        let Input::Live(live, _) = input else {
+            panic!("RawAdapter was not live.");
+        };
+        let Ok(LiveInput::Parsed(parsed)) = live.promote(&CODEC_REGISTRY, &PROBE) else {
+            panic!("RawAdapter could not be parsed.");
+        };
+
+        parsed
+    }
+
+    #[test]
+    fn resample_quality_selects_resampler() {
+        let spec = SignalSpec::new_with_layout(SAMPLE_RATE_RAW as u32, Layout::Stereo);
+        let mut symph_mix = AudioBuffer::<f32>::new(MONO_FRAME_SIZE as u64, spec);
+        let mut resample_scratch = AudioBuffer::<f32>::new(MONO_FRAME_SIZE as u64, spec);
+
+        for (rate, quality) in [
+            (44_100, ResampleQuality::Fast),
+            (44_100, ResampleQuality::Balanced),
+            (44_100, ResampleQuality::High),
+            (48_000, ResampleQuality::High),
+        ] {
+            let mut parsed = sine_source(rate);
+            let mut state = DecodeState::default();
+
+            for _ in 0..5 {
+                symph_mix.clear();
+                symph_mix.render_reserved(Some(MONO_FRAME_SIZE));
+                resample_scratch.clear();
+
+                let (mix_type, _) = mix_symph_indiv(
+                    &mut symph_mix,
+                    &mut resample_scratch,
+                    &mut parsed,
+                    &mut state,
+                    1.0,
+                    quality,
+                    None,
+                );
+                assert_eq!(mix_type, MixType::MixedPcm(MONO_FRAME_SIZE));
+            }
+
+            assert!(symph_mix.chan(0).iter().any(|s| s.abs() > 0.1));
+            match (rate, &state.resampler) {
+                (48_000, None) => {},
+                (_, Some(rs)) => assert!(matches!(
+                    (quality, &rs.resampler),
+                    (ResampleQuality::Fast, MixResampler::Fast(_))
+                        | (ResampleQuality::Balanced, MixResampler::Balanced(_))
+                        | (ResampleQuality::High, MixResampler::High(_))
+                )),
+                _ => panic!("Unexpected resampler for {rate}Hz at {quality:?}."),
+            }
+        }
+    }
+
+    #[test]
+    fn declared_source_spec_drives_resampler() {
+        // One second of a 400Hz tone, well below the source's Nyquist frequency.
+        let floats: Vec<u8> = (0..16_000)
+            .map(|i| (i as f32 * 400.0 * std::f32::consts::TAU / 16_000.0).sin())
+            .flat_map(f32::to_le_bytes)
+            .collect();
+        let input: Input = crate::input::RawAdapter::with_spec(
+            Cursor::new(floats),
+            SignalSpec::new_with_layout(16_000, Layout::Mono),
+        )
+        .into();
+        let Input::Live(live, _) = input else {
+            panic!("RawAdapter was not live.");
+        };
+        let Ok(LiveInput::Parsed(mut parsed)) = live.promote(&CODEC_REGISTRY, &PROBE) else {
+            panic!("RawAdapter could not be parsed.");
+        };
+
+        let spec = SignalSpec::new_with_layout(SAMPLE_RATE_RAW as u32, Layout::Stereo);
+        let mut symph_mix = AudioBuffer::<f32>::new(MONO_FRAME_SIZE as u64, spec);
+        let mut resample_scratch = AudioBuffer::<f32>::new(MONO_FRAME_SIZE as u64, spec);
+        let mut state = DecodeState::default();
+
+        for _ in 0..5 {
+            symph_mix.clear();
+            symph_mix.render_reserved(Some(MONO_FRAME_SIZE));
+            resample_scratch.clear();
+
+            let (mix_type, _) = mix_symph_indiv(
+                &mut symph_mix,
+                &mut resample_scratch,
+                &mut parsed,
+                &mut state,
+                1.0,
+                ResampleQuality::default(),
+                None,
+            );
+            assert_eq!(mix_type, MixType::MixedPcm(MONO_FRAME_SIZE));
+        }
+
+        assert!(state.resampler.is_some());
+        assert!(symph_mix.chan(0).iter().any(|s| s.abs() > 0.1));
+        assert_eq!(symph_mix.chan(0), symph_mix.chan(1));
+    }
+
+    #[test]
+    fn gain_ramp_is_linear_across_frame() {
+        let mut buf = AudioBuffer::<f32>::new(4, SignalSpec::new_with_layout(48_000, Layout::Mono));
+        buf.render_reserved(Some(4));
+        buf.chan_mut(0).fill(1.0);
+
+        apply_gain_ramp(&mut buf, 4, 1.0, 0.5);
+        for (s, expected) in buf.chan(0).iter().zip([1.0, 0.875, 0.75, 0.625]) {
+            assert!((s - expected).abs() < f32::EPSILON);
+        }
+
+        let mut fade = FadeOut::new(Duration::from_millis(50), flume::bounded(1).0);
+        let gains = [fade.step(), fade.step(), fade.step()];
+        for ((start, end), expected) in gains
+            .iter()
+            .zip([1.0, 2.0 / 3.0, 1.0 / 3.0, 0.0].windows(2))
+        {
+            assert!((start - expected[0]).abs() < f32::EPSILON);
+            assert!((end - expected[1]).abs() < f32::EPSILON);
+        }
+        assert!(fade.is_finished());
+    }
+}
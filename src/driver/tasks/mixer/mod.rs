@@ -1,3 +1,4 @@
+mod encoders;
 pub mod mix_logic;
 mod pool;
 mod result;
@@ -5,6 +6,8 @@ pub mod state;
 pub mod track;
 mod util;
 
+pub use encoders::prewarm_encoders;
+use encoders::PooledEncoder;
 use pool::*;
 use result::*;
 use state::*;
@@ -18,26 +21,48 @@ use super::{
 use crate::driver::crypto::TAG_SIZE;
 use crate::{
     constants::*,
-    driver::MixMode,
+    driver::ResampleQuality,
     events::EventStore,
-    input::{Input, Parsed},
-    tracks::{Action, LoopState, PlayError, PlayMode, TrackCommand, TrackHandle, TrackState, View},
+    input::{Input, LiveInput, Parsed},
+    model::SpeakingState,
+    tracks::{
+        Action,
+        DuckLevels,
+        DuckRole,
+        LoopState,
+        Passthrough,
+        PassthroughBlock,
+        PlayError,
+        PlayMode,
+        RemovalReason,
+        TrackCommand,
+        TrackHandle,
+        TrackState,
+        View,
+    },
     Config,
 };
+#[cfg(feature = "receive")]
+use crate::{
+    driver::AdaptiveState,
+    events::{context_data::BitrateChangeData, CoreContext},
+};
 use audiopus::{
-    coder::Encoder as OpusEncoder,
+    coder::{Encoder as OpusEncoder, GenericCtl},
     softclip::SoftClip,
-    Application as CodingMode,
     Bitrate,
+    Result as OpusResult,
 };
+use bytes::Bytes;
+#[cfg(feature = "receive")]
+use discortp::rtcp::report::ReportBlock;
 use discortp::{
     discord::MutableKeepalivePacket,
     rtp::{MutableRtpPacket, RtpPacket},
     MutablePacket,
 };
-use flume::{Receiver, SendError, Sender, TryRecvError};
+use flume::{Receiver, SendError, Sender, TryRecvError, TrySendError};
 use rand::random;
-use rubato::{FftFixedOut, Resampler};
 use std::{
     io::Write,
     result::Result as StdResult,
@@ -53,28 +78,56 @@ use symphonia_core::{
     units::Time,
 };
 use tokio::runtime::Handle;
-use tracing::error;
+use tracing::{error, warn};
 
 #[cfg(test)]
 use crate::driver::test_config::{OutputMessage, OutputMode};
 #[cfg(test)]
 use discortp::Packet as _;
 
+// Bounds of the bitrates accepted by libopus, in bits per second.
+const MIN_OPUS_BITRATE: u64 = 500;
+const MAX_OPUS_BITRATE: u64 = 512_000;
+
 pub struct Mixer {
     pub bitrate: Bitrate,
+    /// Bitrate measured from prior passthrough audio, which overrides `bitrate`
+    /// when `Config::match_passthrough_bitrate` is set.
+    passthrough_bitrate: Option<Bitrate>,
+    passthrough_bytes: u64,
+    passthrough_frames: u64,
+    /// Bitrate adapted to reported packet loss, which overrides `bitrate`
+    /// when `Config::adaptive_bitrate` is set.
+    #[cfg(feature = "receive")]
+    adaptive_bitrate: Option<AdaptiveState>,
     pub config: Arc<Config>,
     pub conn_active: Option<MixerConnection>,
     pub content_prep_sequence: u64,
     pub deadline: Instant,
     pub disposer: DisposalThread,
-    pub encoder: OpusEncoder,
+    pub encoder: PooledEncoder,
+    fade: Option<FadeOut>,
+    /// Loudest level of each ducking group's controllers in the last mixed frame.
+    duck_levels: DuckLevels,
+    /// Ducking controller levels being measured in the current frame.
+    next_duck_levels: DuckLevels,
+    /// Number of consecutive voice packets which have failed to send.
+    send_failures: usize,
     pub interconnect: Interconnect,
+    /// Destination for the peak level of each transmitted frame.
+    meter: Option<Sender<f32>>,
+    /// Additional destinations for each encoded Opus frame.
+    opus_sinks: Vec<Sender<Bytes>>,
     pub mix_rx: Receiver<MixerMessage>,
     pub muted: bool,
     // pub packet: [u8; VOICE_PACKET_MAX],
     pub prevent_events: bool,
     pub silence_frames: u8,
+    /// Acknowledges a graceful shutdown once all trailing silence has been sent.
+    shutdown: Option<Sender<()>>,
     pub soft_clip: SoftClip,
+    /// Flags sent to the voice gateway while audio is being sent.
+    pub speaking_flags: SpeakingState,
     thread_pool: BlockyTaskPool,
     pub ws: Option<Sender<WsMessage>>,
 
@@ -95,11 +148,18 @@ pub struct Mixer {
     raw_msg: Option<OutputMessage>,
 }
 
-fn new_encoder(bitrate: Bitrate, mix_mode: MixMode) -> Result<OpusEncoder> {
-    let mut encoder = OpusEncoder::new(SAMPLE_RATE, mix_mode.to_opus(), CodingMode::Audio)?;
-    encoder.set_bitrate(bitrate)?;
+fn new_encoder(bitrate: Bitrate, config: &Config) -> Result<PooledEncoder> {
+    PooledEncoder::new(bitrate, config).map_err(Into::into)
+}
+
+fn configure_encoder(encoder: &mut OpusEncoder, config: &Config) -> OpusResult<()> {
+    encoder.set_inband_fec(config.opus_fec)?;
+    encoder.set_dtx(config.opus_dtx)?;
+    if let Some(complexity) = config.opus_complexity {
+        encoder.set_complexity(complexity.min(10))?;
+    }
 
-    Ok(encoder)
+    Ok(())
 }
 
 impl Mixer {
@@ -110,7 +170,7 @@ impl Mixer {
         config: Config,
     ) -> Self {
         let bitrate = DEFAULT_BITRATE;
-        let encoder = new_encoder(bitrate, config.mix_mode)
+        let encoder = new_encoder(bitrate, &config)
             .expect("Failed to create encoder in mixing thread with known-good values.");
         let soft_clip = SoftClip::new(config.mix_mode.to_opus());
 
@@ -121,25 +181,17 @@ impl Mixer {
 
         let thread_pool = BlockyTaskPool::new(async_handle);
 
-        let symph_layout = config.mix_mode.symph_layout();
+        let out_spec = config.mix_mode.signal_spec();
 
         let disposer = config.disposer.clone().unwrap_or_default();
+        #[cfg(feature = "receive")]
+        let adaptive_bitrate = config
+            .adaptive_bitrate
+            .map(|bounds| AdaptiveState::new(bounds, bitrate));
         let config = config.into();
 
-        let sample_buffer = SampleBuffer::<f32>::new(
-            MONO_FRAME_SIZE as u64,
-            symphonia_core::audio::SignalSpec::new_with_layout(
-                SAMPLE_RATE_RAW as u32,
-                symph_layout,
-            ),
-        );
-        let symph_mix = AudioBuffer::<f32>::new(
-            MONO_FRAME_SIZE as u64,
-            symphonia_core::audio::SignalSpec::new_with_layout(
-                SAMPLE_RATE_RAW as u32,
-                symph_layout,
-            ),
-        );
+        let sample_buffer = SampleBuffer::<f32>::new(MONO_FRAME_SIZE as u64, out_spec);
+        let symph_mix = AudioBuffer::<f32>::new(MONO_FRAME_SIZE as u64, out_spec);
         let resample_scratch = AudioBuffer::<f32>::new(
             MONO_FRAME_SIZE as u64,
             SignalSpec::new_with_layout(SAMPLE_RATE_RAW as u32, Layout::Stereo),
@@ -149,18 +201,31 @@ impl Mixer {
 
         Self {
             bitrate,
+            passthrough_bitrate: None,
+            passthrough_bytes: 0,
+            passthrough_frames: 0,
+            #[cfg(feature = "receive")]
+            adaptive_bitrate,
             config,
             conn_active: None,
             content_prep_sequence: 0,
             deadline,
             disposer,
             encoder,
+            fade: None,
+            duck_levels: DuckLevels::default(),
+            next_duck_levels: DuckLevels::default(),
+            send_failures: 0,
             interconnect,
+            meter: None,
+            opus_sinks: vec![],
             mix_rx,
             muted: false,
             prevent_events: false,
             silence_frames: 0,
+            shutdown: None,
             soft_clip,
+            speaking_flags: SpeakingState::MICROPHONE,
             thread_pool,
             ws: None,
 
@@ -185,6 +250,106 @@ impl Mixer {
         self.encoder.set_bitrate(bitrate).map_err(Into::into)
     }
 
+    /// The bitrate which the Opus encoder should currently be using.
+    fn encoder_bitrate(&self) -> Bitrate {
+        #[cfg(feature = "receive")]
+        if let (None, Some(adaptive)) = (self.passthrough_bitrate, &self.adaptive_bitrate) {
+            return adaptive.bitrate();
+        }
+
+        self.passthrough_bitrate.unwrap_or(self.bitrate)
+    }
+
+    /// Adapts the encoder bitrate to the packet loss reported for this connection's SSRC.
+    #[cfg(feature = "receive")]
+    fn handle_receiver_report(&mut self, blocks: &[ReportBlock], packet: &[u8]) -> Result<()> {
+        let Some(adaptive) = &mut self.adaptive_bitrate else {
+            return Ok(());
+        };
+
+        let Some(ssrc) = RtpPacket::new(packet).map(|rtp| rtp.get_ssrc()) else {
+            return Ok(());
+        };
+
+        let Some(fraction_lost) = blocks
+            .iter()
+            .filter(|block| block.ssrc == ssrc)
+            .map(|block| block.fraction_lost)
+            .max()
+        else {
+            return Ok(());
+        };
+
+        let previous_bitrate = adaptive.bits_per_second();
+        let Some(bitrate) = adaptive.report(fraction_lost) else {
+            return Ok(());
+        };
+
+        if self.passthrough_bitrate.is_none() {
+            if let Err(e) = self.set_bitrate(Bitrate::BitsPerSecond(bitrate)) {
+                error!("Failed to adapt bitrate {:?}", e);
+            }
+        }
+
+        self.fire_event(EventMessage::FireCoreEvent(CoreContext::BitrateChange(
+            BitrateChangeData {
+                bitrate,
+                previous_bitrate,
+                fraction_lost,
+            },
+        )))
+    }
+
+    /// Sends the peak level of the frame about to be transmitted to the meter, if set.
+    ///
+    /// Passthrough frames are only sent while metering if they are silent.
+    fn report_level(&mut self, mix_len: MixType) {
+        let Some(meter) = &self.meter else {
+            return;
+        };
+
+        let level = match mix_len {
+            MixType::MixedPcm(n) => self.sample_buffer.samples()
+                [..n * self.config.mix_mode.channels()]
+                .iter()
+                .fold(0.0f32, |peak, s| peak.max(s.abs())),
+            MixType::Passthrough(_) => 0.0,
+        };
+
+        if let Err(TrySendError::Disconnected(_)) = meter.try_send(level) {
+            self.meter = None;
+        }
+    }
+
+    /// Measures the size of passthrough Opus frames, and adopts their average
+    /// bitrate for encoding once mixing resumes (if configured).
+    fn update_passthrough_bitrate(&mut self, mix_len: MixType) {
+        match mix_len {
+            MixType::Passthrough(len) => {
+                self.passthrough_bytes += len as u64;
+                self.passthrough_frames += 1;
+            },
+            MixType::MixedPcm(len) if len > 0 && self.passthrough_frames > 0 => {
+                if self.config.match_passthrough_bitrate {
+                    let bps = (self.passthrough_bytes * 8 * AUDIO_FRAME_RATE as u64)
+                        / self.passthrough_frames;
+                    let bitrate = Bitrate::BitsPerSecond(
+                        bps.clamp(MIN_OPUS_BITRATE, MAX_OPUS_BITRATE) as i32,
+                    );
+
+                    match self.set_bitrate(bitrate) {
+                        Ok(()) => self.passthrough_bitrate = Some(bitrate),
+                        Err(e) => error!("Failed to match passthrough bitrate {:?}", e),
+                    }
+                }
+
+                self.passthrough_bytes = 0;
+                self.passthrough_frames = 0;
+            },
+            MixType::MixedPcm(_) => {},
+        }
+    }
+
     pub(crate) fn do_rebuilds(
         &mut self,
         event_failure: bool,
@@ -232,9 +397,7 @@ impl Mixer {
         let error = match msg {
             MixerMessage::AddTrack(t) => self.add_track(t),
             MixerMessage::SetTrack(t) => {
-                self.tracks.clear();
-
-                let mut out = self.fire_event(EventMessage::RemoveAllTracks);
+                let mut out = self.remove_all_tracks();
 
                 if let Some(t) = t {
                     // Do this unconditionally: this affects local state infallibly,
@@ -248,7 +411,12 @@ impl Mixer {
             },
             MixerMessage::SetBitrate(b) => {
                 self.bitrate = b;
-                if let Err(e) = self.set_bitrate(b) {
+                self.passthrough_bitrate = None;
+                #[cfg(feature = "receive")]
+                if let Some(adaptive) = &mut self.adaptive_bitrate {
+                    *adaptive = AdaptiveState::new(self.config.adaptive_bitrate.unwrap(), b);
+                }
+                if let Err(e) = self.set_bitrate(self.encoder_bitrate()) {
                     error!("Failed to update bitrate {:?}", e);
                 }
                 Ok(())
@@ -257,8 +425,82 @@ impl Mixer {
                 self.muted = m;
                 Ok(())
             },
+            MixerMessage::SetMeter(meter) => {
+                self.meter = meter;
+                Ok(())
+            },
+            MixerMessage::AddOpusSink(sink) => {
+                self.opus_sinks.push(sink);
+                Ok(())
+            },
+            MixerMessage::RemoveOpusSinks => {
+                self.opus_sinks.clear();
+                Ok(())
+            },
+            MixerMessage::SetSpeakingFlags(flags) => {
+                self.speaking_flags = flags;
+
+                // An idle mixer has told Discord it isn't speaking: the new flags are
+                // sent once audio resumes.
+                if self.silence_frames > 0 {
+                    self.send_gateway_speaking()
+                } else {
+                    Ok(())
+                }
+            },
+            MixerMessage::SetPaused(paused) => {
+                for (i, track) in self.tracks.iter_mut().enumerate() {
+                    let target = match track.playing {
+                        PlayMode::Play if paused => PlayMode::Pause,
+                        PlayMode::Pause if !paused => PlayMode::Play,
+                        _ => continue,
+                    };
+
+                    track.playing.change_to(target);
+
+                    if !self.prevent_events {
+                        drop(self.interconnect.events.send(EventMessage::ChangeState(
+                            i,
+                            TrackStateChange::Mode(track.playing.clone()),
+                        )));
+                    }
+                }
+                Ok(())
+            },
+            MixerMessage::FadeOut(duration, done) => {
+                // A parked mixer sends no audio, so there is nothing to fade.
+                if self.tracks.is_empty() || !self.is_sending() {
+                    let out = self.remove_all_tracks();
+                    _ = done.send(());
+                    out
+                } else {
+                    self.fade = Some(FadeOut::new(duration, done));
+                    Ok(())
+                }
+            },
+            MixerMessage::Shutdown(done) => {
+                let out = if self.fade.is_some() {
+                    self.finish_fade()
+                } else {
+                    self.remove_all_tracks()
+                };
+
+                // A mixer which is sending audio must first follow it with silence.
+                if self.silence_frames == 0 || !self.is_sending() {
+                    self.finish_shutdown(&done);
+                } else {
+                    self.shutdown = Some(done);
+                }
+
+                out
+            },
+            MixerMessage::GetTracks(tx) => {
+                drop(tx.send(self.track_handles.clone()));
+                Ok(())
+            },
             MixerMessage::SetConn(conn, ssrc) => {
                 self.conn_active = Some(conn);
+                self.send_failures = 0;
                 let mut rtp = MutableRtpPacket::new(packet).expect(
                     "Too few bytes in self.packet for RTP header.\
                         (Blame: VOICE_PACKET_MAX?)",
@@ -273,7 +515,17 @@ impl Mixer {
             },
             MixerMessage::DropConn => {
                 self.conn_active = None;
-                Ok(())
+
+                if let Some(done) = self.shutdown.take() {
+                    self.finish_shutdown(&done);
+                }
+
+                // No more audio will be mixed, so complete any fade now.
+                if self.fade.is_some() {
+                    self.finish_fade()
+                } else {
+                    Ok(())
+                }
             },
             MixerMessage::ReplaceInterconnect(i) => {
                 self.prevent_events = false;
@@ -295,25 +547,57 @@ impl Mixer {
                 self.rebuild_tracks()
             },
             MixerMessage::SetConfig(new_config) => {
-                if new_config.mix_mode != self.config.mix_mode {
+                let mode_changed = new_config.mix_mode != self.config.mix_mode;
+                let rebuild_encoder = mode_changed
+                    || new_config.opus_application != self.config.opus_application
+                    || new_config.opus_complexity != self.config.opus_complexity;
+
+                if mode_changed {
                     self.soft_clip = SoftClip::new(new_config.mix_mode.to_opus());
-                    if let Ok(enc) = new_encoder(self.bitrate, new_config.mix_mode) {
+                    if !new_config.match_passthrough_bitrate {
+                        self.passthrough_bitrate = None;
+                    }
+
+                    let out_spec = new_config.mix_mode.signal_spec();
+                    self.sample_buffer = SampleBuffer::<f32>::new(MONO_FRAME_SIZE as u64, out_spec);
+                    self.symph_mix = AudioBuffer::<f32>::new(MONO_FRAME_SIZE as u64, out_spec);
+                } else if !new_config.match_passthrough_bitrate
+                    && self.passthrough_bitrate.take().is_some()
+                    && !rebuild_encoder
+                {
+                    if let Err(e) = self.set_bitrate(self.encoder_bitrate()) {
+                        error!("Failed to restore bitrate {:?}", e);
+                    }
+                }
+
+                #[cfg(feature = "receive")]
+                if new_config.adaptive_bitrate != self.config.adaptive_bitrate {
+                    self.adaptive_bitrate = new_config
+                        .adaptive_bitrate
+                        .map(|bounds| AdaptiveState::new(bounds, self.bitrate));
+
+                    if !rebuild_encoder && self.passthrough_bitrate.is_none() {
+                        if let Err(e) = self.set_bitrate(self.encoder_bitrate()) {
+                            error!("Failed to apply adaptive bitrate {:?}", e);
+                        }
+                    }
+                }
+
+                if rebuild_encoder {
+                    if let Ok(enc) = new_encoder(self.encoder_bitrate(), &new_config) {
                         self.encoder = enc;
                     } else {
                         self.bitrate = DEFAULT_BITRATE;
-                        self.encoder = new_encoder(self.bitrate, new_config.mix_mode)
+                        self.passthrough_bitrate = None;
+                        self.encoder = new_encoder(self.bitrate, &new_config)
                             .expect("Failed fallback rebuild of OpusEncoder with safe inputs.");
                     }
-
-                    let sl = new_config.mix_mode.symph_layout();
-                    self.sample_buffer = SampleBuffer::<f32>::new(
-                        MONO_FRAME_SIZE as u64,
-                        SignalSpec::new_with_layout(SAMPLE_RATE_RAW as u32, sl),
-                    );
-                    self.symph_mix = AudioBuffer::<f32>::new(
-                        MONO_FRAME_SIZE as u64,
-                        SignalSpec::new_with_layout(SAMPLE_RATE_RAW as u32, sl),
-                    );
+                } else if new_config.opus_fec != self.config.opus_fec
+                    || new_config.opus_dtx != self.config.opus_dtx
+                {
+                    if let Err(e) = configure_encoder(&mut self.encoder, &new_config) {
+                        error!("Failed to reconfigure encoder {:?}", e);
+                    }
                 }
 
                 self.config = Arc::new(
@@ -332,13 +616,24 @@ impl Mixer {
                 if let Some(conn) = &self.conn_active {
                     conn_failure |= conn
                         .udp_rx
-                        .send(UdpRxMessage::SetConfig(new_config))
+                        .send(UdpRxMessage::SetConfig(Box::new(new_config)))
                         .is_err();
                 }
 
                 Ok(())
             },
-            MixerMessage::RebuildEncoder => match new_encoder(self.bitrate, self.config.mix_mode) {
+            #[cfg(feature = "receive")]
+            MixerMessage::ReceiverReport(blocks) => self.handle_receiver_report(&blocks, packet),
+            #[cfg(feature = "receive")]
+            MixerMessage::ResetDecoder(ssrc) => {
+                if let Some(conn) = &self.conn_active {
+                    conn_failure |= conn.udp_rx.send(UdpRxMessage::ResetDecoder(ssrc)).is_err();
+                }
+
+                Ok(())
+            },
+            MixerMessage::RebuildEncoder => match new_encoder(self.encoder_bitrate(), &self.config)
+            {
                 Ok(encoder) => {
                     self.encoder = encoder;
                     Ok(())
@@ -346,7 +641,8 @@ impl Mixer {
                 Err(e) => {
                     error!("Failed to rebuild encoder. Resetting bitrate. {:?}", e);
                     self.bitrate = DEFAULT_BITRATE;
-                    self.encoder = new_encoder(self.bitrate, self.config.mix_mode)
+                    self.passthrough_bitrate = None;
+                    self.encoder = new_encoder(self.bitrate, &self.config)
                         .expect("Failed fallback rebuild of OpusEncoder with safe inputs.");
                     Ok(())
                 },
@@ -359,6 +655,7 @@ impl Mixer {
                 Ok(())
             },
             MixerMessage::Poison => {
+                self.dispose_all_tracks();
                 should_exit = true;
                 Ok(())
             },
@@ -376,7 +673,12 @@ impl Mixer {
         let mut ka = MutableKeepalivePacket::new(&mut self.keepalive_packet[..])
             .expect("FATAL: Insufficient bytes given to keepalive packet.");
         ka.set_ssrc(ssrc);
-        self.keepalive_deadline = self.deadline + UDP_KEEPALIVE_GAP;
+        self.keepalive_deadline = self.deadline + self.keepalive_interval();
+    }
+
+    /// Returns the configured delay between keepalives, respecting the driver's minimum.
+    fn keepalive_interval(&self) -> Duration {
+        self.config.keepalive_interval.max(UDP_KEEPALIVE_GAP_MIN)
     }
 
     #[inline]
@@ -392,12 +694,21 @@ impl Mixer {
 
     #[inline]
     pub fn add_track(&mut self, track: TrackContext) -> Result<()> {
-        let (track, evts, state, handle) = InternalTrack::decompose_track(track);
+        let (mut track, evts, state, handle) = InternalTrack::decompose_track(track);
+        // Tracks over the limit are still stored for one tick, so that their
+        // removal is announced to the event thread like any other error.
+        if self
+            .config
+            .max_tracks
+            .is_some_and(|max| self.tracks.len() >= max)
+        {
+            track.playing = PlayMode::Errored(PlayError::TrackLimit);
+        }
         self.tracks.push(track);
         self.track_handles.push(handle.clone());
         self.interconnect
             .events
-            .send(EventMessage::AddTrack(evts, state, handle))?;
+            .send(EventMessage::AddTrack(evts, Box::new(state), handle))?;
 
         Ok(())
     }
@@ -412,7 +723,7 @@ impl Mixer {
 
             self.interconnect
                 .events
-                .send(EventMessage::AddTrack(evts, state, handle))?;
+                .send(EventMessage::AddTrack(evts, Box::new(state), handle))?;
         }
 
         Ok(())
@@ -420,13 +731,17 @@ impl Mixer {
 
     #[inline]
     pub(crate) fn audio_commands_events(&mut self) -> Result<()> {
+        if self.fade.as_ref().is_some_and(FadeOut::is_finished) {
+            self.finish_fade()?;
+        }
+
         // Apply user commands.
         for (i, track) in self.tracks.iter_mut().enumerate() {
             // This causes fallible event system changes,
             // but if the event thread has died then we'll certainly
             // detect that on the tick later.
             // Changes to play state etc. MUST all be handled.
-            let action = track.process_commands(i, &self.interconnect);
+            let action = track.process_commands(i, &self.interconnect, &self.disposer);
 
             if let Some(req) = action.seek_point {
                 track.seek(
@@ -478,8 +793,10 @@ impl Mixer {
             if track.playing.is_done() {
                 let p_state = track.playing.clone();
                 let to_drop = self.tracks.swap_remove(i);
-                self.disposer
-                    .dispose(DisposalMessage::Track(Box::new(to_drop)));
+                self.disposer.dispose(DisposalMessage::Track(
+                    Box::new(to_drop),
+                    RemovalReason::Finished,
+                ));
 
                 let to_drop = self.track_handles.swap_remove(i);
                 self.disposer.dispose(DisposalMessage::Handle(to_drop));
@@ -500,6 +817,47 @@ impl Mixer {
         Ok(())
     }
 
+    fn finish_fade(&mut self) -> Result<()> {
+        let out = self.remove_all_tracks();
+
+        if let Some(fade) = self.fade.take() {
+            _ = fade.done.send(());
+        }
+
+        out
+    }
+
+    fn finish_shutdown(&self, done: &Sender<()>) {
+        self.send_gateway_not_speaking();
+        _ = done.send(());
+    }
+
+    fn remove_all_tracks(&mut self) -> Result<()> {
+        self.dispose_all_tracks();
+
+        self.fire_event(EventMessage::RemoveAllTracks)
+    }
+
+    fn dispose_all_tracks(&mut self) {
+        for track in self.tracks.drain(..) {
+            self.disposer.dispose(DisposalMessage::Track(
+                Box::new(track),
+                RemovalReason::Cleared,
+            ));
+        }
+        self.track_handles.clear();
+    }
+
+    /// Whether this mixer is able to send audio, and so will be scheduled to mix.
+    fn is_sending(&self) -> bool {
+        #[cfg(test)]
+        if self.config.override_connection.is_some() {
+            return true;
+        }
+
+        self.conn_active.is_some()
+    }
+
     #[cfg(test)]
     #[inline]
     pub(crate) fn test_signal_empty_tick(&self) {
@@ -525,21 +883,47 @@ impl Mixer {
         let mut mix_len = {
             let out = self.mix_tracks(packet);
 
+            if let MixType::MixedPcm(_) = out {
+                self.config.mix_mode.remap_channels(&mut self.symph_mix);
+            }
+
+            if let Some(fade) = self.fade.as_mut() {
+                let (start, end) = fade.step();
+                if let MixType::MixedPcm(len) = out {
+                    mix_logic::apply_gain_ramp(&mut self.symph_mix, len, start, end);
+                }
+            }
+
             self.sample_buffer.copy_interleaved_typed(&self.symph_mix);
 
             out
         };
 
+        self.update_passthrough_bitrate(mix_len);
+
         if self.muted {
             mix_len = MixType::MixedPcm(0);
         }
 
         // Explicit "Silence" frame handling: if there is no mixed data, we must send
-        // ~5 frames of silence (unless another good audio frame appears) before we
-        // stop sending RTP frames.
+        // `silence_frames_on_stop` (default 5) frames of silence (unless another good
+        // audio frame appears) before we stop sending RTP frames.
         if mix_len == MixType::MixedPcm(0) {
             if self.silence_frames > 0 {
                 self.silence_frames -= 1;
+
+                // DTX leaves the encoder in a low-bandwidth state after silent input:
+                // start the next burst of audio from a clean slate.
+                if self.silence_frames == 0 && self.config.opus_dtx {
+                    self.encoder.reset_state()?;
+                }
+
+                if self.silence_frames == 0 {
+                    if let Some(done) = self.shutdown.take() {
+                        self.finish_shutdown(&done);
+                    }
+                }
+
                 let mut rtp = MutableRtpPacket::new(packet).expect(
                     "FATAL: Too few bytes in self.packet for RTP header.\
                         (Blame: VOICE_PACKET_MAX?)",
@@ -551,11 +935,14 @@ impl Mixer {
 
                 mix_len = MixType::Passthrough(SILENT_FRAME.len());
             } else {
-                // Per official guidelines, send 5x silence BEFORE we stop speaking.
+                // Per official guidelines, send (at least) 5x silence BEFORE we stop speaking.
                 return Ok(0);
             }
         } else {
-            self.silence_frames = 5;
+            self.silence_frames = self
+                .config
+                .silence_frames_on_stop
+                .max(MIN_SILENCE_FRAMES_ON_STOP);
 
             if let MixType::MixedPcm(n) = mix_len {
                 if self.config.use_softclip {
@@ -569,6 +956,8 @@ impl Mixer {
             }
         }
 
+        self.report_level(mix_len);
+
         // For the benefit of test cases, send the raw un-RTP'd data.
         #[cfg(test)]
         let out = if let Some(OutputMode::Raw(_)) = &self.config.override_connection {
@@ -624,6 +1013,8 @@ impl Mixer {
             "FATAL: Too few bytes in self.packet for RTP header.\
                 (Blame: VOICE_PACKET_MAX?)",
         );
+        rtp.set_version(self.config.rtp_version);
+        rtp.set_payload_type(self.config.rtp_payload_type);
 
         let payload = rtp.payload_mut();
         let crypto_mode = conn.crypto_state.kind();
@@ -641,6 +1032,18 @@ impl Mixer {
             },
         };
 
+        conn.stats.record_payload_size(payload_len);
+
+        if !self.opus_sinks.is_empty() {
+            let frame = Bytes::copy_from_slice(&payload[TAG_SIZE..TAG_SIZE + payload_len]);
+            self.opus_sinks.retain(|sink| {
+                !matches!(
+                    sink.try_send(frame.clone()),
+                    Err(TrySendError::Disconnected(_))
+                )
+            });
+        }
+
         let final_payload_size = conn
             .crypto_state
             .write_packet_nonce(&mut rtp, TAG_SIZE + payload_len);
@@ -663,7 +1066,7 @@ impl Mixer {
     }
 
     #[inline]
-    pub(crate) fn send_packet(&self, packet: &[u8]) -> Result<()> {
+    pub(crate) fn send_packet(&mut self, packet: &[u8]) -> Result<()> {
         #[cfg(test)]
         let send_status = if let Some(OutputMode::Raw(tx)) = &self.config.override_connection {
             // This case has been handled before buffer clearing in `mix_and_build_packet`.
@@ -677,9 +1080,29 @@ impl Mixer {
         #[cfg(not(test))]
         let send_status = self._send_packet(packet);
 
-        send_status.or_else(Error::disarm_would_block)?;
+        match send_status {
+            Ok(()) => {
+                self.send_failures = 0;
+                Ok(())
+            },
+            Err(e) => {
+                self.send_failures += 1;
+                if let Some(conn) = &self.conn_active {
+                    conn.stats.record_dropped_packet();
+                }
 
-        Ok(())
+                if self.send_failures >= UDP_SEND_FAILURE_LIMIT {
+                    warn!(
+                        "{} consecutive voice packets failed to send, last error {:?}: reconnecting.",
+                        self.send_failures, e
+                    );
+                    self.send_failures = 0;
+                    Err(Error::SendStalled)
+                } else {
+                    e.disarm_would_block()
+                }
+            },
+        }
     }
 
     #[inline]
@@ -703,6 +1126,12 @@ impl Mixer {
             conn.udp_tx.send(packet)?;
         }
 
+        conn.stats.record_sent_packet(packet.len());
+        if let Some(rtp) = RtpPacket::new(packet) {
+            conn.stats
+                .record_sent_rtp(rtp.get_sequence().0 .0, rtp.get_timestamp().0 .0);
+        }
+
         Ok(())
     }
 
@@ -712,7 +1141,8 @@ impl Mixer {
             let now = now.unwrap_or_else(Instant::now);
             if now >= self.keepalive_deadline {
                 conn.udp_tx.send(&self.keepalive_packet)?;
-                self.keepalive_deadline += UDP_KEEPALIVE_GAP;
+                conn.stats.record_keepalive(now);
+                self.keepalive_deadline += self.keepalive_interval();
             }
         }
 
@@ -722,7 +1152,7 @@ impl Mixer {
     #[inline]
     pub(crate) fn send_gateway_speaking(&self) -> Result<()> {
         if let Some(ws) = &self.ws {
-            ws.send(WsMessage::Speaking(true))?;
+            ws.send(WsMessage::Speaking(self.speaking_flags))?;
         }
 
         Ok(())
@@ -735,7 +1165,7 @@ impl Mixer {
             // A full reconnect might cause an inner closed connection.
             // It's safer to leave the central task to clean this up and
             // pass the mixer a new channel.
-            drop(ws.send(WsMessage::Speaking(false)));
+            drop(ws.send(WsMessage::Speaking(SpeakingState::empty())));
         }
     }
 
@@ -756,18 +1186,29 @@ impl Mixer {
         // We *could* cache the number of live tracks separately, but that makes this
         // quite fragile given all the ways a user can alter the PlayMode.
         let mut num_live = 0;
-        let mut last_live_vol = 1.0;
+        let mut last_live_vol = Some(1.0);
         for track in &self.tracks {
             if track.playing.is_playing() {
                 num_live += 1;
-                last_live_vol = track.volume;
+                last_live_vol = track.mixed_volume();
             }
         }
-        let do_passthrough = num_live == 1 && (last_live_vol - 1.0).abs() < f32::EPSILON;
+        let do_passthrough = self.config.allow_passthrough
+            && num_live == 1
+            && last_live_vol.is_some_and(|vol| (vol - 1.0).abs() < f32::EPSILON)
+            && !self.config.mix_mode.remaps_channels()
+            && self.fade.is_none()
+            && self.meter.is_none();
+
+        std::mem::swap(&mut self.duck_levels, &mut self.next_duck_levels);
+        self.next_duck_levels.clear();
 
         let mut len = 0;
         for (i, track) in self.tracks.iter_mut().enumerate() {
             let vol = track.volume;
+            let (gain_target, mut gain) = (track.gain_target, track.gain);
+            let (ducking, mut duck_gain) = (track.ducking, track.duck_gain);
+            track.mix_cost = Duration::ZERO;
 
             // This specifically tries to get tracks who are "preparing",
             // so that event handlers and the like can all be fired without
@@ -815,17 +1256,39 @@ impl Mixer {
                 continue;
             }
 
+            if let (Some(target), None) = (gain_target, gain) {
+                gain = Some(target.gain(input).unwrap_or(1.0));
+            }
+
+            if let Some(DuckRole::Ducked(group, params)) = ducking {
+                duck_gain = params.step(duck_gain, self.duck_levels.get(group));
+            }
+
+            let was_blocked = mix_state.passthrough == Passthrough::Blocked;
+            let mix_start = Instant::now();
             let (mix_type, status) = mix_logic::mix_symph_indiv(
                 &mut self.symph_mix,
                 &mut self.resample_scratch,
                 input,
                 mix_state,
-                vol,
+                vol * gain.unwrap_or(1.0) * duck_gain,
+                self.config.resample_quality,
                 do_passthrough.then_some(&mut *opus_frame),
             );
+            let mix_cost = mix_start.elapsed();
+
+            if let (Some(DuckRole::Controller(group)), MixType::MixedPcm(1..)) = (ducking, mix_type)
+            {
+                let level = mix_logic::rms_of_ref(&input.decoder.last_decoded());
+                self.next_duck_levels
+                    .record(group, level * vol * gain.unwrap_or(1.0));
+            }
 
             let return_here = if let MixType::MixedPcm(pcm_len) = mix_type {
                 len = len.max(pcm_len);
+                if mix_state.passthrough == Passthrough::Active {
+                    mix_state.passthrough = Passthrough::Inactive;
+                }
                 false
             } else {
                 if mix_state.passthrough == Passthrough::Inactive {
@@ -835,26 +1298,52 @@ impl Mixer {
                 true
             };
 
+            if let (false, Some(block)) = (was_blocked, mix_state.passthrough_block) {
+                if !self.prevent_events {
+                    drop(self.interconnect.events.send(EventMessage::ChangeState(
+                        i,
+                        TrackStateChange::PassthroughBlocked(block),
+                    )));
+                }
+            }
+
+            track.mix_cost = mix_cost;
+            track.gain = gain;
+            track.duck_gain = duck_gain;
+
             // FIXME: allow Ended to trigger a seek/loop/revisit in the same mix cycle?
             // Would this be possible with special-casing to mark some inputs as fast
             // to recreate? Probably not doable in the general case.
-            match status {
-                MixStatus::Live => track.step_frame(),
-                MixStatus::Errored(e) =>
-                    track.playing = PlayMode::Errored(PlayError::Decode(e.into())),
-                MixStatus::Ended if track.do_loop() => {
-                    drop(self.track_handles[i].seek(Duration::default()));
-                    if !self.prevent_events {
-                        // position update is sent out later, when the seek concludes.
-                        drop(self.interconnect.events.send(EventMessage::ChangeState(
-                            i,
-                            TrackStateChange::Loops(track.loops, false),
-                        )));
+            let loop_now = match status {
+                MixStatus::Live => {
+                    track.step_frame();
+                    if track.reached_max_duration() {
+                        track.end();
+                        false
+                    } else {
+                        track.reached_loop_end() && track.do_loop()
                     }
                 },
+                MixStatus::Errored(e) => {
+                    track.playing = PlayMode::Errored(PlayError::Decode(e.into()));
+                    false
+                },
+                MixStatus::Ended if track.do_loop() => true,
                 MixStatus::Ended => {
                     track.end();
+                    false
                 },
+            };
+
+            if loop_now {
+                drop(self.track_handles[i].seek(track.loop_start()));
+                if !self.prevent_events {
+                    // position update is sent out later, when the seek concludes.
+                    drop(self.interconnect.events.send(EventMessage::ChangeState(
+                        i,
+                        TrackStateChange::Loops(track.loops, false),
+                    )));
+                }
             }
 
             // This needs to happen here due to borrow checker shenanigans.
@@ -866,3 +1355,899 @@ impl Mixer {
         MixType::MixedPcm(len)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Bitrate,
+        DecodeState,
+        EventMessage,
+        EventSender,
+        Interconnect,
+        MixType,
+        Mixer,
+        MixerMessage,
+        Passthrough,
+        PassthroughBlock,
+        PlayError,
+        PlayMode,
+        SpeakingState,
+        TrackStateChange,
+        WsMessage,
+        DEFAULT_BITRATE,
+        UDP_SEND_FAILURE_LIMIT,
+    };
+    use crate::{
+        constants::{
+            test_data::{FILE_WAV_TARGET, FILE_WEBM_TARGET},
+            MIN_SILENCE_FRAMES_ON_STOP,
+            MONO_FRAME_SIZE,
+            OPUS_PASSTHROUGH_STRIKE_LIMIT,
+            STEREO_FRAME_SIZE,
+            UDP_KEEPALIVE_GAP_MIN,
+            VOICE_PACKET_MAX,
+        },
+        driver::{Driver, MixMode, OutputMessage, OutputPacket},
+        input::File,
+        tracks::TrackHandle,
+        Config,
+    };
+    use crate::{
+        driver::retry::{Retry, Strategy},
+        events::{Event, EventContext, EventHandler, TrackEvent},
+        input::{
+            codecs::{CODEC_REGISTRY, PROBE},
+            AudioStream,
+            AudioStreamError,
+            Compose,
+            Input,
+        },
+        tracks::{ControlError, DuckRole, Ducking, LoopState, RemovalReason, Track},
+    };
+    use audiopus::Application;
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+    use symphonia_core::io::MediaSource;
+
+    async fn count_silence_on_stop(silence_frames_on_stop: u8) -> u8 {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.silence_frames_on_stop(silence_frames_on_stop));
+
+        let handle = driver.play(File::new(FILE_WAV_TARGET).into());
+        t_handle.ready_track(&handle, None).await;
+        handle.stop().unwrap();
+
+        // Once its silence is sent, an empty mixer goes idle and stops responding to ticks.
+        let mut n_silent = 0;
+        for _ in 0..20 {
+            t_handle.tick(1);
+            match tokio::time::timeout(Duration::from_millis(200), t_handle.recv_async()).await {
+                Ok(OutputPacket::Raw(msg)) if msg.is_explicit_silence() => n_silent += 1,
+                Ok(_) => {},
+                Err(_) => break,
+            }
+        }
+
+        n_silent
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn silence_frame_count_is_configurable() {
+        assert_eq!(count_silence_on_stop(8).await, 8);
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn silence_frame_count_has_a_minimum() {
+        assert_eq!(count_silence_on_stop(MIN_SILENCE_FRAMES_ON_STOP).await, 5);
+        assert_eq!(
+            count_silence_on_stop(MIN_SILENCE_FRAMES_ON_STOP + 1).await,
+            6
+        );
+        assert_eq!(
+            count_silence_on_stop(MIN_SILENCE_FRAMES_ON_STOP - 1).await,
+            5
+        );
+        assert_eq!(count_silence_on_stop(0).await, 5);
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn passthrough_can_be_disabled() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.allow_passthrough(false));
+
+        let handle = driver.play(File::new(FILE_WEBM_TARGET).into());
+        t_handle.ready_track(&handle, None).await;
+        t_handle.tick(1);
+
+        let pkt = t_handle.recv_async().await;
+        assert!(pkt.raw().unwrap().is_mixed());
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn stop_with_fade_removes_tracks_once_faded() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config);
+
+        let handle = driver.play(File::new(FILE_WAV_TARGET).into());
+        t_handle.ready_track(&handle, None).await;
+        t_handle.spawn_ticker();
+
+        driver.stop_with_fade(Duration::from_millis(100)).await;
+
+        assert!(driver.tracks().await.is_empty());
+        assert!(handle.get_info().await.is_err());
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn shutdown_waits_for_trailing_silence() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config);
+
+        let handle = driver.play(File::new(FILE_WAV_TARGET).into());
+        t_handle.ready_track(&handle, None).await;
+        t_handle.tick(1);
+        assert!(t_handle.recv_async().await.raw().unwrap().is_mixed());
+
+        let shutdown = tokio::spawn(driver.shutdown());
+
+        let mut n_silent = 0;
+        while n_silent < Config::default().silence_frames_on_stop {
+            assert!(!shutdown.is_finished());
+
+            t_handle.tick(1);
+            // Audio may still be sent until the mixer sees the shutdown request.
+            if t_handle
+                .recv_async()
+                .await
+                .raw()
+                .unwrap()
+                .is_explicit_silence()
+            {
+                n_silent += 1;
+            } else {
+                assert_eq!(n_silent, 0);
+            }
+        }
+
+        shutdown.await.unwrap();
+        assert!(handle.get_info().await.is_err());
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn disposal_hooks_report_final_state() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config);
+        let (tx, rx) = flume::unbounded();
+
+        let tx_stop = tx.clone();
+        let stopped = driver.play(
+            Track::from(File::new(FILE_WAV_TARGET))
+                .on_dispose(move |state, reason| drop(tx_stop.send((1, state, reason)))),
+        );
+        let cleared = driver.play(
+            Track::from(File::new(FILE_WAV_TARGET))
+                .on_dispose(move |state, reason| drop(tx.send((2, state, reason)))),
+        );
+        t_handle
+            .ready_track(&cleared, Some(Duration::from_millis(1)))
+            .await;
+        t_handle.spawn_ticker();
+
+        stopped.stop().unwrap();
+        let (id, state, reason) = rx.recv_async().await.unwrap();
+        assert_eq!(id, 1);
+        assert_eq!(state.playing, PlayMode::Stop);
+        assert_eq!(reason, RemovalReason::Finished);
+
+        driver.stop();
+        let (id, state, reason) = rx.recv_async().await.unwrap();
+        assert_eq!(id, 2);
+        assert_eq!(state.playing, PlayMode::Play);
+        assert_eq!(reason, RemovalReason::Cleared);
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn meter_reports_frame_peaks_and_silence() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config);
+        let (meter_tx, meter_rx) = flume::unbounded();
+        driver.set_meter(meter_tx);
+
+        let handle = driver.play(File::new(FILE_WAV_TARGET).into());
+        t_handle.ready_track(&handle, None).await;
+
+        // Frames are mixed (and metered) up to a tick before they are sent.
+        let mut peaks = vec![];
+        for _ in 0..5 {
+            t_handle.tick(1);
+            let Some(OutputMessage::Mixed(samples)) = t_handle.recv_async().await.raw().cloned()
+            else {
+                panic!("Expected mixed audio.");
+            };
+            peaks.push(samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs())));
+        }
+        let levels = meter_rx.drain().collect::<Vec<_>>();
+        assert!(peaks.iter().any(|peak| *peak > 0.0));
+        assert!(levels.windows(peaks.len()).any(|w| w == peaks));
+
+        driver.mute(true);
+        loop {
+            t_handle.tick(1);
+            if t_handle
+                .recv_async()
+                .await
+                .raw()
+                .is_some_and(OutputMessage::is_explicit_silence)
+            {
+                break;
+            }
+        }
+        let level = meter_rx.drain().last().unwrap();
+        assert!(level.abs() < f32::EPSILON);
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn raw_output_honours_mix_mode_layout() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let config = config.mix_mode(MixMode::Mono);
+        let mut driver = Driver::new(config.clone());
+
+        let handle = driver.play(File::new(FILE_WAV_TARGET).into());
+        t_handle.ready_track(&handle, None).await;
+
+        t_handle.tick(1);
+        let Some(OutputMessage::Mixed(samples)) = t_handle.recv_async().await.raw().cloned() else {
+            panic!("Expected mixed audio.");
+        };
+        assert_eq!(samples.len(), MONO_FRAME_SIZE);
+
+        driver.set_config(config.mix_mode(MixMode::Stereo));
+        loop {
+            t_handle.tick(1);
+            let Some(OutputMessage::Mixed(samples)) = t_handle.recv_async().await.raw().cloned()
+            else {
+                panic!("Expected mixed audio.");
+            };
+            if samples.len() == STEREO_FRAME_SIZE {
+                break;
+            }
+            assert_eq!(samples.len(), MONO_FRAME_SIZE);
+        }
+    }
+
+    struct FlakySource {
+        failures: usize,
+        file: File<&'static str>,
+    }
+
+    #[async_trait::async_trait]
+    impl Compose for FlakySource {
+        fn create(&mut self) -> Result<AudioStream<Box<dyn MediaSource>>, AudioStreamError> {
+            Err(AudioStreamError::Unsupported)
+        }
+
+        async fn create_async(
+            &mut self,
+        ) -> Result<AudioStream<Box<dyn MediaSource>>, AudioStreamError> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                Err(AudioStreamError::RetryIn(Duration::ZERO))
+            } else {
+                self.file.create_async().await
+            }
+        }
+
+        fn should_create_async(&self) -> bool {
+            true
+        }
+    }
+
+    struct CountRetries(Arc<AtomicUsize>);
+
+    #[async_trait::async_trait]
+    impl EventHandler for CountRetries {
+        async fn act(&self, _ctx: &EventContext<'_>) -> Option<Event> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            None
+        }
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn transient_create_failures_are_retried() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.input_retry(Retry {
+            strategy: Strategy::Every(Duration::ZERO),
+            retry_limit: Some(2),
+        }));
+
+        let source = FlakySource {
+            failures: 2,
+            file: File::new(FILE_WAV_TARGET),
+        };
+        let handle = driver.play(Input::Lazy(Box::new(source)).into());
+
+        let retries = Arc::new(AtomicUsize::new(0));
+        handle
+            .add_event(
+                Event::Track(TrackEvent::Retrying),
+                CountRetries(retries.clone()),
+            )
+            .unwrap();
+
+        t_handle.ready_track(&handle, None).await;
+        assert_eq!(retries.load(Ordering::SeqCst), 2);
+    }
+
+    struct HangingSource;
+
+    #[async_trait::async_trait]
+    impl Compose for HangingSource {
+        fn create(&mut self) -> Result<AudioStream<Box<dyn MediaSource>>, AudioStreamError> {
+            Err(AudioStreamError::Unsupported)
+        }
+
+        async fn create_async(
+            &mut self,
+        ) -> Result<AudioStream<Box<dyn MediaSource>>, AudioStreamError> {
+            std::future::pending().await
+        }
+
+        fn should_create_async(&self) -> bool {
+            true
+        }
+    }
+
+    struct ReportError(flume::Sender<PlayMode>);
+
+    #[async_trait::async_trait]
+    impl EventHandler for ReportError {
+        async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+            if let EventContext::Track(&[(state, _)]) = ctx {
+                drop(self.0.send(state.playing.clone()));
+            }
+            None
+        }
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn slow_inputs_time_out() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.input_ready_timeout(Some(Duration::from_millis(100))));
+
+        let handle = driver.play(Input::Lazy(Box::new(HangingSource)).into());
+        let (tx, rx) = flume::bounded(1);
+        handle
+            .add_event(Event::Track(TrackEvent::Error), ReportError(tx))
+            .unwrap();
+        t_handle.spawn_ticker();
+
+        assert!(matches!(
+            rx.recv_async().await.unwrap(),
+            PlayMode::Errored(PlayError::ReadyTimeout)
+        ));
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn tracks_lists_current_handles() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config);
+        t_handle.spawn_ticker();
+
+        let first = driver.play(File::new(FILE_WAV_TARGET).into());
+        let second = driver.play(File::new(FILE_WAV_TARGET).into());
+        let uuids: Vec<_> = driver
+            .tracks()
+            .await
+            .iter()
+            .map(TrackHandle::uuid)
+            .collect();
+        assert_eq!(uuids, vec![first.uuid(), second.uuid()]);
+
+        let only = driver.play_only(File::new(FILE_WAV_TARGET).into());
+        let uuids: Vec<_> = driver
+            .tracks()
+            .await
+            .iter()
+            .map(TrackHandle::uuid)
+            .collect();
+        assert_eq!(uuids, vec![only.uuid()]);
+    }
+
+    #[test]
+    fn opus_sinks_receive_encoded_frames() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (mut mixer, _listeners) = Mixer::test_with_float(1, rt.handle().clone(), false);
+        let mut packet = [0u8; VOICE_PACKET_MAX];
+
+        let (sink_tx, sink_rx) = flume::unbounded();
+        let (full_tx, full_rx) = flume::bounded(1);
+        let (gone_tx, gone_rx) = flume::unbounded();
+        for sink in [sink_tx, full_tx, gone_tx] {
+            mixer.handle_message(MixerMessage::AddOpusSink(sink), &mut packet);
+        }
+        drop(gone_rx);
+
+        for _ in 0..3 {
+            mixer.audio_commands_events().unwrap();
+            let len = mixer.mix_and_build_packet(&mut packet).unwrap();
+            let frame = sink_rx.try_recv().unwrap();
+            assert!(!frame.is_empty());
+            assert!(frame.len() < len);
+        }
+
+        // Full sinks drop new frames, and disconnected sinks are removed.
+        assert_eq!(full_rx.drain().count(), 1);
+        assert_eq!(mixer.opus_sinks.len(), 2);
+
+        mixer.handle_message(MixerMessage::RemoveOpusSinks, &mut packet);
+        mixer.audio_commands_events().unwrap();
+        mixer.mix_and_build_packet(&mut packet).unwrap();
+        assert!(sink_rx.is_empty());
+    }
+
+    #[test]
+    fn passthrough_bitrate_is_matched_on_mix() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (mut mixer, _listeners) = Mixer::mock(rt.handle().clone(), false);
+        mixer.config = Arc::new(Config::default().match_passthrough_bitrate(true));
+
+        // 80B Opus frames, every 20ms: 32kbps.
+        for _ in 0..10 {
+            mixer.update_passthrough_bitrate(MixType::Passthrough(80));
+        }
+        mixer.update_passthrough_bitrate(MixType::MixedPcm(0));
+        assert_eq!(mixer.encoder.bitrate().unwrap(), DEFAULT_BITRATE);
+
+        mixer.update_passthrough_bitrate(MixType::MixedPcm(960));
+        assert_eq!(
+            mixer.encoder.bitrate().unwrap(),
+            Bitrate::BitsPerSecond(32_000)
+        );
+        assert_eq!(mixer.bitrate, DEFAULT_BITRATE);
+    }
+
+    #[test]
+    fn opus_options_survive_encoder_rebuild() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (mut mixer, _listeners) = Mixer::mock(rt.handle().clone(), false);
+        let mut packet = [0u8; VOICE_PACKET_MAX];
+        assert!(!mixer.encoder.inband_fec().unwrap());
+        assert!(!mixer.encoder.dtx().unwrap());
+
+        let config = Config::default().opus_fec(true).opus_dtx(true);
+        mixer.handle_message(MixerMessage::SetConfig(config), &mut packet);
+        assert!(mixer.encoder.inband_fec().unwrap());
+        assert!(mixer.encoder.dtx().unwrap());
+
+        mixer.handle_message(MixerMessage::RebuildEncoder, &mut packet);
+        assert!(mixer.encoder.inband_fec().unwrap());
+        assert!(mixer.encoder.dtx().unwrap());
+    }
+
+    #[test]
+    fn opus_complexity_is_applied_and_restored() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (mut mixer, _listeners) = Mixer::mock(rt.handle().clone(), false);
+        let mut packet = [0u8; VOICE_PACKET_MAX];
+        let default_complexity = mixer.encoder.complexity().unwrap();
+
+        let config = Config::default().opus_complexity(Some(2));
+        mixer.handle_message(MixerMessage::SetConfig(config), &mut packet);
+        assert_eq!(mixer.encoder.complexity().unwrap(), 2);
+
+        let config = Config::default().opus_complexity(Some(200));
+        mixer.handle_message(MixerMessage::SetConfig(config), &mut packet);
+        assert_eq!(mixer.encoder.complexity().unwrap(), 10);
+
+        mixer.handle_message(MixerMessage::SetConfig(Config::default()), &mut packet);
+        assert_eq!(mixer.encoder.complexity().unwrap(), default_complexity);
+    }
+
+    #[test]
+    fn repeated_send_failures_trigger_reconnect() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (mut mixer, _listeners) = Mixer::mock(rt.handle().clone(), false);
+
+        // An unconnected socket cannot send without a destination.
+        let conn = mixer.conn_active.as_mut().unwrap();
+        conn.udp_tx = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let stats = conn.stats.clone();
+
+        for i in 1..=UDP_SEND_FAILURE_LIMIT {
+            let res = mixer.send_packet(&[0u8; 16]);
+            let stalled = res.is_err_and(|e| e.should_trigger_connect());
+            assert_eq!(stalled, i == UDP_SEND_FAILURE_LIMIT);
+        }
+
+        assert_eq!(stats.dropped_packets(), UDP_SEND_FAILURE_LIMIT as u64);
+        assert_eq!(mixer.send_failures, 0);
+    }
+
+    #[test]
+    fn sent_rtp_counters_are_recorded() {
+        use discortp::rtp::MutableRtpPacket;
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (mut mixer, _listeners) = Mixer::mock(rt.handle().clone(), false);
+
+        let stats = mixer.conn_active.as_ref().unwrap().stats.clone();
+        stats.mark_connected(0);
+
+        let mut packet = [0u8; 16];
+        let mut rtp = MutableRtpPacket::new(&mut packet[..]).unwrap();
+        rtp.set_sequence(1234.into());
+        rtp.set_timestamp(5678.into());
+        mixer.send_packet(&packet).unwrap();
+
+        let snapshot = stats.snapshot().unwrap();
+        let counters = snapshot.last_sent_rtp.unwrap();
+        assert_eq!(counters.sequence, 1234);
+        assert_eq!(counters.timestamp, 5678);
+        assert_eq!(snapshot.packets_sent, 1);
+        assert_eq!(snapshot.bytes_sent, packet.len() as u64);
+    }
+
+    #[test]
+    fn controllers_duck_tracks_in_their_group() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (mut mixer, _listeners) = Mixer::mock(rt.handle().clone(), false);
+        let mut packet = [0u8; VOICE_PACKET_MAX];
+
+        let ducking = Ducking::default()
+            .ratio(0.5)
+            .attack(Duration::from_millis(40));
+        let roles = [
+            DuckRole::Controller(1),
+            DuckRole::Ducked(1, ducking),
+            DuckRole::Ducked(2, ducking),
+        ];
+        for role in roles {
+            let input: Input = File::new(FILE_WAV_TARGET).into();
+            let input = rt
+                .block_on(input.make_playable_async(&CODEC_REGISTRY, &PROBE))
+                .unwrap();
+            let (_, ctx) = Track::from(input).ducking(role).into_context();
+            mixer.add_track(ctx).unwrap();
+        }
+
+        // Ducked tracks follow their controllers' level from the previous frame.
+        for _ in 0..4 {
+            mixer.mix_and_build_packet(&mut packet).unwrap();
+        }
+
+        assert!((mixer.tracks[1].duck_gain - 0.5).abs() < f32::EPSILON);
+        assert!((mixer.tracks[2].duck_gain - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn loop_region_seeks_back_to_its_start() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (mut mixer, _listeners) = Mixer::mock(rt.handle().clone(), false);
+        let mut packet = [0u8; VOICE_PACKET_MAX];
+
+        let input: Input = File::new(FILE_WAV_TARGET).into();
+        let input = rt
+            .block_on(input.make_playable_async(&CODEC_REGISTRY, &PROBE))
+            .unwrap();
+        let (handle, ctx) = Track::from(input).into_context();
+        mixer.add_track(ctx).unwrap();
+
+        let start = Duration::from_millis(20);
+        let end = Duration::from_millis(60);
+        assert!(matches!(
+            handle.set_loop_region(Some((end, start))),
+            Err(ControlError::InvalidLoopRegion)
+        ));
+        handle.set_loop_region(Some((start, end))).unwrap();
+        handle.loop_for(1).unwrap();
+
+        for _ in 0..3 {
+            mixer.audio_commands_events().unwrap();
+            mixer.mix_and_build_packet(&mut packet).unwrap();
+        }
+        assert_eq!(mixer.tracks[0].loops, LoopState::Finite(0));
+
+        // Wait for the loop's seek to complete.
+        for _ in 0..100 {
+            mixer.audio_commands_events().unwrap();
+            mixer.mix_and_build_packet(&mut packet).unwrap();
+            if mixer.tracks[0].position < end {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(mixer.tracks[0].position >= start && mixer.tracks[0].position < end);
+
+        // With no loops left, playback continues past the region.
+        for _ in 0..3 {
+            mixer.audio_commands_events().unwrap();
+            mixer.mix_and_build_packet(&mut packet).unwrap();
+        }
+        assert!(mixer.tracks[0].position > end);
+    }
+
+    #[test]
+    fn tracks_end_at_max_duration() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (mut mixer, _listeners) = Mixer::mock(rt.handle().clone(), false);
+        let mut packet = [0u8; VOICE_PACKET_MAX];
+
+        let input: Input = File::new(FILE_WAV_TARGET).into();
+        let input = rt
+            .block_on(input.make_playable_async(&CODEC_REGISTRY, &PROBE))
+            .unwrap();
+        let (handle, ctx) = Track::from(input)
+            .loops(LoopState::Infinite)
+            .max_duration(Duration::from_millis(60))
+            .into_context();
+        mixer.add_track(ctx).unwrap();
+
+        for _ in 0..2 {
+            mixer.audio_commands_events().unwrap();
+            mixer.mix_and_build_packet(&mut packet).unwrap();
+        }
+        assert!(mixer.tracks[0].playing.is_playing());
+
+        // Seeking does not change how much audio has been played.
+        drop(handle.seek(Duration::from_secs(1)));
+        for _ in 0..100 {
+            mixer.audio_commands_events().unwrap();
+            mixer.mix_and_build_packet(&mut packet).unwrap();
+            if mixer.tracks[0].position >= Duration::from_secs(1) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        assert_eq!(mixer.tracks[0].play_time, Duration::from_millis(60));
+        assert!(mixer.tracks[0].playing.is_done());
+
+        mixer.audio_commands_events().unwrap();
+        assert!(mixer.tracks.is_empty());
+    }
+
+    #[test]
+    fn passthrough_block_records_offending_frame() {
+        let mut state = DecodeState::default();
+        let frame = PassthroughBlock {
+            len: 40,
+            samples: Some(MONO_FRAME_SIZE / 2),
+        };
+
+        for _ in 0..OPUS_PASSTHROUGH_STRIKE_LIMIT {
+            assert!(!state.record_and_check_passthrough_strike_final(frame, false));
+        }
+        assert_eq!(state.passthrough_block, None);
+
+        assert!(state.record_and_check_passthrough_strike_final(frame, false));
+        assert_eq!(state.passthrough, Passthrough::Blocked);
+        assert_eq!(state.passthrough_block, Some(frame));
+    }
+
+    #[test]
+    fn tracks_over_the_limit_end_with_an_error() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (mut mixer, listeners) = Mixer::mock(rt.handle().clone(), false);
+        mixer.config = Arc::new(Config::default().max_tracks(Some(1)));
+
+        let (first, ctx) = Track::from(Input::from(&[][..])).into_context();
+        mixer.add_track(ctx).unwrap();
+        let (_second, ctx) = Track::from(Input::from(&[][..])).into_context();
+        mixer.add_track(ctx).unwrap();
+
+        mixer.audio_commands_events().unwrap();
+        assert_eq!(mixer.tracks.len(), 1);
+        assert_eq!(mixer.track_handles[0].uuid(), first.uuid());
+
+        let removed = listeners.1.drain().find_map(|msg| match msg {
+            EventMessage::ChangeState(i, TrackStateChange::Mode(mode)) => Some((i, mode)),
+            _ => None,
+        });
+        assert!(matches!(
+            removed,
+            Some((1, PlayMode::Errored(PlayError::TrackLimit)))
+        ));
+    }
+
+    #[test]
+    fn track_position_survives_reconnect() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (mut mixer, _listeners) = Mixer::mock(rt.handle().clone(), false);
+        let mut packet = [0u8; VOICE_PACKET_MAX];
+
+        let input: Input = File::new(FILE_WAV_TARGET).into();
+        let input = rt
+            .block_on(input.make_playable_async(&CODEC_REGISTRY, &PROBE))
+            .unwrap();
+        let (_handle, ctx) = Track::from(input).into_context();
+        mixer.add_track(ctx).unwrap();
+
+        for _ in 0..5 {
+            mixer.audio_commands_events().unwrap();
+            mixer.mix_and_build_packet(&mut packet).unwrap();
+        }
+        let position = mixer.tracks[0].position;
+        assert_eq!(position, Duration::from_millis(100));
+
+        // A full reconnect drops the connection and rebuilds the event thread's
+        // view of each track, before a new connection is handed over.
+        let conn = mixer.conn_active.take().unwrap();
+        mixer.handle_message(MixerMessage::DropConn, &mut packet);
+
+        let (evt_tx, evt_rx) = flume::unbounded();
+        let interconnect = Interconnect {
+            core: mixer.interconnect.core.clone(),
+            events: EventSender::new(evt_tx, None),
+            mixer: mixer.interconnect.mixer.clone(),
+        };
+        mixer.handle_message(MixerMessage::ReplaceInterconnect(interconnect), &mut packet);
+        let Ok(EventMessage::AddTrack(_, state, _)) = evt_rx.try_recv() else {
+            panic!("Expected the track to be re-added to the event thread.");
+        };
+        assert_eq!(state.position, position);
+
+        mixer.handle_message(MixerMessage::SetConn(conn, 1), &mut packet);
+        mixer.audio_commands_events().unwrap();
+        mixer.mix_and_build_packet(&mut packet).unwrap();
+        assert_eq!(
+            mixer.tracks[0].position,
+            position + Duration::from_millis(20)
+        );
+    }
+
+    #[test]
+    fn keepalive_interval_is_configurable() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (mut mixer, _listeners) = Mixer::mock(rt.handle().clone(), false);
+
+        mixer.config = Arc::new(Config::default().keepalive_interval(Duration::from_secs(2)));
+        mixer.update_keepalive(1);
+        assert_eq!(
+            mixer.keepalive_deadline,
+            mixer.deadline + Duration::from_secs(2)
+        );
+
+        // Intervals are kept above a minimum.
+        mixer.config = Arc::new(Config::default().keepalive_interval(Duration::ZERO));
+        mixer.update_keepalive(1);
+        assert_eq!(
+            mixer.keepalive_deadline,
+            mixer.deadline + UDP_KEEPALIVE_GAP_MIN
+        );
+    }
+
+    #[test]
+    fn speaking_flags_are_sent_to_gateway() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (mut mixer, _listeners) = Mixer::mock(rt.handle().clone(), false);
+        let mut packet = [0u8; VOICE_PACKET_MAX];
+
+        let (ws_tx, ws_rx) = flume::unbounded();
+        mixer.handle_message(MixerMessage::Ws(Some(ws_tx)), &mut packet);
+        assert!(matches!(
+            ws_rx.try_recv(),
+            Ok(WsMessage::Speaking(flags)) if flags == SpeakingState::MICROPHONE
+        ));
+
+        // Idle mixers store the flags without claiming to speak.
+        let priority = SpeakingState::MICROPHONE | SpeakingState::PRIORITY;
+        mixer.handle_message(MixerMessage::SetSpeakingFlags(priority), &mut packet);
+        assert!(ws_rx.is_empty());
+        assert_eq!(mixer.speaking_flags, priority);
+
+        // Mixers sending audio (or trailing silence) update Discord immediately.
+        mixer.silence_frames = 1;
+        mixer.handle_message(MixerMessage::SetSpeakingFlags(priority), &mut packet);
+        assert!(matches!(
+            ws_rx.try_recv(),
+            Ok(WsMessage::Speaking(flags)) if flags == priority
+        ));
+
+        mixer.send_gateway_not_speaking();
+        assert!(matches!(
+            ws_rx.try_recv(),
+            Ok(WsMessage::Speaking(flags)) if flags.is_empty()
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "receive")]
+    fn receiver_reports_adapt_bitrate() {
+        use crate::{
+            driver::{tasks::message::EventMessage, AdaptiveBitrate},
+            events::CoreContext,
+        };
+        use discortp::{rtcp::report::ReportBlock, rtp::MutableRtpPacket};
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (mut mixer, (_core_rx, event_rx, _udp_rx)) = Mixer::mock(rt.handle().clone(), false);
+        let mut packet = [0u8; VOICE_PACKET_MAX];
+        MutableRtpPacket::new(&mut packet[..])
+            .unwrap()
+            .set_ssrc(1234);
+
+        let config = mixer
+            .config
+            .as_ref()
+            .clone()
+            .adaptive_bitrate(Some(AdaptiveBitrate::new(64_000, 128_000)));
+        mixer.handle_message(MixerMessage::SetConfig(config), &mut packet);
+        mixer.handle_message(
+            MixerMessage::SetBitrate(Bitrate::BitsPerSecond(128_000)),
+            &mut packet,
+        );
+
+        let report = |ssrc, fraction_lost| {
+            MixerMessage::ReceiverReport(vec![ReportBlock {
+                ssrc,
+                fraction_lost,
+                cumulative_pkts_lost: 0,
+                cycles: 0,
+                sequence: 0,
+                interarrival_jitter: 0,
+                last_sr_timestamp: 0,
+                last_sr_delay: 0,
+                payload: vec![],
+            }])
+        };
+
+        // Loss reported against other senders is ignored.
+        for _ in 0..4 {
+            mixer.handle_message(report(4321, 64), &mut packet);
+        }
+        assert_eq!(
+            mixer.encoder.bitrate().unwrap(),
+            Bitrate::BitsPerSecond(128_000)
+        );
+
+        mixer.handle_message(report(1234, 64), &mut packet);
+        mixer.handle_message(report(1234, 64), &mut packet);
+        assert_eq!(
+            mixer.encoder.bitrate().unwrap(),
+            Bitrate::BitsPerSecond(96_000)
+        );
+
+        let change = event_rx.try_iter().find_map(|msg| match msg {
+            EventMessage::FireCoreEvent(CoreContext::BitrateChange(data)) => Some(data),
+            _ => None,
+        });
+        let change = change.expect("bitrate change event should be fired");
+        assert_eq!(change.bitrate, 96_000);
+        assert_eq!(change.previous_bitrate, 128_000);
+        assert_eq!(change.fraction_lost, 64);
+    }
+
+    #[test]
+    fn opus_application_rebuilds_encoder() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (mut mixer, _listeners) = Mixer::mock(rt.handle().clone(), false);
+        let mut packet = [0u8; VOICE_PACKET_MAX];
+        assert_eq!(mixer.encoder.application().unwrap(), Application::Audio);
+
+        let config = Config::default()
+            .opus_application(Application::Voip)
+            .opus_fec(true);
+        mixer.handle_message(MixerMessage::SetConfig(config), &mut packet);
+        assert_eq!(mixer.encoder.application().unwrap(), Application::Voip);
+        assert!(mixer.encoder.inband_fec().unwrap());
+
+        mixer.handle_message(MixerMessage::RebuildEncoder, &mut packet);
+        assert_eq!(mixer.encoder.application().unwrap(), Application::Voip);
+    }
+}
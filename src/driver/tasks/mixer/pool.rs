@@ -72,7 +72,7 @@ impl BlockyTaskPool {
                 self.parse(config, callback, LiveInput::Raw(o), Some(rec), seek_time);
             },
             Err(e) => {
-                drop(callback.send(MixerInputResultMessage::CreateErr(e.into())));
+                drop(callback.send(MixerInputResultMessage::CreateErr(e.into(), Some(rec))));
             },
         }
     }
@@ -126,6 +126,15 @@ impl BlockyTaskPool {
                 pool_clone.create(callback, Input::Lazy(rec), Some(seek_time), config);
             },
             _ => {
+                // Timestamps are only meaningful relative to the track being played,
+                // which may not have been known when the seek was requested.
+                let seek_time = match seek_time {
+                    SeekTo::TimeStamp { ts, .. } => SeekTo::TimeStamp {
+                        ts,
+                        track_id: input.track_id,
+                    },
+                    t @ SeekTo::Time { .. } => t,
+                };
                 let seek_result = input
                     .format
                     .seek(SeekMode::Accurate, copy_seek_to(&seek_time));
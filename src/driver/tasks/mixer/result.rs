@@ -31,6 +31,7 @@ pub enum InputReadyingError {
     Creation(Arc<AudioStreamError>),
     Seeking(Arc<SymphoniaError>),
     Dropped,
+    TimedOut,
     Waiting,
     NeedsSeek(SeekRequest),
 }
@@ -41,6 +42,7 @@ impl InputReadyingError {
             Self::Parsing(e) => Some(PlayError::Parse(e.clone())),
             Self::Creation(e) => Some(PlayError::Create(e.clone())),
             Self::Seeking(e) => Some(PlayError::Seek(e.clone())),
+            Self::TimedOut => Some(PlayError::ReadyTimeout),
             _ => None,
         }
     }
@@ -1,16 +1,32 @@
 use crate::{
-    constants::OPUS_PASSTHROUGH_STRIKE_LIMIT,
-    driver::tasks::message::*,
+    constants::{
+        OPUS_PASSTHROUGH_STRIKE_LIMIT,
+        RESAMPLE_OUTPUT_FRAME_SIZE,
+        SAMPLE_RATE_RAW,
+        TIMESTEP_LENGTH,
+    },
+    driver::{tasks::message::*, ResampleQuality},
     input::{Compose, Input, LiveInput, Metadata, Parsed},
-    tracks::{ReadyState, SeekRequest},
+    tracks::{Passthrough, PassthroughBlock, ReadyState, SeekRequest},
 };
-use flume::Receiver;
-use rubato::FftFixedOut;
-use std::time::Instant;
+use flume::{Receiver, Sender};
+use rubato::{
+    FastFixedOut,
+    FftFixedOut,
+    PolynomialDegree,
+    ResampleResult,
+    Resampler,
+    SincFixedOut,
+    SincInterpolationParameters,
+    SincInterpolationType,
+    WindowFunction,
+};
+use std::time::{Duration, Instant};
 
 pub enum InputState {
     NotReady(Input),
     Preparing(PreparingInfo),
+    Retrying(RetryingInfo),
     Ready(Parsed, Option<Box<dyn Compose>>),
 }
 
@@ -26,7 +42,7 @@ impl InputState {
     pub fn ready_state(&self) -> ReadyState {
         match self {
             Self::NotReady(_) => ReadyState::Uninitialised,
-            Self::Preparing(_) => ReadyState::Preparing,
+            Self::Preparing(_) | Self::Retrying(_) => ReadyState::Preparing,
             Self::Ready(_, _) => ReadyState::Playable,
         }
     }
@@ -45,7 +61,6 @@ impl From<Input> for InputState {
 }
 
 pub struct PreparingInfo {
-    #[allow(dead_code)]
     /// Time this request was fired.
     pub time: Instant,
     /// Used to handle seek requests fired while a track was being created (or a seek was in progress).
@@ -54,11 +69,129 @@ pub struct PreparingInfo {
     pub callback: Receiver<MixerInputResultMessage>,
 }
 
+pub struct RetryingInfo {
+    /// Source to recreate the input from, once `resume_at` has passed.
+    pub compose: Box<dyn Compose>,
+    /// Time at which creation may next be attempted.
+    pub resume_at: Instant,
+}
+
+/// A track's resampler, alongside the input format and quality it was built for.
+pub struct ResampleState {
+    pub channels: usize,
+    pub rate: u32,
+    pub quality: ResampleQuality,
+    pub resampler: MixResampler,
+    pub output: Vec<Vec<f32>>,
+}
+
+impl ResampleState {
+    pub fn new(channels: usize, rate: u32, quality: ResampleQuality) -> Self {
+        let resampler = MixResampler::new(channels, rate, quality);
+        let output = resampler.output_buffer_allocate();
+
+        Self {
+            channels,
+            rate,
+            quality,
+            resampler,
+            output,
+        }
+    }
+
+    pub fn matches(&self, channels: usize, rate: u32, quality: ResampleQuality) -> bool {
+        self.channels == channels && self.rate == rate && self.quality == quality
+    }
+}
+
+/// Resamplers offered by each [`ResampleQuality`], producing a fixed number of
+/// 48kHz output frames per call.
+pub enum MixResampler {
+    Fast(FastFixedOut<f32>),
+    Balanced(FftFixedOut<f32>),
+    High(SincFixedOut<f32>),
+}
+
+impl MixResampler {
+    pub fn new(channels: usize, rate: u32, quality: ResampleQuality) -> Self {
+        let ratio = SAMPLE_RATE_RAW as f64 / f64::from(rate);
+
+        // TODO: integ. error handling here.
+        match quality {
+            ResampleQuality::Fast => Self::Fast(
+                FastFixedOut::new(
+                    ratio,
+                    1.0,
+                    PolynomialDegree::Linear,
+                    RESAMPLE_OUTPUT_FRAME_SIZE,
+                    channels,
+                )
+                .expect("Failed to create resampler."),
+            ),
+            ResampleQuality::High => Self::High(
+                SincFixedOut::new(
+                    ratio,
+                    1.0,
+                    SincInterpolationParameters {
+                        sinc_len: 256,
+                        f_cutoff: 0.95,
+                        oversampling_factor: 128,
+                        interpolation: SincInterpolationType::Cubic,
+                        window: WindowFunction::BlackmanHarris2,
+                    },
+                    RESAMPLE_OUTPUT_FRAME_SIZE,
+                    channels,
+                )
+                .expect("Failed to create resampler."),
+            ),
+            ResampleQuality::Balanced => Self::Balanced(
+                FftFixedOut::new(
+                    rate as usize,
+                    SAMPLE_RATE_RAW,
+                    RESAMPLE_OUTPUT_FRAME_SIZE,
+                    4,
+                    channels,
+                )
+                .expect("Failed to create resampler."),
+            ),
+        }
+    }
+
+    pub fn input_frames_next(&self) -> usize {
+        match self {
+            Self::Fast(r) => r.input_frames_next(),
+            Self::Balanced(r) => r.input_frames_next(),
+            Self::High(r) => r.input_frames_next(),
+        }
+    }
+
+    pub fn output_buffer_allocate(&self) -> Vec<Vec<f32>> {
+        match self {
+            Self::Fast(r) => r.output_buffer_allocate(true),
+            Self::Balanced(r) => r.output_buffer_allocate(true),
+            Self::High(r) => r.output_buffer_allocate(true),
+        }
+    }
+
+    pub fn process_into_buffer<V: AsRef<[f32]>>(
+        &mut self,
+        wave_in: &[V],
+        wave_out: &mut [Vec<f32>],
+    ) -> ResampleResult<(usize, usize)> {
+        match self {
+            Self::Fast(r) => r.process_into_buffer(wave_in, wave_out, None),
+            Self::Balanced(r) => r.process_into_buffer(wave_in, wave_out, None),
+            Self::High(r) => r.process_into_buffer(wave_in, wave_out, None),
+        }
+    }
+}
+
 pub struct DecodeState {
     pub inner_pos: usize,
-    pub resampler: Option<(usize, FftFixedOut<f32>, Vec<Vec<f32>>)>,
+    pub resampler: Option<ResampleState>,
     pub passthrough: Passthrough,
     pub passthrough_violations: u8,
+    pub passthrough_block: Option<PassthroughBlock>,
 }
 
 impl DecodeState {
@@ -67,11 +200,16 @@ impl DecodeState {
         self.resampler = None;
     }
 
-    pub fn record_and_check_passthrough_strike_final(&mut self, fatal: bool) -> bool {
+    pub fn record_and_check_passthrough_strike_final(
+        &mut self,
+        frame: PassthroughBlock,
+        fatal: bool,
+    ) -> bool {
         self.passthrough_violations = self.passthrough_violations.saturating_add(1);
         let blocked = fatal || self.passthrough_violations > OPUS_PASSTHROUGH_STRIKE_LIMIT;
         if blocked {
-            self.passthrough = Passthrough::Block;
+            self.passthrough = Passthrough::Blocked;
+            self.passthrough_block = Some(frame);
         }
         blocked
     }
@@ -84,21 +222,43 @@ impl Default for DecodeState {
             resampler: None,
             passthrough: Passthrough::Inactive,
             passthrough_violations: 0,
+            passthrough_block: None,
         }
     }
 }
 
-/// Simple state to manage decoder resets etc.
-///
-/// Inactive->Active transitions should trigger a reset.
-///
-/// Block should be used if a source contains known-bad packets:
-/// it's unlikely that packet sizes will vary, but if they do then
-/// we can't passthrough (and every attempt will trigger a codec reset,
-/// which probably won't sound too smooth).
-#[derive(Clone, Copy, Eq, PartialEq)]
-pub enum Passthrough {
-    Active,
-    Inactive,
-    Block,
+/// A linear ramp of the mixed output down to silence, after which all tracks are stopped.
+pub struct FadeOut {
+    total_frames: u32,
+    remaining_frames: u32,
+    pub done: Sender<()>,
+}
+
+impl FadeOut {
+    pub fn new(duration: Duration, done: Sender<()>) -> Self {
+        let total_frames = duration
+            .as_nanos()
+            .div_ceil(TIMESTEP_LENGTH.as_nanos())
+            .clamp(1, u32::MAX.into()) as u32;
+
+        Self {
+            total_frames,
+            remaining_frames: total_frames,
+            done,
+        }
+    }
+
+    /// Returns the gains to apply at the start and end of the next frame,
+    /// and advances the fade by one frame.
+    pub fn step(&mut self) -> (f32, f32) {
+        let start = self.remaining_frames as f32 / self.total_frames as f32;
+        self.remaining_frames = self.remaining_frames.saturating_sub(1);
+        let end = self.remaining_frames as f32 / self.total_frames as f32;
+
+        (start, end)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.remaining_frames == 0
+    }
 }
@@ -1,4 +1,16 @@
-use crate::tracks::{ReadyState, SeekRequest};
+use crate::{
+    driver::retry::Retry,
+    input::AudioStreamError,
+    tracks::{
+        DisposalHook,
+        DuckRole,
+        ReadyState,
+        SeekCallback,
+        SeekRequest,
+        SeekTarget,
+        TargetLoudness,
+    },
+};
 use std::result::Result as StdResult;
 use symphonia_core::errors::Error as SymphError;
 
@@ -7,13 +19,27 @@ use super::*;
 pub struct InternalTrack {
     pub(crate) playing: PlayMode,
     pub(crate) volume: f32,
+    pub(crate) gain_target: Option<TargetLoudness>,
+    /// Linear gain computed from `gain_target`, once the input has been parsed.
+    pub(crate) gain: Option<f32>,
+    pub(crate) ducking: Option<DuckRole>,
+    /// Current gain applied by ducking, if this track is ducked.
+    pub(crate) duck_gain: f32,
     pub(crate) input: InputState,
     pub(crate) mix_state: DecodeState,
     pub(crate) position: Duration,
     pub(crate) play_time: Duration,
     pub(crate) commands: Receiver<TrackCommand>,
     pub(crate) loops: LoopState,
+    /// Start and end of the region which loops return to and from, if not the whole track.
+    pub(crate) loop_region: Option<(Duration, Duration)>,
+    pub(crate) max_duration: Option<Duration>,
     pub(crate) callbacks: Callbacks,
+    pub(crate) mix_cost: Duration,
+    /// Consecutive retries of input creation, and the last delay between them.
+    pub(crate) create_retries: usize,
+    pub(crate) last_retry_wait: Option<Duration>,
+    pub(crate) disposal_hook: Option<DisposalHook>,
 }
 
 impl<'a> InternalTrack {
@@ -25,16 +51,33 @@ impl<'a> InternalTrack {
             track,
             receiver,
         } = val;
+        let input = match InputState::from(track.input) {
+            // Pre-parsed inputs must still pass through the thread pool to reach `start_at`.
+            InputState::Ready(parsed, rec) if !track.start_at.is_zero() =>
+                InputState::NotReady(Input::Live(LiveInput::Parsed(parsed), rec)),
+            other => other,
+        };
+
         let out = InternalTrack {
             playing: track.playing,
             volume: track.volume,
-            input: InputState::from(track.input),
+            gain_target: track.gain_target,
+            gain: None,
+            ducking: track.ducking,
+            duck_gain: 1.0,
+            input,
             mix_state: DecodeState::default(),
-            position: Duration::default(),
+            position: track.start_at,
             play_time: Duration::default(),
             commands: receiver,
             loops: track.loops,
+            loop_region: None,
+            max_duration: track.max_duration,
             callbacks: Callbacks::default(),
+            mix_cost: Duration::ZERO,
+            create_retries: 0,
+            last_retry_wait: None,
+            disposal_hook: track.disposal_hook,
         };
 
         let state = out.state();
@@ -52,6 +95,9 @@ impl<'a> InternalTrack {
             play_time: self.play_time,
             loops: self.loops,
             ready,
+            mix_cost: self.mix_cost,
+            passthrough: self.mix_state.passthrough,
+            passthrough_block: self.mix_state.passthrough_block,
         }
     }
 
@@ -66,10 +112,16 @@ impl<'a> InternalTrack {
             ready,
             playing: &mut self.playing,
             loops: &mut self.loops,
+            passthrough: self.mix_state.passthrough,
         }
     }
 
-    pub(crate) fn process_commands(&mut self, index: usize, ic: &Interconnect) -> Action {
+    pub(crate) fn process_commands(
+        &mut self,
+        index: usize,
+        ic: &Interconnect,
+        disposer: &DisposalThread,
+    ) -> Action {
         // Note: disconnection and an empty channel are both valid,
         // and should allow the audio object to keep running as intended.
 
@@ -134,13 +186,52 @@ impl<'a> InternalTrack {
                         TrackStateChange::Loops(self.loops, true),
                     )));
                 },
+                TrackCommand::LoopRegion(region) => self.loop_region = region,
                 TrackCommand::MakePlayable(callback) => action.make_playable = Some(callback),
+                TrackCommand::Ducking(role) => {
+                    self.ducking = role;
+                    if !matches!(role, Some(DuckRole::Ducked(..))) {
+                        self.duck_gain = 1.0;
+                    }
+                },
+                TrackCommand::ReplaceInput(input) => {
+                    self.replace_input(input, disposer);
+                    action.seek_point = None;
+                    drop(ic.events.send(EventMessage::ChangeState(
+                        index,
+                        TrackStateChange::Total(self.state()),
+                    )));
+                },
             }
         }
 
         action
     }
 
+    /// Swaps in a new input, which will be readied when next needed.
+    ///
+    /// Pending seeks are cancelled, as they were aimed at the old input.
+    pub(crate) fn replace_input(&mut self, input: Input, disposer: &DisposalThread) {
+        let old = std::mem::replace(&mut self.input, InputState::from(input));
+        disposer.dispose(DisposalMessage::Input(Box::new(old)));
+
+        self.mix_state = DecodeState::default();
+        self.gain = None;
+        self.position = Duration::ZERO;
+        self.callbacks.seek = None;
+    }
+
+    /// Returns the volume at which this track is mixed, including any loudness
+    /// and ducking gain.
+    ///
+    /// This is `None` while the gain for a [`TargetLoudness`] has yet to be computed.
+    pub(crate) fn mixed_volume(&self) -> Option<f32> {
+        match (self.gain_target, self.gain) {
+            (Some(_), None) => None,
+            (_, gain) => Some(self.volume * gain.unwrap_or(1.0) * self.duck_gain),
+        }
+    }
+
     pub(crate) fn do_loop(&mut self) -> bool {
         match self.loops {
             LoopState::Infinite => true,
@@ -158,8 +249,28 @@ impl<'a> InternalTrack {
         self.play_time += TIMESTEP_LENGTH;
     }
 
+    /// Returns whether this track has played as much audio as it is allowed.
+    pub(crate) fn reached_max_duration(&self) -> bool {
+        self.max_duration.is_some_and(|max| self.play_time >= max)
+    }
+
+    /// Returns whether the last frame crossed the end of this track's loop region.
+    pub(crate) fn reached_loop_end(&self) -> bool {
+        self.loop_region
+            .is_some_and(|(_, end)| self.position >= end && self.position < end + TIMESTEP_LENGTH)
+    }
+
+    /// Returns the position which a loop of this track should seek to.
+    pub(crate) fn loop_start(&self) -> Duration {
+        self.loop_region.map_or(Duration::ZERO, |(start, _)| start)
+    }
+
     pub(crate) fn should_check_input(&self) -> bool {
-        self.playing.is_playing() || matches!(self.input, InputState::Preparing(_))
+        self.playing.is_playing()
+            || matches!(
+                self.input,
+                InputState::Preparing(_) | InputState::Retrying(_)
+            )
     }
 
     pub(crate) fn end(&mut self) -> &mut Self {
@@ -183,7 +294,9 @@ impl<'a> InternalTrack {
         let mix_state = &mut self.mix_state;
 
         let (out, queued_seek) = match input {
-            InputState::NotReady(_) => {
+            InputState::Retrying(info) if info.resume_at > Instant::now() =>
+                (Err(InputReadyingError::Waiting), None),
+            InputState::NotReady(_) | InputState::Retrying(_) => {
                 let (tx, rx) = flume::bounded(1);
 
                 let mut state = InputState::Preparing(PreparingInfo {
@@ -194,12 +307,21 @@ impl<'a> InternalTrack {
 
                 std::mem::swap(&mut state, input);
 
+                // Nothing has been played yet, so any position is the track's `start_at`.
+                let start_at = (!self.position.is_zero()).then(|| SeekTo::Time {
+                    time: Time::from(self.position.as_secs_f64()),
+                    track_id: None,
+                });
+
                 match state {
                     InputState::NotReady(a @ Input::Lazy(_)) => {
-                        pool.create(tx, a, None, config.clone());
+                        pool.create(tx, a, start_at, config.clone());
+                    },
+                    InputState::Retrying(info) => {
+                        pool.create(tx, Input::Lazy(info.compose), start_at, config.clone());
                     },
                     InputState::NotReady(Input::Live(audio, rec)) => {
-                        pool.parse(config.clone(), tx, audio, rec, None);
+                        pool.parse(config.clone(), tx, audio, rec, start_at);
                     },
                     _ => unreachable!(),
                 }
@@ -214,18 +336,21 @@ impl<'a> InternalTrack {
                 (Err(InputReadyingError::Waiting), None)
             },
             InputState::Preparing(info) => {
-                let queued_seek = info.queued_seek.take();
+                let mut queued_seek = info.queued_seek.take();
 
                 let orig_out = match info.callback.try_recv() {
                     Ok(MixerInputResultMessage::Built(parsed, rec)) => {
                         *input = InputState::Ready(parsed, rec);
                         mix_state.reset();
+                        self.create_retries = 0;
+                        self.last_retry_wait = None;
 
                         // possible TODO: set position to the true track position here?
                         // ISSUE: need to get next_packet to see its `ts`, but inner_pos==0
                         // will trigger next packet to be taken at mix time.
 
-                        if !prevent_events {
+                        // A queued seek will re-ready the track, and report `Playable` once done.
+                        if !prevent_events && queued_seek.is_none() {
                             drop(interconnect.events.send(EventMessage::ChangeState(
                                 id,
                                 TrackStateChange::Ready(ReadyState::Playable),
@@ -251,7 +376,7 @@ impl<'a> InternalTrack {
                                     self.position =
                                         std::time::Duration::from_secs_f64(time_in_float);
 
-                                    self.callbacks.seeked(self.position);
+                                    self.callbacks.seeked(self.position, pos.actual_ts);
                                     self.callbacks.playable();
 
                                     if !prevent_events {
@@ -286,11 +411,53 @@ impl<'a> InternalTrack {
                             Err(e) => Err(InputReadyingError::Seeking(e)),
                         }
                     },
-                    Ok(MixerInputResultMessage::CreateErr(e)) =>
-                        Err(InputReadyingError::Creation(e)),
+                    Ok(MixerInputResultMessage::CreateErr(e, rec)) => match (
+                        rec,
+                        retry_wait(
+                            &e,
+                            &config.input_retry,
+                            self.last_retry_wait,
+                            self.create_retries,
+                        ),
+                    ) {
+                        (Some(compose), Some(wait)) => {
+                            self.create_retries += 1;
+                            self.last_retry_wait = Some(wait);
+                            *input = InputState::Retrying(RetryingInfo {
+                                compose,
+                                resume_at: Instant::now() + wait,
+                            });
+
+                            // The input is recreated at the current position, so any
+                            // seek which led to this creation cannot be honoured.
+                            if let Some(callback) = self.callbacks.seek.take() {
+                                callback.failed(PlayError::Create(e));
+                            }
+                            queued_seek = None;
+
+                            if !prevent_events {
+                                drop(interconnect.events.send(EventMessage::ChangeState(
+                                    id,
+                                    TrackStateChange::Retrying,
+                                )));
+                            }
+
+                            Err(InputReadyingError::Waiting)
+                        },
+                        _ => Err(InputReadyingError::Creation(e)),
+                    },
                     Ok(MixerInputResultMessage::ParseErr(e)) => Err(InputReadyingError::Parsing(e)),
                     Err(TryRecvError::Disconnected) => Err(InputReadyingError::Dropped),
-                    Err(TryRecvError::Empty) => Err(InputReadyingError::Waiting),
+                    Err(TryRecvError::Empty)
+                        if config
+                            .input_ready_timeout
+                            .is_some_and(|timeout| info.time.elapsed() >= timeout) =>
+                        Err(InputReadyingError::TimedOut),
+                    Err(TryRecvError::Empty) => {
+                        // Hold onto any queued seek until the input is ready.
+                        info.queued_seek = queued_seek.take();
+                        Err(InputReadyingError::Waiting)
+                    },
                 };
 
                 let orig_out = orig_out.map(|a| (a, mix_state));
@@ -327,7 +494,7 @@ impl<'a> InternalTrack {
         }
 
         // might be a little topsy turvy: rethink me.
-        let SeekRequest { time, callback } = request;
+        let SeekRequest { target, callback } = request;
 
         self.callbacks.seek = Some(callback);
         if !prevent_events {
@@ -337,12 +504,31 @@ impl<'a> InternalTrack {
             )));
         }
 
-        let backseek_needed = time < self.position;
+        let (backseek_needed, mut ts) = match target {
+            SeekTarget::Time(time) => (
+                time < self.position,
+                SeekTo::Time {
+                    time: Time::from(time.as_secs_f64()),
+                    track_id: None,
+                },
+            ),
+            SeekTarget::Sample(ts) => {
+                // Without a time base, we can't tell where we are in the stream:
+                // assume that we must rewind.
+                let target_time = match &self.input {
+                    InputState::Ready(p, _) => p.decoder.codec_params().time_base.map(|tb| {
+                        let t = tb.calc_time(ts);
+                        Duration::from_secs_f64(t.seconds as f64 + t.frac)
+                    }),
+                    _ => None,
+                };
 
-        let time = Time::from(time.as_secs_f64());
-        let mut ts = SeekTo::Time {
-            time,
-            track_id: None,
+                (
+                    target_time.map_or(true, |t| t < self.position),
+                    // The track ID is filled in once the input is parsed.
+                    SeekTo::TimeStamp { ts, track_id: 0 },
+                )
+            },
         };
         let (tx, rx) = flume::bounded(1);
 
@@ -364,21 +550,40 @@ impl<'a> InternalTrack {
                 pool.seek(tx, p, r, ts, backseek_needed, config.clone());
             },
             InputState::NotReady(lazy) => pool.create(tx, lazy, Some(ts), config.clone()),
+            InputState::Retrying(info) =>
+                pool.create(tx, Input::Lazy(info.compose), Some(ts), config.clone()),
             InputState::Preparing(_) => unreachable!(), // Covered above.
         }
     }
 }
 
+/// Returns how long to wait before retrying a failed input creation, if it may be retried.
+///
+/// Only sources which ask to be retried are, waiting at least as long as they request.
+fn retry_wait(
+    err: &AudioStreamError,
+    retry: &Retry,
+    last_wait: Option<Duration>,
+    attempts: usize,
+) -> Option<Duration> {
+    match err {
+        AudioStreamError::RetryIn(min_wait) => retry
+            .retry_in(last_wait, attempts)
+            .map(|wait| wait.max(*min_wait)),
+        _ => None,
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct Callbacks {
-    pub seek: Option<Sender<StdResult<Duration, PlayError>>>,
+    pub seek: Option<SeekCallback>,
     pub make_playable: Option<Sender<StdResult<(), PlayError>>>,
 }
 
 impl Callbacks {
     fn readying_error(&mut self, err: PlayError) {
         if let Some(callback) = self.seek.take() {
-            drop(callback.send(Err(err.clone())));
+            callback.failed(err.clone());
         }
 
         if let Some(callback) = self.make_playable.take() {
@@ -392,9 +597,34 @@ impl Callbacks {
         }
     }
 
-    fn seeked(&mut self, time: Duration) {
+    fn seeked(&mut self, time: Duration, ts: u64) {
         if let Some(callback) = self.seek.take() {
-            drop(callback.send(Ok(time)));
+            callback.seeked(time, ts);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::retry::Strategy;
+
+    #[test]
+    fn only_requested_retries_are_attempted() {
+        let retry = Retry {
+            strategy: Strategy::Every(Duration::from_secs(1)),
+            retry_limit: Some(1),
+        };
+        let transient = AudioStreamError::RetryIn(Duration::from_secs(3));
+
+        assert_eq!(
+            retry_wait(&transient, &retry, None, 0),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(retry_wait(&transient, &retry, None, 1), None);
+        assert_eq!(
+            retry_wait(&AudioStreamError::Unauthorized, &retry, None, 0),
+            None
+        );
+    }
+}
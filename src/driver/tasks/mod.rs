@@ -9,14 +9,15 @@ pub mod mixer;
 pub(crate) mod udp_rx;
 pub(crate) mod ws;
 
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
-use super::connection::{error::Error as ConnectionError, Connection};
+use super::connection::{error::Error as ConnectionError, stats::ConnectionStatBlock, Connection};
 use crate::{
     events::{
         context_data::{DisconnectKind, DisconnectReason},
         internal_data::{InternalConnect, InternalDisconnect},
         CoreContext,
+        GlobalEvents,
     },
     Config,
     ConnectionInfo,
@@ -26,27 +27,41 @@ use message::*;
 use tokio::{spawn, time::sleep as tsleep};
 use tracing::{debug, instrument, trace};
 
-pub(crate) fn start(config: Config, rx: Receiver<CoreMessage>, tx: Sender<CoreMessage>) {
+pub(crate) fn start(
+    config: Config,
+    rx: Receiver<CoreMessage>,
+    tx: Sender<CoreMessage>,
+    stats: Arc<ConnectionStatBlock>,
+) {
     spawn(async move {
         trace!("Driver started.");
-        runner(config, rx, tx).await;
+        Box::pin(runner(config, rx, tx, stats)).await;
         trace!("Driver finished.");
     });
 }
 
-fn start_internals(core: Sender<CoreMessage>, config: &Config) -> Interconnect {
+fn start_internals(
+    core: Sender<CoreMessage>,
+    config: &Config,
+    stats: &Arc<ConnectionStatBlock>,
+) -> Interconnect {
     let (evt_tx, evt_rx) = flume::unbounded();
     let (mix_tx, mix_rx) = flume::unbounded();
 
+    let core_queue = config
+        .event_queue_limit
+        .map(|limit| Arc::new(CoreEventQueue::new(limit.get(), stats.clone())));
+    let events = EventSender::new(evt_tx, core_queue.clone());
+
     spawn(async move {
         trace!("Event processor started.");
-        events::runner(evt_rx).await;
+        events::runner(evt_rx, core_queue, GlobalEvents::default()).await;
         trace!("Event processor finished.");
     });
 
     let ic = Interconnect {
         core,
-        events: evt_tx,
+        events,
         mixer: mix_tx,
     };
 
@@ -54,11 +69,16 @@ fn start_internals(core: Sender<CoreMessage>, config: &Config) -> Interconnect {
     ic
 }
 
-#[instrument(skip(rx, tx))]
-async fn runner(mut config: Config, rx: Receiver<CoreMessage>, tx: Sender<CoreMessage>) {
+#[instrument(skip(rx, tx, stats))]
+async fn runner(
+    mut config: Config,
+    rx: Receiver<CoreMessage>,
+    tx: Sender<CoreMessage>,
+    stats: Arc<ConnectionStatBlock>,
+) {
     let mut next_config: Option<Config> = None;
     let mut connection: Option<Connection> = None;
-    let mut interconnect = start_internals(tx, &config);
+    let mut interconnect = start_internals(tx, &config, &stats);
     let mut retrying = None;
     let mut attempt_idx = 0;
 
@@ -81,9 +101,37 @@ async fn runner(mut config: Config, rx: Receiver<CoreMessage>, tx: Sender<CoreMe
                     // active connection.
                     // This allows the gateway component to keep sending join requests independent
                     // of driver failures.
-                    connection = ConnectionRetryData::connect(tx, info, &mut attempt_idx)
-                        .attempt(&mut retrying, &interconnect, &config)
-                        .await;
+                    //
+                    // If the session and voice server are unchanged (e.g., we were moved between
+                    // channels), then try to resume on the existing connection to keep audio
+                    // flowing. A changed server, or a rejected resume, needs a full connection.
+                    let handed_over = match connection.as_mut() {
+                        Some(conn) if conn.info.can_resume_onto(&info) =>
+                            match conn.handover(info.clone(), &config).await {
+                                Ok(()) => true,
+                                Err(e) => {
+                                    debug!("Failed to resume connection on new info: {}", e);
+                                    false
+                                },
+                            },
+                        _ => false,
+                    };
+
+                    if let (true, Some(conn)) = (handed_over, &connection) {
+                        drop(tx.send(Ok(())));
+
+                        drop(interconnect.events.send(EventMessage::FireCoreEvent(
+                            CoreContext::DriverReconnect(InternalConnect {
+                                info: conn.info.clone(),
+                                ssrc: conn.ssrc,
+                                crypto_mode: conn.crypto_mode,
+                            }),
+                        )));
+                    } else {
+                        connection = ConnectionRetryData::connect(tx, info, &mut attempt_idx)
+                            .attempt(&mut retrying, &interconnect, &config, &stats)
+                            .await;
+                    }
                 } else {
                     // No reconnection was attempted as there's a valid, identical connection;
                     // tell the outside listener that the operation was a success.
@@ -95,7 +143,7 @@ async fn runner(mut config: Config, rx: Receiver<CoreMessage>, tx: Sender<CoreMe
                 if retry_idx == attempt_idx {
                     if let Some(progress) = retrying.take() {
                         connection = progress
-                            .attempt(&mut retrying, &interconnect, &config)
+                            .attempt(&mut retrying, &interconnect, &config, &stats)
                             .await;
                     }
                 }
@@ -166,6 +214,42 @@ async fn runner(mut config: Config, rx: Receiver<CoreMessage>, tx: Sender<CoreMe
             CoreMessage::Mute(m) => {
                 drop(interconnect.mixer.send(MixerMessage::SetMute(m)));
             },
+            CoreMessage::SetMeter(meter) => {
+                drop(interconnect.mixer.send(MixerMessage::SetMeter(meter)));
+            },
+            CoreMessage::AddOpusSink(sink) => {
+                drop(interconnect.mixer.send(MixerMessage::AddOpusSink(sink)));
+            },
+            CoreMessage::RemoveOpusSinks => {
+                drop(interconnect.mixer.send(MixerMessage::RemoveOpusSinks));
+            },
+            CoreMessage::SetSpeakingFlags(flags) => {
+                drop(
+                    interconnect
+                        .mixer
+                        .send(MixerMessage::SetSpeakingFlags(flags)),
+                );
+            },
+            CoreMessage::SetPaused(p) => {
+                drop(interconnect.mixer.send(MixerMessage::SetPaused(p)));
+            },
+            CoreMessage::FadeOut(duration, done) => {
+                drop(
+                    interconnect
+                        .mixer
+                        .send(MixerMessage::FadeOut(duration, done)),
+                );
+            },
+            CoreMessage::Shutdown(done) => {
+                drop(interconnect.mixer.send(MixerMessage::Shutdown(done)));
+            },
+            #[cfg(feature = "receive")]
+            CoreMessage::ResetDecoder(ssrc) => {
+                drop(interconnect.mixer.send(MixerMessage::ResetDecoder(ssrc)));
+            },
+            CoreMessage::GetTracks(tx) => {
+                drop(interconnect.mixer.send(MixerMessage::GetTracks(tx)));
+            },
             CoreMessage::Reconnect => {
                 if let Some(mut conn) = connection.take() {
                     // try once: if interconnect, try again.
@@ -193,13 +277,14 @@ async fn runner(mut config: Config, rx: Receiver<CoreMessage>, tx: Sender<CoreMe
 
                     if full_connect {
                         connection = ConnectionRetryData::reconnect(info, &mut attempt_idx)
-                            .attempt(&mut retrying, &interconnect, &config)
+                            .attempt(&mut retrying, &interconnect, &config, &stats)
                             .await;
                     } else if let Some(ref connection) = &connection {
                         drop(interconnect.events.send(EventMessage::FireCoreEvent(
                             CoreContext::DriverReconnect(InternalConnect {
                                 info: connection.info.clone(),
                                 ssrc: connection.ssrc,
+                                crypto_mode: connection.crypto_mode,
                             }),
                         )));
                     }
@@ -210,7 +295,7 @@ async fn runner(mut config: Config, rx: Receiver<CoreMessage>, tx: Sender<CoreMe
                     let info = conn.info.clone();
 
                     connection = ConnectionRetryData::reconnect(info, &mut attempt_idx)
-                        .attempt(&mut retrying, &interconnect, &config)
+                        .attempt(&mut retrying, &interconnect, &config, &stats)
                         .await;
                 },
             CoreMessage::RebuildInterconnect => {
@@ -262,8 +347,9 @@ impl ConnectionRetryData {
         attempt_slot: &mut Option<Self>,
         interconnect: &Interconnect,
         config: &Config,
+        stats: &Arc<ConnectionStatBlock>,
     ) -> Option<Connection> {
-        match Connection::new(self.info.clone(), interconnect, config, self.idx).await {
+        match Connection::new(self.info.clone(), interconnect, config, self.idx, stats).await {
             Ok(connection) => {
                 match self.flavour {
                     ConnectionFlavour::Connect(tx) => {
@@ -274,6 +360,7 @@ impl ConnectionRetryData {
                             CoreContext::DriverConnect(InternalConnect {
                                 info: connection.info.clone(),
                                 ssrc: connection.ssrc,
+                                crypto_mode: connection.crypto_mode,
                             }),
                         )));
                     },
@@ -282,6 +369,7 @@ impl ConnectionRetryData {
                             CoreContext::DriverReconnect(InternalConnect {
                                 info: connection.info.clone(),
                                 ssrc: connection.ssrc,
+                                crypto_mode: connection.crypto_mode,
                             }),
                         )));
                     },
@@ -7,8 +7,12 @@ use self::{decode_sizes::*, playout_buffer::*, ssrc_state::*};
 use super::message::*;
 use crate::{
     constants::*,
-    driver::CryptoMode,
-    events::{context_data::VoiceTick, internal_data::*, CoreContext},
+    driver::{connection::stats::ConnectionStatBlock, CryptoMode},
+    events::{
+        context_data::{RtcpData, VoiceTick},
+        internal_data::*,
+        CoreContext,
+    },
     Config,
 };
 use bytes::BytesMut;
@@ -37,6 +41,7 @@ struct UdpRx {
     config: Config,
     rx: Receiver<UdpRxMessage>,
     ssrc_signalling: Arc<SsrcTracker>,
+    stats: Arc<ConnectionStatBlock>,
     udp_socket: UdpSocket,
 }
 
@@ -64,8 +69,22 @@ impl UdpRx {
                         Ok(UdpRxMessage::ReplaceInterconnect(i)) => {
                             *interconnect = i;
                         },
-                        Ok(UdpRxMessage::SetConfig(c)) => {
-                            self.config = c;
+                        #[cfg(test)]
+                        Ok(UdpRxMessage::InjectPacket(pkt)) => {
+                            self.process_udp_message(interconnect, pkt);
+                        },
+                        Ok(UdpRxMessage::ResetDecoder(ssrc)) => {
+                            if let Some(state) = self.decoder_map.get_mut(&ssrc) {
+                                state.reset_decoder();
+                            }
+                        },
+                        Ok(UdpRxMessage::SetConfig(mut c)) => {
+                            // The negotiated encryption mode is fixed for this session.
+                            c.crypto_mode = self.config.crypto_mode;
+                            let old_config = std::mem::replace(&mut self.config, *c);
+                            for state in self.decoder_map.values_mut() {
+                                state.reconfigure(&old_config, &self.config);
+                            }
                         },
                         Err(flume::RecvError::Disconnected) => break,
                     }
@@ -74,10 +93,19 @@ impl UdpRx {
                     let mut tick = VoiceTick {
                         speaking: HashMap::new(),
                         silent: HashSet::new(),
+                        mixed_voice: None,
+                        playout: HashMap::new(),
                     };
 
                     for (ssrc, state) in &mut self.decoder_map {
-                        match state.get_voice_tick(&self.config) {
+                        let voice = state.get_voice_tick(&self.config);
+                        tick.playout.insert(*ssrc, state.playout_stats());
+
+                        if let Some(change) = state.playout_change(*ssrc) {
+                            drop(interconnect.events.send(EventMessage::FireCoreEvent(CoreContext::PlayoutChange(change))));
+                        }
+
+                        match voice {
                             Ok(Some(data)) => {
                                 tick.speaking.insert(*ssrc, data);
                             },
@@ -93,9 +121,19 @@ impl UdpRx {
                         }
                     }
 
+                    self.stats.record_ssrc_stats(
+                        self.decoder_map
+                            .iter_mut()
+                            .filter_map(|(ssrc, state)| state.take_stats_change().map(|s| (*ssrc, s))),
+                    );
+
+                    if self.config.mix_received_voice {
+                        tick.mixed_voice = mix_voice(&tick, |ssrc| self.muted_in_mix(ssrc));
+                    }
+
                     playout_time += TIMESTEP_LENGTH;
 
-                    drop(interconnect.events.send(EventMessage::FireCoreEvent(CoreContext::VoiceTick(tick))));
+                    drop(interconnect.events.send(EventMessage::FireCoreEvent(CoreContext::VoiceTick(Box::new(tick)))));
                 },
                 () = tokio::time::sleep_until(cleanup_time) => {
                     // periodic cleanup.
@@ -128,6 +166,7 @@ impl UdpRx {
 
                     // now remove all dead ssrcs.
                     self.decoder_map.retain(|_, v| v.prune_time > now);
+                    self.stats.retain_ssrc_stats(|ssrc| self.decoder_map.contains_key(&ssrc));
 
                     cleanup_time = now + Duration::from_secs(5);
                 },
@@ -135,6 +174,17 @@ impl UdpRx {
         }
     }
 
+    /// Returns whether an SSRC's audio must be left out of the mix of received voice.
+    fn muted_in_mix(&self, ssrc: u32) -> bool {
+        self.config.mix_received_muted_ssrcs.contains(&ssrc)
+            || self.config.mix_received_muted_users.iter().any(|user| {
+                self.ssrc_signalling
+                    .user_ssrc_map
+                    .get(user)
+                    .is_some_and(|mapped| *mapped == ssrc)
+            })
+    }
+
     fn process_udp_message(&mut self, interconnect: &Interconnect, mut packet: BytesMut) {
         // NOTE: errors here (and in general for UDP) are not fatal to the connection.
         // Panics should be avoided due to adversarial nature of rx'd packets,
@@ -147,7 +197,7 @@ impl UdpRx {
 
         match demux::demux_mut(packet.as_mut()) {
             DemuxedMut::Rtp(mut rtp) => {
-                if !rtp_valid(&rtp.to_immutable()) {
+                if !rtp_valid(&rtp.to_immutable(), &self.config) {
                     error!("Illegal RTP message received.");
                     return;
                 }
@@ -200,7 +250,9 @@ impl UdpRx {
                 )));
             },
             DemuxedMut::Rtcp(mut rtcp) => {
-                let packet_data = if self.config.decode_mode.should_decrypt() {
+                // Loss reports must be read to adapt the send bitrate, whatever the decode mode.
+                let adaptive = self.config.adaptive_bitrate.is_some();
+                let packet_data = if self.config.decode_mode.should_decrypt() || adaptive {
                     let out = crypto_mode.decrypt_in_place(&mut rtcp, &self.cipher);
 
                     if let Err(e) = out {
@@ -212,6 +264,7 @@ impl UdpRx {
                     None
                 };
 
+                let decrypted = packet_data.is_some();
                 let (start, tail) = packet_data.unwrap_or_else(|| {
                     (
                         CryptoMode::payload_prefix_len(),
@@ -219,13 +272,27 @@ impl UdpRx {
                     )
                 });
 
-                drop(interconnect.events.send(EventMessage::FireCoreEvent(
-                    CoreContext::RtcpPacket(InternalRtcpPacket {
-                        packet: packet.freeze(),
-                        payload_offset: start,
-                        payload_end_pad: tail,
-                    }),
-                )));
+                let rtcp = InternalRtcpPacket {
+                    packet: packet.freeze(),
+                    payload_offset: start,
+                    payload_end_pad: tail,
+                };
+
+                if adaptive && decrypted {
+                    if let Some(blocks) = RtcpData::from(&rtcp).report_blocks() {
+                        drop(
+                            interconnect
+                                .mixer
+                                .send(MixerMessage::ReceiverReport(blocks)),
+                        );
+                    }
+                }
+
+                drop(
+                    interconnect
+                        .events
+                        .send(EventMessage::FireCoreEvent(CoreContext::RtcpPacket(rtcp))),
+                );
             },
             DemuxedMut::FailedParse(t) => {
                 warn!("Failed to parse message of type {:?}.", t);
@@ -245,6 +312,7 @@ pub(crate) async fn runner(
     config: Config,
     udp_socket: UdpSocket,
     ssrc_signalling: Arc<SsrcTracker>,
+    conn_stats: Arc<ConnectionStatBlock>,
 ) {
     trace!("UDP receive handle started.");
 
@@ -254,6 +322,7 @@ pub(crate) async fn runner(
         config,
         rx,
         ssrc_signalling,
+        stats: conn_stats,
         udp_socket,
     };
 
@@ -263,6 +332,235 @@ pub(crate) async fn runner(
 }
 
 #[inline]
-fn rtp_valid(packet: &RtpPacket<'_>) -> bool {
-    packet.get_version() == RTP_VERSION && packet.get_payload_type() == RTP_PROFILE_TYPE
+fn rtp_valid(packet: &RtpPacket<'_>, config: &Config) -> bool {
+    packet.get_version() == config.rtp_version
+        && packet.get_payload_type() == config.rtp_payload_type
+}
+
+/// Sums every speaking user's decoded audio (other than those `muted`), clamping
+/// to the range of an `i16`.
+fn mix_voice(tick: &VoiceTick, muted: impl Fn(u32) -> bool) -> Option<Vec<i16>> {
+    let mut mixed: Option<Vec<i32>> = None;
+
+    for voice in tick
+        .speaking
+        .iter()
+        .filter(|(ssrc, _)| !muted(**ssrc))
+        .filter_map(|(_, d)| d.decoded_voice.as_ref())
+    {
+        let out = mixed.get_or_insert_with(|| Vec::with_capacity(voice.len()));
+        if out.len() < voice.len() {
+            out.resize(voice.len(), 0);
+        }
+
+        for (o, s) in out.iter_mut().zip(voice) {
+            *o += i32::from(*s);
+        }
+    }
+
+    mixed.map(|out| {
+        out.into_iter()
+            .map(|s| s.clamp(i16::MIN.into(), i16::MAX.into()) as i16)
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        driver::{crypto::TAG_SIZE, DecodeMode},
+        events::context_data::{PlayoutState, VoiceData},
+    };
+    use crypto_secretbox::KeyInit;
+    use discortp::rtp::{MutableRtpPacket, RtpType};
+    use flume::Sender;
+    use std::num::NonZeroUsize;
+
+    const SSRC: u32 = 0xdead_beef;
+
+    fn packet(seq: u16) -> BytesMut {
+        let mut bytes = BytesMut::zeroed(32);
+        let mut rtp = MutableRtpPacket::new(&mut bytes[..]).unwrap();
+        rtp.set_version(RTP_VERSION);
+        rtp.set_payload_type(RTP_PROFILE_TYPE);
+        rtp.set_sequence(seq.into());
+        rtp.set_timestamp((u32::from(seq) * MONO_FRAME_SIZE as u32).into());
+        rtp.set_ssrc(SSRC);
+
+        bytes
+    }
+
+    async fn spawn_rx(
+        config: Config,
+    ) -> (
+        Sender<UdpRxMessage>,
+        Receiver<EventMessage>,
+        Arc<ConnectionStatBlock>,
+    ) {
+        let (core_tx, _core_rx) = flume::unbounded();
+        let (event_tx, event_rx) = flume::unbounded();
+        let (mixer_tx, _mixer_rx) = flume::unbounded();
+        let (rx_tx, rx_rx) = flume::unbounded();
+
+        let interconnect = Interconnect {
+            core: core_tx,
+            events: EventSender::new(event_tx, None),
+            mixer: mixer_tx,
+        };
+        let cipher = Cipher::new_from_slice(&[0u8; crate::driver::crypto::KEY_SIZE]).unwrap();
+        let udp_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let stats = Arc::new(ConnectionStatBlock::default());
+
+        tokio::spawn(runner(
+            interconnect,
+            rx_rx,
+            cipher,
+            config,
+            udp_socket,
+            Arc::default(),
+            stats.clone(),
+        ));
+
+        (rx_tx, event_rx, stats)
+    }
+
+    /// Returns the sequence number played out for `SSRC` on each of the next `n` ticks,
+    /// along with any playout state changes.
+    async fn next_ticks(
+        events: &Receiver<EventMessage>,
+        n: usize,
+    ) -> (Vec<Option<u16>>, Vec<PlayoutState>) {
+        let mut seqs = vec![];
+        let mut changes = vec![];
+
+        while seqs.len() < n {
+            match events.recv_async().await.unwrap() {
+                EventMessage::FireCoreEvent(CoreContext::VoiceTick(tick)) => seqs.push(
+                    tick.speaking
+                        .get(&SSRC)
+                        .and_then(|data| data.packet.as_ref())
+                        .map(|pkt| pkt.rtp().get_sequence().0 .0),
+                ),
+                EventMessage::FireCoreEvent(CoreContext::PlayoutChange(change)) =>
+                    changes.push(change.state),
+                _ => {},
+            }
+        }
+
+        (seqs, changes)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn injected_packets_are_reordered() {
+        let config = Config::default()
+            .decode_mode(DecodeMode::Pass)
+            .playout_buffer_length(NonZeroUsize::new(3).unwrap());
+        let (tx, events, _) = spawn_rx(config).await;
+
+        for seq in [0, 2, 1, 3, 4] {
+            tx.send(UdpRxMessage::InjectPacket(packet(seq))).unwrap();
+        }
+
+        let (seqs, changes) = next_ticks(&events, 6).await;
+        assert_eq!(
+            seqs,
+            vec![Some(0), Some(1), Some(2), Some(3), Some(4), None]
+        );
+        assert_eq!(changes[0], PlayoutState::Draining);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn injected_loss_is_reported_as_missed() {
+        let config = Config::default()
+            .decode_mode(DecodeMode::Pass)
+            .playout_buffer_length(NonZeroUsize::new(3).unwrap());
+        let (tx, events, _) = spawn_rx(config).await;
+
+        for seq in [0, 1, 3, 4] {
+            tx.send(UdpRxMessage::InjectPacket(packet(seq))).unwrap();
+        }
+
+        let (seqs, _) = next_ticks(&events, 5).await;
+        assert_eq!(seqs, vec![Some(0), Some(1), None, Some(3), Some(4)]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn loss_and_decode_errors_are_counted() {
+        let config = Config::default()
+            .decode_mode(DecodeMode::Decode)
+            .playout_buffer_length(NonZeroUsize::new(3).unwrap());
+        let (tx, events, stats) = spawn_rx(config).await;
+        let cipher = Cipher::new_from_slice(&[0u8; crate::driver::crypto::KEY_SIZE]).unwrap();
+
+        // A code 3 Opus packet which claims to hold no frames cannot be decoded.
+        let opus = [0x03, 0x00];
+        for seq in [0, 2] {
+            let mut bytes = BytesMut::zeroed(12 + TAG_SIZE + opus.len());
+            bytes[..12].copy_from_slice(&packet(seq)[..12]);
+            bytes[12 + TAG_SIZE..].copy_from_slice(&opus);
+
+            let mut rtp = MutableRtpPacket::new(&mut bytes[..]).unwrap();
+            let payload_len = TAG_SIZE + opus.len();
+            CryptoMode::Normal
+                .encrypt_in_place(&mut rtp, &cipher, payload_len)
+                .unwrap();
+            tx.send(UdpRxMessage::InjectPacket(bytes)).unwrap();
+        }
+
+        next_ticks(&events, 6).await;
+
+        let ssrc_stats = stats.ssrc_stats();
+        assert_eq!(ssrc_stats[&SSRC].lost_frames, 1);
+        assert_eq!(ssrc_stats[&SSRC].decode_errors, 2);
+    }
+
+    #[test]
+    fn rtp_validity_follows_config() {
+        let bytes = packet(0);
+        let rtp = RtpPacket::new(&bytes[..]).unwrap();
+        assert!(rtp_valid(&rtp, &Config::default()));
+
+        let custom = Config::default().rtp_payload_type(RtpType::Dynamic(111));
+        assert!(!rtp_valid(&rtp, &custom));
+
+        let mut bytes = packet(0);
+        MutableRtpPacket::new(&mut bytes[..])
+            .unwrap()
+            .set_payload_type(RtpType::Dynamic(111));
+        let rtp = RtpPacket::new(&bytes[..]).unwrap();
+        assert!(rtp_valid(&rtp, &custom));
+        assert!(!rtp_valid(&rtp, &custom.rtp_version(1)));
+    }
+
+    #[test]
+    fn mixed_voice_sums_and_clamps_speakers() {
+        let voice = |pcm: Vec<i16>| VoiceData {
+            packet: None,
+            opus_frame: None,
+            decoded_voice: Some(pcm),
+            rms: None,
+        };
+
+        let mut tick = VoiceTick {
+            speaking: HashMap::new(),
+            silent: HashSet::new(),
+            mixed_voice: None,
+            playout: HashMap::new(),
+        };
+        assert_eq!(mix_voice(&tick, |_| false), None);
+
+        tick.speaking.insert(1, voice(vec![100, -200, i16::MAX, 5]));
+        tick.speaking.insert(2, voice(vec![50, -50, 1000]));
+
+        assert_eq!(
+            mix_voice(&tick, |_| false),
+            Some(vec![150, -250, i16::MAX, 5])
+        );
+        assert_eq!(
+            mix_voice(&tick, |ssrc| ssrc == 1),
+            Some(vec![50, -50, 1000])
+        );
+        assert_eq!(mix_voice(&tick, |_| true), None);
+    }
 }
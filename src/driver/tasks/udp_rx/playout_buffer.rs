@@ -1,9 +1,12 @@
 use super::*;
+use crate::events::context_data::{PlayoutData, PlayoutState, PlayoutStats};
 use bytes::Bytes;
 use discortp::rtp::RtpPacket;
 use std::collections::VecDeque;
 use tracing::trace;
 
+const MAX_PLAYOUT_LEN: usize = 64;
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct StoredPacket {
     pub packet: Bytes,
@@ -46,6 +49,17 @@ pub struct PlayoutBuffer {
     playout_mode: PlayoutMode,
     next_seq: RtpSequence,
     current_timestamp: Option<RtpTimestamp>,
+    /// Frames of excess delay left to skip after the playout length was reduced.
+    drain_frames: usize,
+    /// Playout mode at the time of the last reported change.
+    reported_mode: PlayoutMode,
+    lost_packets: u32,
+    late_packets: u32,
+    overflow_packets: u32,
+    /// Running totals of the above, which are never reset.
+    total_lost: u64,
+    total_late: u64,
+    total_overflow: u64,
 }
 
 impl PlayoutBuffer {
@@ -55,6 +69,93 @@ impl PlayoutBuffer {
             playout_mode: PlayoutMode::Fill,
             next_seq,
             current_timestamp: None,
+            drain_frames: 0,
+            reported_mode: PlayoutMode::Fill,
+            lost_packets: 0,
+            late_packets: 0,
+            overflow_packets: 0,
+            total_lost: 0,
+            total_late: 0,
+            total_overflow: 0,
+        }
+    }
+
+    /// Returns the current occupancy and lifetime packet counts of this buffer.
+    pub fn stats(&self) -> PlayoutStats {
+        PlayoutStats {
+            state: self.playout_state(),
+            buffered_frames: self.buffer.len(),
+            lost_packets: self.total_lost,
+            late_packets: self.total_late,
+            overflow_packets: self.total_overflow,
+        }
+    }
+
+    fn playout_state(&self) -> PlayoutState {
+        match self.playout_mode {
+            PlayoutMode::Fill => PlayoutState::Filling,
+            PlayoutMode::Drain => PlayoutState::Draining,
+        }
+    }
+
+    /// Returns any change in playout mode or packet loss since this was last called.
+    pub fn take_change(&mut self, ssrc: RtpSsrc) -> Option<PlayoutData> {
+        let unchanged = self.playout_mode == self.reported_mode
+            && self.lost_packets == 0
+            && self.late_packets == 0
+            && self.overflow_packets == 0;
+
+        if unchanged {
+            return None;
+        }
+
+        self.reported_mode = self.playout_mode;
+
+        Some(PlayoutData {
+            ssrc,
+            state: self.playout_state(),
+            lost_packets: std::mem::take(&mut self.lost_packets),
+            late_packets: std::mem::take(&mut self.late_packets),
+            overflow_packets: std::mem::take(&mut self.overflow_packets),
+        })
+    }
+
+    /// Apply a change in playout buffer configuration to a live buffer.
+    ///
+    /// Growing the playout length returns the buffer to `Fill` until the extra
+    /// delay has been built up.
+    ///
+    /// Shrinking the playout length does not discard any buffered audio at once.
+    /// Instead, one frame of excess delay is skipped per tick until the buffer
+    /// reaches its new target, or until the buffer next empties.
+    pub fn reconfigure(&mut self, old_config: &Config, config: &Config) {
+        let old_len = old_config.playout_buffer_length.get();
+        let new_len = config.playout_buffer_length.get();
+
+        let capacity = (new_len + config.playout_spike_length).min(MAX_PLAYOUT_LEN);
+        if capacity > self.buffer.capacity() {
+            self.buffer.reserve(capacity - self.buffer.len());
+        } else {
+            self.buffer.shrink_to(capacity);
+        }
+
+        if self.current_timestamp.is_none() {
+            // Nothing is buffered; the next packet will use the new length.
+            return;
+        }
+
+        if new_len > old_len {
+            self.drain_frames = self.drain_frames.saturating_sub(new_len - old_len);
+
+            if self.buffer.len() < new_len {
+                self.playout_mode = PlayoutMode::Fill;
+            }
+        } else {
+            self.drain_frames += old_len - new_len;
+
+            if self.buffer.len() >= new_len {
+                self.playout_mode = PlayoutMode::Drain;
+            }
         }
     }
 
@@ -77,8 +178,12 @@ impl PlayoutBuffer {
 
         if desired_index < 0 {
             trace!("Missed packet arrived late, discarding from playout.");
-        } else if desired_index >= 64 {
+            self.late_packets = self.late_packets.saturating_add(1);
+            self.total_late += 1;
+        } else if desired_index >= MAX_PLAYOUT_LEN as i16 {
             trace!("Packet arrived beyond playout max length.");
+            self.overflow_packets = self.overflow_packets.saturating_add(1);
+            self.total_overflow += 1;
         } else {
             let index = desired_index as usize;
             while self.buffer.len() <= index {
@@ -92,11 +197,24 @@ impl PlayoutBuffer {
         }
     }
 
+    /// Returns whether all buffered audio has been played out, such that the next
+    /// packet begins a new playout.
+    pub fn is_idle(&self) -> bool {
+        self.current_timestamp.is_none()
+    }
+
     pub fn fetch_packet(&mut self) -> PacketLookup {
         if self.playout_mode == PlayoutMode::Fill {
             return PacketLookup::Filling;
         }
 
+        // Catch up after a reduction in playout length, but never skip our last packet.
+        if self.drain_frames > 0 && self.buffer.len() > 1 {
+            trace!("Skipping frame to reduce playout delay.");
+            self.drain_frames -= 1;
+            self.skip_frame();
+        }
+
         let out = match self.buffer.pop_front() {
             Some(Some(pkt)) => {
                 let rtp = RtpPacket::new(&pkt.packet)
@@ -118,6 +236,8 @@ impl PlayoutBuffer {
             },
             Some(None) => {
                 self.next_seq += 1;
+                self.lost_packets = self.lost_packets.saturating_add(1);
+                self.total_lost += 1;
                 PacketLookup::MissedPacket
             },
             None => PacketLookup::Filling,
@@ -126,6 +246,7 @@ impl PlayoutBuffer {
         if self.buffer.is_empty() {
             self.playout_mode = PlayoutMode::Fill;
             self.current_timestamp = None;
+            self.drain_frames = 0;
         }
 
         if let Some(ts) = self.current_timestamp.as_mut() {
@@ -138,6 +259,22 @@ impl PlayoutBuffer {
     pub fn next_seq(&self) -> RtpSequence {
         self.next_seq
     }
+
+    fn skip_frame(&mut self) {
+        match self.buffer.pop_front() {
+            Some(Some(pkt)) => {
+                let rtp = RtpPacket::new(&pkt.packet)
+                    .expect("FATAL: earlier valid packet now invalid (skip)");
+                self.next_seq = (rtp.get_sequence() + 1).0;
+            },
+            Some(None) => self.next_seq += 1,
+            None => return,
+        }
+
+        if let Some(ts) = self.current_timestamp.as_mut() {
+            *ts += &(MONO_FRAME_SIZE as u32);
+        }
+    }
 }
 
 #[inline]
@@ -145,3 +282,134 @@ fn reset_timeout(packet: &RtpPacket<'_>, config: &Config) -> RtpTimestamp {
     let t_shift = MONO_FRAME_SIZE * config.playout_buffer_length.get();
     (packet.get_timestamp() - (t_shift as u32)).0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use discortp::rtp::{MutableRtpPacket, RtpType};
+    use std::num::NonZeroUsize;
+
+    fn packet(seq: u16) -> StoredPacket {
+        let mut bytes = vec![0u8; 32];
+        let mut rtp = MutableRtpPacket::new(&mut bytes[..]).unwrap();
+        rtp.set_version(2);
+        rtp.set_payload_type(RtpType::Dynamic(120));
+        rtp.set_sequence(seq.into());
+        rtp.set_timestamp((u32::from(seq) * MONO_FRAME_SIZE as u32).into());
+
+        StoredPacket {
+            packet: bytes.into(),
+            decrypted: true,
+        }
+    }
+
+    fn fetched_seq(lookup: PacketLookup) -> Option<u16> {
+        match lookup {
+            PacketLookup::Packet(pkt) =>
+                Some(RtpPacket::new(&pkt.packet).unwrap().get_sequence().0 .0),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn shrinking_playout_skips_gradually() {
+        let old_config = Config::default().playout_buffer_length(NonZeroUsize::new(5).unwrap());
+        let new_config = Config::default().playout_buffer_length(NonZeroUsize::new(2).unwrap());
+
+        let mut buffer = PlayoutBuffer::new(8, Wrapping(0));
+        for seq in 0..5 {
+            buffer.store_packet(packet(seq), &old_config);
+        }
+
+        assert_eq!(fetched_seq(buffer.fetch_packet()), Some(0));
+        buffer.reconfigure(&old_config, &new_config);
+
+        // Each tick skips at most one frame of the excess delay.
+        let mut played = vec![];
+        for seq in 5..10 {
+            buffer.store_packet(packet(seq), &new_config);
+            played.extend(fetched_seq(buffer.fetch_packet()));
+        }
+
+        assert_eq!(played, vec![2, 4, 6, 7, 8]);
+    }
+
+    #[test]
+    fn growing_playout_withholds_packets() {
+        let old_config = Config::default().playout_buffer_length(NonZeroUsize::new(2).unwrap());
+        let new_config = Config::default().playout_buffer_length(NonZeroUsize::new(4).unwrap());
+
+        let mut buffer = PlayoutBuffer::new(8, Wrapping(0));
+        for seq in 0..2 {
+            buffer.store_packet(packet(seq), &old_config);
+        }
+
+        assert_eq!(fetched_seq(buffer.fetch_packet()), Some(0));
+        buffer.reconfigure(&old_config, &new_config);
+
+        buffer.store_packet(packet(2), &new_config);
+        assert_eq!(buffer.fetch_packet(), PacketLookup::Filling);
+        buffer.store_packet(packet(3), &new_config);
+        assert_eq!(buffer.fetch_packet(), PacketLookup::Filling);
+        buffer.store_packet(packet(4), &new_config);
+        assert_eq!(fetched_seq(buffer.fetch_packet()), Some(1));
+    }
+
+    #[test]
+    fn playout_changes_report_loss() {
+        let config = Config::default().playout_buffer_length(NonZeroUsize::new(2).unwrap());
+
+        let mut buffer = PlayoutBuffer::new(8, Wrapping(0));
+        assert_eq!(buffer.take_change(1), None);
+
+        buffer.store_packet(packet(0), &config);
+        buffer.store_packet(packet(2), &config);
+        buffer.store_packet(packet(MAX_PLAYOUT_LEN as u16 + 1), &config);
+
+        let change = buffer.take_change(1).unwrap();
+        assert_eq!(change.state, PlayoutState::Draining);
+        assert_eq!(change.overflow_packets, 1);
+        assert_eq!(change.lost_packets, 0);
+
+        assert_eq!(fetched_seq(buffer.fetch_packet()), Some(0));
+        assert_eq!(buffer.fetch_packet(), PacketLookup::MissedPacket);
+        buffer.store_packet(packet(1), &config);
+        assert_eq!(fetched_seq(buffer.fetch_packet()), Some(2));
+
+        let change = buffer.take_change(1).unwrap();
+        assert_eq!(change.state, PlayoutState::Filling);
+        assert_eq!(change.lost_packets, 1);
+        assert_eq!(change.late_packets, 1);
+        assert_eq!(change.overflow_packets, 0);
+
+        assert_eq!(buffer.take_change(1), None);
+
+        // Totals are unaffected by taking changes.
+        let stats = buffer.stats();
+        assert_eq!(stats.state, PlayoutState::Filling);
+        assert_eq!(stats.buffered_frames, 0);
+        assert_eq!(stats.lost_packets, 1);
+        assert_eq!(stats.late_packets, 1);
+        assert_eq!(stats.overflow_packets, 1);
+    }
+
+    #[test]
+    fn buffer_is_idle_only_once_drained() {
+        let config = Config::default().playout_buffer_length(NonZeroUsize::new(2).unwrap());
+
+        let mut buffer = PlayoutBuffer::new(8, Wrapping(0));
+        assert!(buffer.is_idle());
+
+        buffer.store_packet(packet(0), &config);
+        assert!(!buffer.is_idle());
+        buffer.store_packet(packet(1), &config);
+
+        assert_eq!(fetched_seq(buffer.fetch_packet()), Some(0));
+        assert!(!buffer.is_idle());
+        assert_eq!(fetched_seq(buffer.fetch_packet()), Some(1));
+        assert!(buffer.is_idle());
+
+        buffer.store_packet(packet(2), &config);
+        assert!(!buffer.is_idle());
+    }
+}
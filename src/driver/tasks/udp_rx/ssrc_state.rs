@@ -5,12 +5,13 @@ use crate::{
         tasks::error::{Error, Result},
         CryptoMode,
         DecodeMode,
+        SsrcStats,
     },
-    events::context_data::{RtpData, VoiceData},
+    events::context_data::{PlayoutData, PlayoutStats, RtpData, VoiceData},
     Config,
 };
 use audiopus::{
-    coder::Decoder as OpusDecoder,
+    coder::{Decoder as OpusDecoder, GenericCtl},
     error::{Error as OpusError, ErrorCode},
     packet::Packet as OpusPacket,
     Channels,
@@ -31,6 +32,8 @@ pub struct SsrcState {
     decode_size: PacketDecodeSize,
     pub(crate) prune_time: Instant,
     pub(crate) disconnected: bool,
+    stats: SsrcStats,
+    stats_changed: bool,
 }
 
 impl SsrcState {
@@ -44,13 +47,51 @@ impl SsrcState {
             decode_size: PacketDecodeSize::TwentyMillis,
             prune_time: Instant::now() + config.decode_state_timeout,
             disconnected: false,
+            stats: SsrcStats::default(),
+            stats_changed: true,
+        }
+    }
+
+    pub fn reconfigure(&mut self, old_config: &Config, config: &Config) {
+        self.playout_buffer.reconfigure(old_config, config);
+    }
+
+    pub fn playout_stats(&self) -> PlayoutStats {
+        self.playout_buffer.stats()
+    }
+
+    pub fn playout_change(&mut self, ssrc: RtpSsrc) -> Option<PlayoutData> {
+        self.playout_buffer.take_change(ssrc)
+    }
+
+    /// Returns this source's stream health, if it has changed since last taken.
+    pub fn take_stats_change(&mut self) -> Option<SsrcStats> {
+        std::mem::take(&mut self.stats_changed).then_some(self.stats)
+    }
+
+    fn record_loss(&mut self, frames: u16) {
+        if frames != 0 {
+            self.stats.lost_frames += u64::from(frames);
+            self.stats_changed = true;
         }
     }
 
     pub fn store_packet(&mut self, packet: StoredPacket, config: &Config) {
+        // Audio from before a full drain of the playout buffer has been completely
+        // played out, so the decoder can start afresh for this talk spurt.
+        if self.playout_buffer.is_idle() {
+            self.reset_decoder();
+        }
+
         self.playout_buffer.store_packet(packet, config);
     }
 
+    pub fn reset_decoder(&mut self) {
+        if let Err(e) = self.decoder.reset_state() {
+            warn!("Failed to reset decoder state: {:?}", e);
+        }
+    }
+
     pub fn refresh_timer(&mut self, state_timeout: Duration) {
         if !self.disconnected {
             self.prune_time = Instant::now() + state_timeout;
@@ -58,19 +99,35 @@ impl SsrcState {
     }
 
     pub fn get_voice_tick(&mut self, config: &Config) -> Result<Option<VoiceData>> {
+        let out = self.next_voice_data(config);
+
+        if out.is_err() {
+            self.stats.decode_errors += 1;
+            self.stats_changed = true;
+        }
+
+        out
+    }
+
+    fn next_voice_data(&mut self, config: &Config) -> Result<Option<VoiceData>> {
         // Acquire a packet from the playout buffer:
         // Update nexts, lasts...
         // different cases: null packet who we want to decode as a miss, and packet who we must ignore temporarily.
         let m_pkt = self.playout_buffer.fetch_packet();
         let pkt = match m_pkt {
             PacketLookup::Packet(StoredPacket { packet, decrypted }) => Some((packet, decrypted)),
-            PacketLookup::MissedPacket => None,
+            PacketLookup::MissedPacket => {
+                self.record_loss(1);
+                None
+            },
             PacketLookup::Filling => return Ok(None),
         };
 
         let mut out = VoiceData {
             packet: None,
+            opus_frame: None,
             decoded_voice: None,
+            rms: None,
         };
 
         let should_decode = config.decode_mode == DecodeMode::Decode;
@@ -81,22 +138,36 @@ impl SsrcState {
 
             let payload = rtp.payload();
             let payload_offset = CryptoMode::payload_prefix_len();
-            let payload_end_pad = payload.len() - config.crypto_mode.payload_suffix_len();
+            let payload_end_pad = payload
+                .len()
+                .checked_sub(config.crypto_mode.payload_suffix_len())
+                .filter(|end| *end >= payload_offset)
+                .ok_or_else(|| {
+                    error!("Voice packet too small for its encryption headers.");
+                    Error::IllegalVoicePacket
+                })?;
 
             // We still need to compute missed packets here in case of long loss chains or similar.
             // This occurs due to the fallback in 'store_packet' (i.e., empty buffer and massive seq difference).
             // Normal losses should be handled by the below `else` branch.
             let new_seq: u16 = rtp.get_sequence().into();
             let missed_packets = new_seq.saturating_sub(self.playout_buffer.next_seq().0);
+            self.record_loss(missed_packets);
 
-            // TODO: maybe hand over audio and extension indices alongside packet?
-            let (audio, _packet_size) = self.scan_and_decode(
+            let (audio, extension_size) = self.scan_and_decode(
                 &payload[payload_offset..payload_end_pad],
                 extensions,
                 missed_packets,
                 should_decode && decrypted,
             )?;
 
+            if decrypted {
+                let header_len = packet.len() - payload.len();
+                out.opus_frame = Some(packet.slice(
+                    header_len + payload_offset + extension_size..header_len + payload_end_pad,
+                ));
+            }
+
             let rtp_data = RtpData {
                 packet,
                 payload_offset,
@@ -104,6 +175,7 @@ impl SsrcState {
             };
 
             out.packet = Some(rtp_data);
+            out.rms = audio.as_deref().map(rms);
             out.decoded_voice = audio;
         } else if should_decode {
             let mut audio = vec![0; self.decode_size.len()];
@@ -113,6 +185,7 @@ impl SsrcState {
             let len = self.decoder.decode(None, dest_samples, false)?;
             audio.truncate(2 * len);
 
+            out.rms = Some(rms(&audio));
             out.decoded_voice = Some(audio);
         }
 
@@ -137,6 +210,12 @@ impl SsrcState {
             Ok(0)
         }?;
 
+        // Extension lengths are sender-controlled, and may claim more space than remains.
+        if start > data.len() {
+            error!("Extension packet larger than its voice packet.");
+            return Err(Error::IllegalVoicePacket);
+        }
+
         let pkt = if decode {
             let mut out = vec![0; self.decode_size.len()];
 
@@ -191,6 +270,113 @@ impl SsrcState {
             None
         };
 
-        Ok((pkt, data.len() - start))
+        Ok((pkt, start))
+    }
+}
+
+/// Computes the RMS amplitude of a block of PCM audio, in sample units.
+fn rms(samples: &[i16]) -> u16 {
+    if samples.is_empty() {
+        return 0;
+    }
+
+    let sum_sq: f64 = samples
+        .iter()
+        .map(|&s| {
+            let s = f64::from(s);
+            s * s
+        })
+        .sum();
+
+    // `i16::MIN` alone may round above `i16::MAX`: keep within the documented range.
+    ((sum_sq / samples.len() as f64).sqrt().round() as u16).min(i16::MAX as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::crypto::TAG_SIZE;
+    use discortp::{
+        rtp::{MutableRtpPacket, RtpType},
+        MutablePacket,
+    };
+    use std::num::NonZeroUsize;
+
+    const OPUS_FRAME: [u8; 3] = [0xf8, 0xff, 0xfe];
+
+    fn config() -> Config {
+        Config::default()
+            .crypto_mode(CryptoMode::Normal)
+            .decode_mode(DecodeMode::Decrypt)
+            .playout_buffer_length(NonZeroUsize::new(1).unwrap())
+    }
+
+    /// Builds a decrypted voice packet whose payload is `extension` followed by an Opus frame.
+    fn packet(extension: Option<&[u8]>) -> StoredPacket {
+        let ext = extension.unwrap_or_default();
+        let mut bytes = vec![0u8; RtpPacket::minimum_packet_size() + TAG_SIZE + ext.len() + 3];
+        let mut rtp = MutableRtpPacket::new(&mut bytes[..]).unwrap();
+        rtp.set_version(RTP_VERSION);
+        rtp.set_payload_type(RtpType::Dynamic(120));
+        rtp.set_extension(u8::from(extension.is_some()));
+
+        let payload = &mut rtp.payload_mut()[TAG_SIZE..];
+        payload[..ext.len()].copy_from_slice(ext);
+        payload[ext.len()..].copy_from_slice(&OPUS_FRAME);
+
+        StoredPacket {
+            packet: bytes.into(),
+            decrypted: true,
+        }
+    }
+
+    fn voice_tick(packet: StoredPacket) -> Result<Option<VoiceData>> {
+        let config = config();
+        let mut state = SsrcState::new(&RtpPacket::new(&packet.packet).unwrap(), &config);
+        state.store_packet(packet, &config);
+        state.get_voice_tick(&config)
+    }
+
+    #[test]
+    fn rms_is_measured_in_sample_units() {
+        assert_eq!(rms(&[]), 0);
+        assert_eq!(rms(&[0; 16]), 0);
+        assert_eq!(rms(&[1000, -1000, 1000, -1000]), 1000);
+        assert_eq!(rms(&[3, 4, 3, 4]), 4);
+        assert_eq!(rms(&[i16::MAX, i16::MIN]), i16::MAX as u16);
+    }
+
+    #[test]
+    fn extensions_are_skipped_in_opus_frames() {
+        // One-byte header profile, with one 32-bit word of extension data.
+        let pkt = packet(Some(&[0xbe, 0xde, 0x00, 0x01, 0x10, 0xff, 0x00, 0x00]));
+        let data = voice_tick(pkt).unwrap().unwrap();
+        assert_eq!(data.opus_frame.as_deref(), Some(&OPUS_FRAME[..]));
+
+        let data = voice_tick(packet(None)).unwrap().unwrap();
+        assert_eq!(data.opus_frame.as_deref(), Some(&OPUS_FRAME[..]));
+    }
+
+    #[test]
+    fn truncated_extensions_are_rejected() {
+        // Extension bit set, but too few bytes remain for its header.
+        let pkt = packet(None);
+        let mut bytes = pkt.packet[..RtpPacket::minimum_packet_size() + TAG_SIZE + 2].to_vec();
+        MutableRtpPacket::new(&mut bytes[..])
+            .unwrap()
+            .set_extension(1);
+        let pkt = StoredPacket {
+            packet: bytes.into(),
+            decrypted: true,
+        };
+
+        assert!(matches!(voice_tick(pkt), Err(Error::IllegalVoicePacket)));
+    }
+
+    #[test]
+    fn oversized_extensions_are_rejected() {
+        // Claims 16 words of extension data, far beyond the end of the packet.
+        let pkt = packet(Some(&[0xbe, 0xde, 0x00, 0x10]));
+        assert!(matches!(voice_tick(pkt), Err(Error::IllegalVoicePacket)));
     }
 }
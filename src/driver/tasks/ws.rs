@@ -1,7 +1,9 @@
 use super::message::*;
 use crate::{
-    events::CoreContext,
+    driver::connection::stats::ConnectionStatBlock,
+    events::{context_data::ClientConnectData, CoreContext},
     model::{
+        id::UserId,
         payload::{Heartbeat, Speaking},
         CloseCode as VoiceCloseCode,
         Event as GatewayEvent,
@@ -13,9 +15,7 @@ use crate::{
 };
 use flume::Receiver;
 use rand::random;
-#[cfg(feature = "receive")]
-use std::sync::Arc;
-use std::time::Duration;
+use std::{collections::HashSet, sync::Arc, time::Duration};
 use tokio::{
     select,
     time::{sleep_until, Instant},
@@ -33,15 +33,19 @@ pub(crate) struct AuxNetwork {
 
     speaking: SpeakingState,
     last_heartbeat_nonce: Option<u64>,
+    last_heartbeat_sent: Option<Instant>,
+    known_users: KnownUsers,
 
     attempt_idx: usize,
     info: ConnectionInfo,
+    stats: Arc<ConnectionStatBlock>,
 
     #[cfg(feature = "receive")]
     ssrc_signalling: Arc<SsrcTracker>,
 }
 
 impl AuxNetwork {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         evt_rx: Receiver<WsMessage>,
         ws_client: WsStream,
@@ -49,6 +53,7 @@ impl AuxNetwork {
         heartbeat_interval: f64,
         attempt_idx: usize,
         info: ConnectionInfo,
+        stats: Arc<ConnectionStatBlock>,
         #[cfg(feature = "receive")] ssrc_signalling: Arc<SsrcTracker>,
     ) -> Self {
         Self {
@@ -61,9 +66,12 @@ impl AuxNetwork {
 
             speaking: SpeakingState::empty(),
             last_heartbeat_nonce: None,
+            last_heartbeat_sent: None,
+            known_users: KnownUsers::default(),
 
             attempt_idx,
             info,
+            stats,
 
             #[cfg(feature = "receive")]
             ssrc_signalling,
@@ -117,13 +125,16 @@ impl AuxNetwork {
                         Ok(WsMessage::ReplaceInterconnect(i)) => {
                             *interconnect = i;
                         },
+                        Ok(WsMessage::SetInfo(info)) => {
+                            self.info = info;
+                        },
                         Ok(WsMessage::SetKeepalive(keepalive)) => {
                             self.heartbeat_interval = Duration::from_secs_f64(keepalive / 1000.0);
                             next_heartbeat = self.next_heartbeat();
                         },
-                        Ok(WsMessage::Speaking(is_speaking)) => {
-                            if self.speaking.contains(SpeakingState::MICROPHONE) != is_speaking && !self.dont_send {
-                                self.speaking.set(SpeakingState::MICROPHONE, is_speaking);
+                        Ok(WsMessage::Speaking(speaking)) => {
+                            if self.speaking != speaking && !self.dont_send {
+                                self.speaking = speaking;
                                 info!("Changing to {:?}", self.speaking);
 
                                 let ssu_status = self.ws_client
@@ -176,6 +187,7 @@ impl AuxNetwork {
     async fn send_heartbeat(&mut self) -> Result<(), WsError> {
         let nonce = random::<u64>();
         self.last_heartbeat_nonce = Some(nonce);
+        self.last_heartbeat_sent = Some(Instant::now());
 
         trace!("Sent heartbeat {:?}", self.speaking);
 
@@ -196,14 +208,31 @@ impl AuxNetwork {
                     self.ssrc_signalling.user_ssrc_map.insert(*user_id, ev.ssrc);
                 }
 
+                if let Some(connect) = self.known_users.saw_speaking(&ev) {
+                    drop(interconnect.events.send(EventMessage::FireCoreEvent(
+                        CoreContext::ClientConnect(connect),
+                    )));
+                }
+
                 drop(interconnect.events.send(EventMessage::FireCoreEvent(
                     CoreContext::SpeakingStateUpdate(ev),
                 )));
             },
             GatewayEvent::ClientConnect(ev) => {
                 debug!("Received discontinued ClientConnect: {:?}", ev);
+
+                if self.known_users.insert(ev.user_id) {
+                    drop(interconnect.events.send(EventMessage::FireCoreEvent(
+                        CoreContext::ClientConnect(ClientConnectData {
+                            user_id: ev.user_id,
+                            ssrc: ev.audio_ssrc,
+                        }),
+                    )));
+                }
             },
             GatewayEvent::ClientDisconnect(ev) => {
+                self.known_users.remove(ev.user_id);
+
                 #[cfg(feature = "receive")]
                 {
                     self.ssrc_signalling.disconnected_users.insert(ev.user_id);
@@ -217,6 +246,9 @@ impl AuxNetwork {
                 if let Some(nonce) = self.last_heartbeat_nonce.take() {
                     if ev.nonce == nonce {
                         trace!("Heartbeat ACK received.");
+                        if let Some(sent) = self.last_heartbeat_sent.take() {
+                            self.stats.record_heartbeat(sent.elapsed());
+                        }
                     } else {
                         warn!(
                             "Heartbeat nonce mismatch! Expected {}, saw {}.",
@@ -232,6 +264,32 @@ impl AuxNetwork {
     }
 }
 
+/// Users seen in this voice session, used to derive `ClientConnect` events
+/// now that Discord no longer sends them.
+#[derive(Debug, Default)]
+struct KnownUsers(HashSet<UserId>);
+
+impl KnownUsers {
+    /// Records a speaking state update, returning connection details if its user is new.
+    fn saw_speaking(&mut self, ev: &Speaking) -> Option<ClientConnectData> {
+        let user_id = ev.user_id?;
+
+        self.insert(user_id).then_some(ClientConnectData {
+            user_id,
+            ssrc: ev.ssrc,
+        })
+    }
+
+    /// Records a user, returning whether they were not already known.
+    fn insert(&mut self, user_id: UserId) -> bool {
+        self.0.insert(user_id)
+    }
+
+    fn remove(&mut self, user_id: UserId) {
+        self.0.remove(&user_id);
+    }
+}
+
 #[instrument(skip(interconnect, aux))]
 pub(crate) async fn runner(mut interconnect: Interconnect, mut aux: AuxNetwork) {
     trace!("WS thread started.");
@@ -256,3 +314,34 @@ fn ws_error_is_not_final(err: &WsError) -> bool {
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn speaking(user_id: Option<u64>, ssrc: u32) -> Speaking {
+        Speaking {
+            delay: None,
+            speaking: SpeakingState::MICROPHONE,
+            ssrc,
+            user_id: user_id.map(UserId),
+        }
+    }
+
+    #[test]
+    fn first_speaking_update_per_user_connects() {
+        let mut users = KnownUsers::default();
+
+        let connect = users.saw_speaking(&speaking(Some(1), 10)).unwrap();
+        assert_eq!(connect.user_id, UserId(1));
+        assert_eq!(connect.ssrc, 10);
+
+        assert!(users.saw_speaking(&speaking(Some(1), 10)).is_none());
+        assert!(users.saw_speaking(&speaking(None, 20)).is_none());
+        assert!(users.saw_speaking(&speaking(Some(2), 20)).is_some());
+
+        // Rejoining users are announced again.
+        users.remove(UserId(1));
+        assert!(users.saw_speaking(&speaking(Some(1), 30)).is_some());
+    }
+}
@@ -48,7 +48,7 @@ impl Mixer {
 
         let ic = Interconnect {
             core: core_tx,
-            events: event_tx,
+            events: EventSender::new(event_tx, None),
             mixer: mix_tx,
         };
 
@@ -70,6 +70,7 @@ impl Mixer {
             crypto_state: CryptoState::Normal,
             udp_rx: udp_receiver_tx,
             udp_tx,
+            stats: Arc::default(),
         };
 
         #[cfg(not(feature = "receive"))]
@@ -77,6 +78,7 @@ impl Mixer {
             cipher: Cipher::new_from_slice(&[0u8; KEY_SIZE]).unwrap(),
             crypto_state: CryptoState::Normal,
             udp_tx,
+            stats: Arc::default(),
         };
 
         out.conn_active = Some(fake_conn);
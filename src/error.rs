@@ -1,5 +1,7 @@
 //! Driver and gateway error handling.
 
+#[cfg(feature = "gateway")]
+use crate::id::ChannelId;
 #[cfg(feature = "serenity")]
 use futures::channel::mpsc::TrySendError;
 #[cfg(not(feature = "simd-json"))]
@@ -9,6 +11,7 @@ use serenity::gateway::ShardRunnerMessage;
 #[cfg(feature = "simd-json")]
 pub use simd_json::Error as JsonError;
 #[cfg(feature = "gateway")]
+use std::time::Duration;
 use std::{error::Error, fmt};
 #[cfg(feature = "twilight")]
 use twilight_gateway::error::SendError;
@@ -24,10 +27,14 @@ pub enum JoinError {
     /// No available gateway connection was provided to send
     /// voice state update messages.
     NoSender,
-    /// Tried to leave a [`Call`] which was not found.
+    /// Tried to leave or move a [`Call`] which was not found.
     ///
     /// [`Call`]: crate::Call
     NoCall,
+    /// Tried to move a [`Call`] which is not in, or joining, any voice channel.
+    ///
+    /// [`Call`]: crate::Call
+    NotConnected,
     /// Connection details were not received from Discord in the
     /// time given in [the `Call`'s configuration].
     ///
@@ -39,7 +46,32 @@ pub enum JoinError {
     /// re-attempting connection.*
     ///
     /// [the `Call`'s configuration]: crate::Config
-    TimedOut,
+    TimedOut {
+        /// Time spent waiting for Discord before giving up.
+        elapsed: Duration,
+    },
+    /// A voice server update was received from Discord, but no voice state
+    /// update for this bot arrived in the time given in [the `Call`'s configuration].
+    ///
+    /// This almost always means that the `GUILD_VOICE_STATES` gateway intent
+    /// has not been enabled for this bot's shards, which Songbird needs to learn
+    /// its voice session ID.
+    ///
+    /// [the `Call`'s configuration]: crate::Config
+    MissingVoiceState {
+        /// Time spent waiting for Discord before giving up.
+        elapsed: Duration,
+    },
+    /// Discord moved this bot out of the voice channel it was joining, either to
+    /// another channel or out of voice entirely.
+    ///
+    /// This can occur if the bot is disconnected or moved by a moderator, or lacks
+    /// permission to stay in the requested channel.
+    MovedOut {
+        /// ID of the voice channel the bot was moved to, or `None` if it was
+        /// disconnected from voice.
+        channel_id: Option<ChannelId>,
+    },
     #[cfg(feature = "driver")]
     /// The driver failed to establish a voice connection.
     ///
@@ -62,8 +94,12 @@ impl JoinError {
     ///
     /// Failure to `leave` before rejoining may cause further
     /// timeouts.
+    #[must_use]
     pub fn should_leave_server(&self) -> bool {
-        matches!(self, JoinError::TimedOut)
+        matches!(
+            self,
+            JoinError::TimedOut { .. } | JoinError::MissingVoiceState { .. }
+        )
     }
 
     #[cfg(feature = "driver")]
@@ -74,6 +110,7 @@ impl JoinError {
     /// timeouts.
     ///
     /// [`Driver::connect`]: crate::driver::Driver
+    #[must_use]
     pub fn should_reconnect_driver(&self) -> bool {
         matches!(self, JoinError::Driver(_))
     }
@@ -87,7 +124,23 @@ impl fmt::Display for JoinError {
             JoinError::Dropped => write!(f, "request was cancelled/dropped"),
             JoinError::NoSender => write!(f, "no gateway destination"),
             JoinError::NoCall => write!(f, "tried to leave a non-existent call"),
-            JoinError::TimedOut => write!(f, "gateway response from Discord timed out"),
+            JoinError::NotConnected => write!(f, "tried to move a call with no voice channel"),
+            JoinError::TimedOut { elapsed } => write!(
+                f,
+                "gateway response from Discord timed out after {}ms",
+                elapsed.as_millis()
+            ),
+            JoinError::MissingVoiceState { elapsed } => write!(
+                f,
+                "no voice state update received from Discord after {}ms \
+                 (is the GUILD_VOICE_STATES intent enabled?)",
+                elapsed.as_millis()
+            ),
+            JoinError::MovedOut {
+                channel_id: Some(id),
+            } => write!(f, "moved to channel {id} while joining"),
+            JoinError::MovedOut { channel_id: None } =>
+                write!(f, "disconnected from voice while joining"),
             #[cfg(feature = "driver")]
             JoinError::Driver(_) => write!(f, "establishing connection failed"),
             #[cfg(feature = "serenity")]
@@ -105,7 +158,10 @@ impl Error for JoinError {
             JoinError::Dropped => None,
             JoinError::NoSender => None,
             JoinError::NoCall => None,
-            JoinError::TimedOut => None,
+            JoinError::NotConnected => None,
+            JoinError::TimedOut { .. } => None,
+            JoinError::MissingVoiceState { .. } => None,
+            JoinError::MovedOut { .. } => None,
             #[cfg(feature = "driver")]
             JoinError::Driver(e) => Some(e),
             #[cfg(feature = "serenity")]
@@ -141,6 +197,101 @@ impl From<ConnectionError> for JoinError {
 /// Convenience type for Discord gateway error handling.
 pub type JoinResult<T> = Result<T, JoinError>;
 
+/// Error returned when building a [`ConnectionInfo`] from details
+/// gathered by an external gateway.
+///
+/// [`ConnectionInfo`]: crate::ConnectionInfo
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ConnectionInfoError {
+    /// No voice server endpoint was provided.
+    MissingEndpoint,
+    /// No voice session ID was provided.
+    MissingSessionId,
+    /// No voice session token was provided.
+    MissingToken,
+    /// The provided endpoint was not a valid `host[:port]` pair.
+    InvalidEndpoint(String),
+}
+
+impl fmt::Display for ConnectionInfoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to build connection info: ")?;
+        match self {
+            Self::MissingEndpoint => write!(f, "no endpoint was given"),
+            Self::MissingSessionId => write!(f, "no session ID was given"),
+            Self::MissingToken => write!(f, "no token was given"),
+            Self::InvalidEndpoint(endpoint) => write!(f, "endpoint {endpoint:?} was invalid"),
+        }
+    }
+}
+
+impl Error for ConnectionInfoError {}
+
+/// Error returned by [`Config::validate`] when a configuration contains
+/// conflicting or out-of-range settings.
+///
+/// [`Config::validate`]: crate::Config::validate
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ConfigError {
+    #[cfg(feature = "driver")]
+    /// The Opus encoder complexity was above `10`.
+    OpusComplexity(u8),
+    #[cfg(feature = "driver")]
+    /// The UDP keepalive interval was below [`UDP_KEEPALIVE_GAP_MIN`].
+    ///
+    /// [`UDP_KEEPALIVE_GAP_MIN`]: crate::constants::UDP_KEEPALIVE_GAP_MIN
+    KeepaliveInterval(std::time::Duration),
+    #[cfg(feature = "driver")]
+    /// A driver timeout of zero was set, which would fail every connection attempt.
+    ZeroDriverTimeout,
+    #[cfg(feature = "driver")]
+    /// The RTP version did not fit in the 2 bits allowed by the RTP header.
+    RtpVersion(u8),
+    #[cfg(feature = "driver")]
+    /// The RTP payload type did not fit in the 7 bits allowed by the RTP header.
+    RtpPayloadType(u8),
+    #[cfg(feature = "gateway")]
+    /// A gateway timeout of zero was set, which would fail every join attempt.
+    ZeroGatewayTimeout,
+    #[cfg(all(feature = "driver", feature = "receive"))]
+    /// Received voice was set to be mixed without [`DecodeMode::Decode`].
+    ///
+    /// [`DecodeMode::Decode`]: crate::driver::DecodeMode::Decode
+    MixRequiresDecode,
+    #[cfg(all(feature = "driver", feature = "receive"))]
+    /// The playout buffer length was above the maximum of 64 packets.
+    PlayoutBufferTooLarge(usize),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid config: ")?;
+        match self {
+            #[cfg(feature = "driver")]
+            Self::OpusComplexity(c) => write!(f, "opus complexity {c} is above 10"),
+            #[cfg(feature = "driver")]
+            Self::KeepaliveInterval(d) => write!(f, "keepalive interval {d:?} is too short"),
+            #[cfg(feature = "driver")]
+            Self::ZeroDriverTimeout => write!(f, "driver timeout is zero"),
+            #[cfg(feature = "driver")]
+            Self::RtpVersion(v) => write!(f, "RTP version {v} is above 3"),
+            #[cfg(feature = "driver")]
+            Self::RtpPayloadType(t) => write!(f, "RTP payload type {t} is above 127"),
+            #[cfg(feature = "gateway")]
+            Self::ZeroGatewayTimeout => write!(f, "gateway timeout is zero"),
+            #[cfg(all(feature = "driver", feature = "receive"))]
+            Self::MixRequiresDecode => write!(f, "mixing received voice requires decoding"),
+            #[cfg(all(feature = "driver", feature = "receive"))]
+            Self::PlayoutBufferTooLarge(n) =>
+                write!(f, "playout buffer of {n} packets exceeds the maximum of 64"),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
 #[cfg(feature = "driver")]
 pub use crate::{
     driver::{
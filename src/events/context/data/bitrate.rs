@@ -0,0 +1,17 @@
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+/// A change in the Opus encoder's bitrate, made in response to packet loss
+/// reported by Discord's voice servers.
+///
+/// See [`CoreEvent::BitrateChange`] for when this is fired.
+///
+/// [`CoreEvent::BitrateChange`]: crate::events::CoreEvent::BitrateChange
+pub struct BitrateChangeData {
+    /// New encoder bitrate, in bits per second.
+    pub bitrate: i32,
+    /// Previous encoder bitrate, in bits per second.
+    pub previous_bitrate: i32,
+    /// Fraction of sent packets reported lost (out of 256) in the receiver
+    /// report which prompted this change.
+    pub fraction_lost: u8,
+}
@@ -0,0 +1,15 @@
+use crate::model::id::UserId;
+
+/// A user who has been seen in the voice session for the first time.
+///
+/// See [`CoreEvent::ClientConnect`] for when this is fired.
+///
+/// [`CoreEvent::ClientConnect`]: crate::events::CoreEvent::ClientConnect
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct ClientConnectData {
+    /// ID of the connecting user.
+    pub user_id: UserId,
+    /// SSRC of any audio packets sent by this user.
+    pub ssrc: u32,
+}
@@ -1,4 +1,4 @@
-use crate::id::*;
+use crate::{driver::CryptoMode, id::*};
 
 /// Voice connection details gathered at setup/reinstantiation.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -29,4 +29,13 @@ pub struct ConnectData<'a> {
     ///
     /// [RTP SSRC]: https://tools.ietf.org/html/rfc3550#section-3
     pub ssrc: u32,
+    /// The encryption scheme negotiated with the voice server for this session.
+    ///
+    /// This is the first of [`Config::crypto_mode`] and [`Config::crypto_mode_fallbacks`]
+    /// which the server supports, chosen when the session is established. Reconnections
+    /// which resume an existing session keep its original mode.
+    ///
+    /// [`Config::crypto_mode`]: crate::Config::crypto_mode
+    /// [`Config::crypto_mode_fallbacks`]: crate::Config::crypto_mode_fallbacks
+    pub crypto_mode: CryptoMode,
 }
@@ -31,6 +31,18 @@ pub struct DisconnectData<'a> {
     pub session_id: &'a str,
 }
 
+impl DisconnectData<'_> {
+    /// Returns whether Discord forcibly ended this voice session.
+    ///
+    /// This is the case when a running call's websocket was closed with a code
+    /// that forbids resumption, such as when the bot is kicked from its channel or the
+    /// channel is deleted. See [`DisconnectReason::is_forced`].
+    #[must_use]
+    pub fn is_forced(&self) -> bool {
+        self.kind == DisconnectKind::Runtime && self.reason.is_some_and(|r| r.is_forced())
+    }
+}
+
 /// The location that a voice connection was terminated.
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 #[non_exhaustive]
@@ -86,6 +98,29 @@ pub enum DisconnectReason {
     WsClosed(Option<VoiceCloseCode>),
 }
 
+impl DisconnectReason {
+    /// Returns the voice gateway close code sent by Discord, if any.
+    #[must_use]
+    pub fn close_code(&self) -> Option<VoiceCloseCode> {
+        match self {
+            Self::WsClosed(code) => *code,
+            _ => None,
+        }
+    }
+
+    /// Returns whether Discord closed the session with a code which forbids resumption.
+    ///
+    /// Transient failures (e.g., I/O errors or a voice server crash) are not forced:
+    /// songbird attempts to resume these itself. Forced disconnects such as
+    /// [`VoiceCloseCode::Disconnected`] (the bot was kicked, or its channel was deleted)
+    /// or [`VoiceCloseCode::SessionInvalid`] should not be retried without first
+    /// rejoining via the gateway.
+    #[must_use]
+    pub fn is_forced(&self) -> bool {
+        self.close_code().is_some_and(|c| !c.should_resume())
+    }
+}
+
 impl From<&ConnectionError> for DisconnectReason {
     fn from(e: &ConnectionError) -> Self {
         match e {
@@ -107,6 +142,10 @@ impl From<&ConnectionError> for DisconnectReason {
 
 impl From<&WsError> for DisconnectReason {
     fn from(e: &WsError) -> Self {
+        if let WsError::Proxy(_) = e {
+            return Self::Io;
+        }
+
         Self::WsClosed(match e {
             WsError::WsClosed(Some(frame)) => match frame.code {
                 CloseCode::Library(l) => VoiceCloseCode::from_u16(l),
@@ -116,3 +155,30 @@ impl From<&WsError> for DisconnectReason {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+
+    fn closed_with(code: u16) -> DisconnectReason {
+        (&WsError::WsClosed(Some(CloseFrame {
+            code: CloseCode::Library(code),
+            reason: "".into(),
+        })))
+            .into()
+    }
+
+    #[test]
+    fn close_codes_distinguish_forced_disconnects() {
+        let kicked = closed_with(4014);
+        assert_eq!(kicked.close_code(), Some(VoiceCloseCode::Disconnected));
+        assert!(kicked.is_forced());
+        assert!(closed_with(4006).is_forced());
+
+        assert!(!closed_with(4015).is_forced());
+        assert!(!DisconnectReason::WsClosed(None).is_forced());
+        assert!(!DisconnectReason::Io.is_forced());
+        assert_eq!(DisconnectReason::Io.close_code(), None);
+    }
+}
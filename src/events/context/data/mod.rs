@@ -1,9 +1,14 @@
 //! Types containing the main body of an [`EventContext`].
 //!
 //! [`EventContext`]: super::EventContext
+#[cfg(feature = "receive")]
+mod bitrate;
+mod client_connect;
 mod connect;
 mod disconnect;
 #[cfg(feature = "receive")]
+mod playout;
+#[cfg(feature = "receive")]
 mod rtcp;
 #[cfg(feature = "receive")]
 mod rtp;
@@ -13,6 +18,6 @@ mod voice;
 #[cfg(feature = "receive")]
 use bytes::Bytes;
 
-pub use self::{connect::*, disconnect::*};
 #[cfg(feature = "receive")]
-pub use self::{rtcp::*, rtp::*, voice::*};
+pub use self::{bitrate::*, playout::*, rtcp::*, rtp::*, voice::*};
+pub use self::{client_connect::*, connect::*, disconnect::*};
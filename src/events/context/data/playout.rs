@@ -0,0 +1,66 @@
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+/// Changes in the state of one user's playout (jitter) buffer, and any packets lost
+/// or discarded since the previous such event.
+///
+/// See [`CoreEvent::PlayoutChange`] for when this is fired.
+///
+/// [`CoreEvent::PlayoutChange`]: crate::events::CoreEvent::PlayoutChange
+pub struct PlayoutData {
+    /// SSRC of the stream whose playout buffer changed.
+    pub ssrc: u32,
+    /// Whether the playout buffer is building up delay or clocking out packets.
+    pub state: PlayoutState,
+    /// Number of packets which never arrived in time to be played out.
+    pub lost_packets: u32,
+    /// Number of packets discarded for arriving after their playout time.
+    pub late_packets: u32,
+    /// Number of packets discarded for arriving too far ahead of the current
+    /// playout position to be buffered.
+    pub overflow_packets: u32,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+/// A snapshot of one user's playout (jitter) buffer, reported on every [`VoiceTick`].
+///
+/// Unlike [`PlayoutData`], packet counts here are running totals for the lifetime of
+/// the stream. These are intended for logging and graphing receive health, e.g., to
+/// choose a [`Config::playout_buffer_length`] suited to users' network conditions.
+///
+/// [`VoiceTick`]: super::VoiceTick
+/// [`Config::playout_buffer_length`]: crate::Config::playout_buffer_length
+pub struct PlayoutStats {
+    /// Whether the playout buffer is building up delay or clocking out packets.
+    pub state: PlayoutState,
+    /// Number of frames currently held in the buffer, including slots for packets
+    /// which have not yet arrived.
+    ///
+    /// This is the stream's current playout delay, in 20ms frames.
+    pub buffered_frames: usize,
+    /// Total number of packets which never arrived in time to be played out.
+    pub lost_packets: u64,
+    /// Total number of packets discarded for arriving after their playout time.
+    pub late_packets: u64,
+    /// Total number of packets discarded for arriving too far ahead of the current
+    /// playout position to be buffered.
+    pub overflow_packets: u64,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+/// Whether a user's playout buffer is currently producing audio.
+pub enum PlayoutState {
+    /// The buffer is accumulating [`Config::playout_buffer_length`] packets
+    /// before playout begins.
+    ///
+    /// A stream returns to this state whenever its buffer runs dry, such as when a
+    /// user stops speaking or when packets are delayed by the network.
+    ///
+    /// [`Config::playout_buffer_length`]: crate::Config::playout_buffer_length
+    Filling,
+    /// Packets are being clocked out on every [`VoiceTick`].
+    ///
+    /// [`VoiceTick`]: super::VoiceTick
+    Draining,
+}
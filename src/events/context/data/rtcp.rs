@@ -1,4 +1,12 @@
-use discortp::rtcp::RtcpPacket;
+use discortp::{
+    rtcp::{
+        report::{ReportBlock, ReportBlockPacket, SenderInfoPacket, SenderReportPacket},
+        RtcpPacket,
+    },
+    FromPacket,
+    PacketSize,
+};
+use std::time::Duration;
 
 use super::*;
 
@@ -25,4 +33,146 @@ impl RtcpData {
         RtcpPacket::new(&self.packet)
             .expect("FATAL: leaked illegally small RTP packet from UDP Rx task.")
     }
+
+    /// Parses the sender information of this packet, if it is a sender report.
+    ///
+    /// This requires that the packet body was decrypted, i.e., that the driver
+    /// was configured with a [`DecodeMode`] other than [`DecodeMode::Pass`].
+    ///
+    /// [`DecodeMode`]: crate::driver::DecodeMode
+    /// [`DecodeMode::Pass`]: crate::driver::DecodeMode::Pass
+    #[must_use]
+    pub fn sender_report(&self) -> Option<RtcpSenderReport> {
+        let RtcpPacket::SenderReport(sr) = self.rtcp() else {
+            return None;
+        };
+
+        let info = SenderInfoPacket::new(self.body()?)?;
+
+        Some(RtcpSenderReport {
+            ssrc: sr.get_ssrc(),
+            ntp_timestamp: (u64::from(info.get_ntp_timestamp_second()) << 32)
+                | u64::from(info.get_ntp_timestamp_fraction()),
+            rtp_timestamp: info.get_rtp_timestamp(),
+            packet_count: info.get_pkt_count(),
+            octet_count: info.get_byte_count(),
+        })
+    }
+
+    /// Parses the reception report blocks contained in this packet, if it is a
+    /// sender or receiver report.
+    ///
+    /// As with [`Self::sender_report`], this requires that the packet body was decrypted.
+    #[must_use]
+    pub fn report_blocks(&self) -> Option<Vec<ReportBlock>> {
+        let (count, skip) = match self.rtcp() {
+            RtcpPacket::SenderReport(sr) => (
+                sr.get_rx_report_count(),
+                SenderInfoPacket::minimum_packet_size(),
+            ),
+            RtcpPacket::ReceiverReport(rr) => (rr.get_rx_report_count(), 0),
+            _ => return None,
+        };
+
+        let mut blocks = Vec::with_capacity(count.into());
+        let mut body = self.body()?.get(skip..)?;
+
+        for _ in 0..count {
+            let block = ReportBlockPacket::new(body)?;
+            blocks.push(block.from_packet());
+            body = &body[block.packet_size()..];
+        }
+
+        Some(blocks)
+    }
+
+    /// Decrypted report body, following the fixed header and SSRC.
+    ///
+    /// Sender and receiver reports share the same header layout.
+    fn body(&self) -> Option<&[u8]> {
+        let payload = self
+            .packet
+            .get(SenderReportPacket::minimum_packet_size()..)?;
+        let end = payload.len().checked_sub(self.payload_end_pad)?;
+        payload.get(self.payload_offset..end)
+    }
+}
+
+/// Sender timing and volume information, parsed from an RTCP sender report.
+///
+/// Pairing `ntp_timestamp` with `rtp_timestamp` allows the RTP timestamps of
+/// each SSRC to be mapped onto a shared wallclock, to align received audio.
+///
+/// See the relevant [RTP RFC section](https://tools.ietf.org/html/rfc3550#section-6.4.1).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct RtcpSenderReport {
+    /// SSRC of the stream which sent this report.
+    pub ssrc: u32,
+    /// Wallclock time at which this report was sent, as a 64-bit NTP timestamp.
+    ///
+    /// The upper 32 bits count seconds since 1900-01-01, and the lower 32 bits
+    /// are a binary fraction of a second.
+    pub ntp_timestamp: u64,
+    /// The same instant as `ntp_timestamp`, in the units and offset of this
+    /// stream's RTP timestamps.
+    pub rtp_timestamp: u32,
+    /// Total number of RTP packets sent by this source.
+    pub packet_count: u32,
+    /// Total number of payload bytes sent by this source.
+    pub octet_count: u32,
+}
+
+impl RtcpSenderReport {
+    /// Converts `ntp_timestamp` into the time elapsed since the NTP epoch (1900-01-01).
+    #[must_use]
+    pub fn ntp_time(&self) -> Duration {
+        let secs = self.ntp_timestamp >> 32;
+        let nanos = ((self.ntp_timestamp & u64::from(u32::MAX)) * 1_000_000_000) >> 32;
+
+        Duration::new(secs, nanos as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sender_report(ssrc: u32, blocks: u8) -> Vec<u8> {
+        let mut pkt = vec![0x80 | blocks, 200];
+        pkt.extend_from_slice(&(6 + 6 * u16::from(blocks)).to_be_bytes());
+        pkt.extend_from_slice(&ssrc.to_be_bytes());
+
+        // NTP seconds and fraction, RTP timestamp, packet and octet counts.
+        for field in [3_900_000_000u32, 1 << 31, 48_000, 100, 16_000] {
+            pkt.extend_from_slice(&field.to_be_bytes());
+        }
+
+        for i in 0..blocks {
+            pkt.extend_from_slice(&(u32::from(i) + 1).to_be_bytes());
+            pkt.extend_from_slice(&[0u8; 20]);
+        }
+
+        pkt
+    }
+
+    #[test]
+    fn sender_report_fields_are_parsed() {
+        let data = RtcpData {
+            packet: sender_report(1234, 2).into(),
+            payload_offset: 0,
+            payload_end_pad: 0,
+        };
+
+        let sr = data.sender_report().unwrap();
+        assert_eq!(sr.ssrc, 1234);
+        assert_eq!(sr.ntp_time(), Duration::new(3_900_000_000, 500_000_000));
+        assert_eq!(sr.rtp_timestamp, 48_000);
+        assert_eq!(sr.packet_count, 100);
+        assert_eq!(sr.octet_count, 16_000);
+
+        let blocks = data.report_blocks().unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[1].ssrc, 2);
+    }
 }
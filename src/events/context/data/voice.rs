@@ -17,6 +17,22 @@ pub struct VoiceTick {
 
     /// Set of all SSRCs currently known in the call who aren't included in [`Self::speaking`].
     pub silent: HashSet<u32>,
+
+    /// Decoded audio of all users in [`Self::speaking`], summed into one buffer.
+    ///
+    /// This uses the same format as [`VoiceData::decoded_voice`]: 20ms of 16-bit stereo
+    /// PCM audio at 48kHz. Summed samples which exceed the range of an `i16` are clamped,
+    /// rather than soft-clipped, so loud overlapping speakers may distort.
+    ///
+    /// This value will be `None` unless Songbird is configured to decode audio and
+    /// [`Config::mix_received_voice`] is set, or if nobody is speaking.
+    ///
+    /// [`Config::mix_received_voice`]: crate::Config::mix_received_voice
+    pub mixed_voice: Option<Vec<i16>>,
+
+    /// Playout buffer state of every SSRC in [`Self::speaking`] and [`Self::silent`],
+    /// as well as any recently disconnected SSRCs whose buffers are still draining.
+    pub playout: HashMap<u32, PlayoutStats>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -28,6 +44,18 @@ pub struct VoiceData {
     /// If `None`, then the packet was lost, and [`Self::decoded_voice`] may include
     /// around one codec delay's worth of audio.
     pub packet: Option<RtpData>,
+    /// The decrypted Opus frame held within [`Self::packet`], with any RTP header
+    /// extensions and encryption framing removed.
+    ///
+    /// This is a zero-copy view into the packet, and allows users to run their own
+    /// Opus decoder (or forward audio elsewhere) with [`DecodeMode::Decrypt`] without
+    /// incurring the cost of decoding within Songbird.
+    ///
+    /// This value will be `None` if the packet was lost, or if Songbird is not configured
+    /// to decrypt audio.
+    ///
+    /// [`DecodeMode::Decrypt`]: crate::driver::DecodeMode::Decrypt
+    pub opus_frame: Option<Bytes>,
     /// PCM audio obtained from a user.
     ///
     /// Valid audio data (`Some(audio)` where `audio.len >= 0`) typically contains 20ms of 16-bit stereo PCM audio
@@ -35,4 +63,10 @@ pub struct VoiceData {
     ///
     /// This value will be `None` if Songbird is not configured to decode audio.
     pub decoded_voice: Option<Vec<i16>>,
+    /// Root-mean-square amplitude of [`Self::decoded_voice`], in the same units as
+    /// its samples: between `0` (silence) and [`i16::MAX`] (full-scale).
+    ///
+    /// This is computed alongside decoding, and will be `None` if Songbird is not
+    /// configured to decode audio.
+    pub rms: Option<u16>,
 }
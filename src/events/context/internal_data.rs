@@ -1,10 +1,11 @@
 use super::context_data::*;
-use crate::ConnectionInfo;
+use crate::{driver::CryptoMode, ConnectionInfo};
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct InternalConnect {
     pub info: ConnectionInfo,
     pub ssrc: u32,
+    pub crypto_mode: CryptoMode,
 }
 
 #[derive(Debug)]
@@ -22,6 +23,7 @@ impl<'a> From<&'a InternalConnect> for ConnectData<'a> {
             session_id: &val.info.session_id,
             server: &val.info.endpoint,
             ssrc: val.ssrc,
+            crypto_mode: val.crypto_mode,
         }
     }
 }
@@ -44,6 +44,19 @@ pub enum EventContext<'a> {
     /// Telemetry/statistics packet, received from another stream.
     RtcpPacket(RtcpData),
 
+    #[cfg(feature = "receive")]
+    /// Change in the state of a user's playout buffer, or packet loss.
+    PlayoutChange(PlayoutData),
+
+    #[cfg(feature = "receive")]
+    /// Change in the driver's encoder bitrate, in response to reported packet loss.
+    BitrateChange(BitrateChangeData),
+
+    /// Fired when a user is first seen in the voice session.
+    ///
+    /// See [`CoreEvent::ClientConnect`] for what triggers this.
+    ClientConnect(ClientConnectData),
+
     /// Fired whenever a client disconnects.
     ClientDisconnect(ClientDisconnect),
 
@@ -61,11 +74,16 @@ pub enum EventContext<'a> {
 pub enum CoreContext {
     SpeakingStateUpdate(Speaking),
     #[cfg(feature = "receive")]
-    VoiceTick(VoiceTick),
+    VoiceTick(Box<VoiceTick>),
     #[cfg(feature = "receive")]
     RtpPacket(InternalRtpPacket),
     #[cfg(feature = "receive")]
     RtcpPacket(InternalRtcpPacket),
+    #[cfg(feature = "receive")]
+    PlayoutChange(PlayoutData),
+    #[cfg(feature = "receive")]
+    BitrateChange(BitrateChangeData),
+    ClientConnect(ClientConnectData),
     ClientDisconnect(ClientDisconnect),
     DriverConnect(InternalConnect),
     DriverReconnect(InternalConnect),
@@ -77,11 +95,16 @@ impl<'a> CoreContext {
         match self {
             Self::SpeakingStateUpdate(evt) => EventContext::SpeakingStateUpdate(*evt),
             #[cfg(feature = "receive")]
-            Self::VoiceTick(evt) => EventContext::VoiceTick(evt.clone()),
+            Self::VoiceTick(evt) => EventContext::VoiceTick((**evt).clone()),
             #[cfg(feature = "receive")]
             Self::RtpPacket(evt) => EventContext::RtpPacket(RtpData::from(evt)),
             #[cfg(feature = "receive")]
             Self::RtcpPacket(evt) => EventContext::RtcpPacket(RtcpData::from(evt)),
+            #[cfg(feature = "receive")]
+            Self::PlayoutChange(evt) => EventContext::PlayoutChange(*evt),
+            #[cfg(feature = "receive")]
+            Self::BitrateChange(evt) => EventContext::BitrateChange(*evt),
+            Self::ClientConnect(evt) => EventContext::ClientConnect(*evt),
             Self::ClientDisconnect(evt) => EventContext::ClientDisconnect(*evt),
             Self::DriverConnect(evt) => EventContext::DriverConnect(ConnectData::from(evt)),
             Self::DriverReconnect(evt) => EventContext::DriverReconnect(ConnectData::from(evt)),
@@ -104,6 +127,11 @@ impl EventContext<'_> {
             Self::RtpPacket(_) => Some(CoreEvent::RtpPacket),
             #[cfg(feature = "receive")]
             Self::RtcpPacket(_) => Some(CoreEvent::RtcpPacket),
+            #[cfg(feature = "receive")]
+            Self::PlayoutChange(_) => Some(CoreEvent::PlayoutChange),
+            #[cfg(feature = "receive")]
+            Self::BitrateChange(_) => Some(CoreEvent::BitrateChange),
+            Self::ClientConnect(_) => Some(CoreEvent::ClientConnect),
             Self::ClientDisconnect(_) => Some(CoreEvent::ClientDisconnect),
             Self::DriverConnect(_) => Some(CoreEvent::DriverConnect),
             Self::DriverReconnect(_) => Some(CoreEvent::DriverReconnect),
@@ -6,7 +6,8 @@
 ///
 /// ## Events from other users
 /// Songbird can observe when a user *speaks for the first time* ([`SpeakingStateUpdate`]),
-/// when a client leaves the session ([`ClientDisconnect`]).
+/// when a client is first seen in the session ([`ClientConnect`]),
+/// and when a client leaves the session ([`ClientDisconnect`]).
 ///
 /// When the `"receive"` feature is enabled, songbird can also handle voice packets
 #[cfg_attr(feature = "receive", doc = "([`RtpPacket`](Self::RtpPacket)),")]
@@ -24,13 +25,14 @@
 )]
 #[cfg_attr(not(feature = "receive"), doc = "`VoiceData`.")]
 ///
-/// To detect when a user connects, you must correlate gateway (e.g., `VoiceStateUpdate`) events
-/// from the main part of your bot.
+/// To reliably detect when a user connects, including users who never transmit audio,
+/// you must correlate gateway (e.g., `VoiceStateUpdate`) events from the main part of your bot.
 ///
 /// To obtain a user's SSRC, you must use [`SpeakingStateUpdate`] events.
 ///
 /// [`EventData`]: super::EventData
 /// [`SpeakingStateUpdate`]: Self::SpeakingStateUpdate
+/// [`ClientConnect`]: Self::ClientConnect
 /// [`ClientDisconnect`]: Self::ClientDisconnect
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 #[non_exhaustive]
@@ -63,6 +65,43 @@ pub enum CoreEvent {
     /// such as latency reports.
     RtcpPacket,
 
+    #[cfg(feature = "receive")]
+    /// Fires when a user's playout buffer begins or stops producing audio, or
+    /// when any of their packets are lost or discarded.
+    ///
+    /// This fires at most once per user every 20ms, alongside [`VoiceTick`].
+    /// A buffer will return to filling both when a user stops speaking, and when it
+    /// underruns due to network delay or loss.
+    ///
+    /// [`VoiceTick`]: Self::VoiceTick
+    PlayoutChange,
+
+    #[cfg(feature = "receive")]
+    /// Fires when the driver changes its Opus encoder bitrate in response to packet
+    /// loss reported by Discord's voice servers.
+    ///
+    /// This only fires if [`Config::adaptive_bitrate`] is set.
+    ///
+    /// [`Config::adaptive_bitrate`]: crate::Config::adaptive_bitrate
+    BitrateChange,
+
+    /// Fires when a user is first seen in the same stream as the bot.
+    ///
+    /// Discord no longer sends a dedicated message when a user joins a voice session.
+    /// Instead, this is derived from the first [`SpeakingStateUpdate`] received for each
+    /// user, which Discord sends once a user begins transmitting audio (and, typically, for
+    /// users already transmitting when the bot connects). It fires immediately before that
+    /// [`SpeakingStateUpdate`], and includes the user's SSRC. Legacy connect messages
+    /// are also used, if a voice server still sends them.
+    ///
+    /// This fires again for a user after they trigger [`ClientDisconnect`], and may fire
+    /// again for users already present if the driver has to reconnect. Users who join but
+    /// never transmit audio are not seen.
+    ///
+    /// [`SpeakingStateUpdate`]: Self::SpeakingStateUpdate
+    /// [`ClientDisconnect`]: Self::ClientDisconnect
+    ClientConnect,
+
     /// Fires whenever a user disconnects from the same stream as the bot.
     ClientDisconnect,
 
@@ -70,6 +109,9 @@ pub enum CoreEvent {
     DriverConnect,
 
     /// Fires when this driver successfully reconnects after a network error.
+    ///
+    /// This also fires when the driver resumes its existing session on new connection
+    /// info, e.g., after the bot is moved to another voice channel.
     DriverReconnect,
 
     /// Fires when this driver fails to connect to, or drops from, a voice channel.
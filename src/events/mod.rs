@@ -21,6 +21,9 @@
 //! The exception is one-off events like [`Event::Delayed`], which remove themselves
 //! after one call *unless* an [`Event`] override is returned.
 //!
+//! Any number of handlers may listen for the same event: each is called in the order it
+//! was registered, and the `Option<Event>` returned by each handler affects only that handler.
+//!
 //! ## Global and local listeners
 //! *Global* event listeners are those which are placed onto the [`Driver`],
 //! while *local* event listeners are those which are placed on a
@@ -131,12 +131,12 @@ impl EventStore {
                 // Only remove/readd if the event type changes (i.e., Some AND new != old)
                 if let Some(new_evt_type) = evt.action.act(&ctx).await {
                     if evt.event == new_evt_type {
+                        i += 1;
+                    } else {
                         let mut evt = events.remove(i);
 
                         evt.event = new_evt_type;
                         self.add_event(evt, now);
-                    } else {
-                        i += 1;
                     }
                 } else {
                     i += 1;
@@ -263,3 +263,54 @@ impl GlobalEvents {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{id::UserId, payload::ClientDisconnect};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    struct Counter {
+        hits: Arc<AtomicUsize>,
+        cancel: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl EventHandler for Counter {
+        async fn act(&self, _ctx: &EventContext<'_>) -> Option<Event> {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            self.cancel.then_some(Event::Cancel)
+        }
+    }
+
+    #[tokio::test]
+    async fn core_event_fans_out_to_all_handlers() {
+        let mut store = EventStore::new();
+        let persistent = Arc::new(AtomicUsize::new(0));
+        let one_shot = Arc::new(AtomicUsize::new(0));
+
+        for (hits, cancel) in [(&persistent, false), (&one_shot, true)] {
+            let handler = Counter {
+                hits: hits.clone(),
+                cancel,
+            };
+            store.add_event(
+                EventData::new(CoreEvent::ClientDisconnect.into(), handler),
+                Duration::ZERO,
+            );
+        }
+
+        for _ in 0..2 {
+            let ctx = EventContext::ClientDisconnect(ClientDisconnect { user_id: UserId(1) });
+            store
+                .process_untimed(Duration::ZERO, CoreEvent::ClientDisconnect.into(), ctx)
+                .await;
+        }
+
+        assert_eq!(persistent.load(Ordering::Relaxed), 2);
+        assert_eq!(one_shot.load(Ordering::Relaxed), 1);
+    }
+}
@@ -26,10 +26,41 @@ pub enum TrackEvent {
     End,
     /// The attached track has looped.
     Loop,
+    /// The attached track has looped for the last time, and will end once this
+    /// play-through completes.
+    ///
+    /// This fires alongside [`Loop`] when a track with [`LoopState::Finite`] loops
+    /// with no loops remaining, i.e., once its loop count reaches `Finite(0)`.
+    ///
+    /// [`Loop`]: Self::Loop
+    /// [`LoopState::Finite`]: crate::tracks::LoopState::Finite
+    FinalLoop,
     /// The attached track is being readied or recreated.
     Preparing,
     /// The attached track has become playable.
+    ///
+    /// This fires once each time a track leaves the [`Preparing`] state, either after
+    /// its input is first created and parsed or after a seek has completed. Tracks which
+    /// fail to ready will instead fire [`Error`].
+    ///
+    /// [`Preparing`]: Self::Preparing
+    /// [`Error`]: Self::Error
     Playable,
     /// The attached track has encountered a runtime or initialisation error.
     Error,
+    /// The attached track's input failed to be created due to a transient error,
+    /// and creation will be retried.
+    ///
+    /// This fires once per retry, as configured by [`Config::input_retry`].
+    ///
+    /// [`Config::input_retry`]: crate::Config::input_retry
+    Retrying,
+    /// The attached track contains Opus frames which cannot be passed directly to
+    /// Discord, and so will now always be decoded and mixed.
+    ///
+    /// This fires at most once per track. The offending frame is described by
+    /// [`TrackState::passthrough_block`].
+    ///
+    /// [`TrackState::passthrough_block`]: crate::tracks::TrackState::passthrough_block
+    PassthroughBlocked,
 }
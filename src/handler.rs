@@ -1,5 +1,9 @@
 #[cfg(feature = "driver")]
-use crate::{driver::Driver, error::ConnectionResult};
+use crate::{
+    driver::{ConnectionStats, Driver},
+    error::ConnectionResult,
+    events::{CoreEvent, Event, EventContext, EventHandler},
+};
 use crate::{
     error::{JoinError, JoinResult},
     id::{ChannelId, GuildId, UserId},
@@ -13,19 +17,35 @@ use std::fmt::Debug;
 use tracing::instrument;
 
 #[cfg(feature = "driver")]
-use std::ops::{Deref, DerefMut};
+use async_trait::async_trait;
+#[cfg(feature = "driver")]
+use std::{
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
 
 #[derive(Clone, Debug)]
 enum Return {
     // Return the connection info as it is received.
-    Info(Sender<ConnectionInfo>),
+    Info(Sender<JoinResult<ConnectionInfo>>),
 
     // Two channels: first indicates "gateway connection" was successful,
     // second indicates that the driver successfully connected.
     // The first is needed to cancel a timeout as the driver can/should
     // have separate connection timing/retry config.
     #[cfg(feature = "driver")]
-    Conn(Sender<()>, Sender<ConnectionResult<()>>),
+    Conn(Sender<JoinResult<()>>, Sender<ConnectionResult<()>>),
+}
+
+impl Return {
+    fn fail_gateway(&self, err: JoinError) {
+        // It's okay if the receiver hung up.
+        match self {
+            Return::Info(tx) => drop(tx.send(Err(err))),
+            #[cfg(feature = "driver")]
+            Return::Conn(tx, _) => drop(tx.send(Err(err))),
+        }
+    }
 }
 
 /// The Call handler is responsible for a single voice connection, acting
@@ -46,7 +66,11 @@ pub struct Call {
     /// The internal controller of the voice connection monitor thread.
     driver: Driver,
 
+    /// Self-deafen and self-mute flags last reported by the gateway.
+    gateway_flags: Option<(bool, bool)>,
     guild_id: GuildId,
+    /// Gateway responses received during the current join attempt.
+    join_progress: JoinProgress,
     /// Whether the current handler is set to deafen voice connections.
     self_deaf: bool,
     /// Whether the current handler is set to mute voice connections.
@@ -123,7 +147,9 @@ impl Call {
             connection: None,
             #[cfg(feature = "driver")]
             driver: Driver::new(config),
+            gateway_flags: None,
             guild_id,
+            join_progress: JoinProgress::default(),
             self_deaf: false,
             self_mute: false,
             user_id,
@@ -136,12 +162,12 @@ impl Call {
         match &self.connection {
             Some((ConnectionProgress::Complete(c), Return::Info(tx))) => {
                 // It's okay if the receiver hung up.
-                drop(tx.send(c.clone()));
+                drop(tx.send(Ok(c.clone())));
             },
             #[cfg(feature = "driver")]
             Some((ConnectionProgress::Complete(c), Return::Conn(first_tx, driver_tx))) => {
                 // It's okay if the receiver hung up.
-                _ = first_tx.send(());
+                _ = first_tx.send(Ok(()));
 
                 self.driver.raw_connect(c.clone(), driver_tx.clone());
             },
@@ -154,6 +180,10 @@ impl Call {
     /// If there is no live voice connection, then this only acts as a settings
     /// update for future connections.
     ///
+    /// This flag is stored by the `Call` and included in every voice state update
+    /// it sends, so it is preserved across rejoins, channel moves, and reconnections.
+    /// Use [`set_deaf`] to change it without sending an update.
+    ///
     /// **Note**: Unlike in the official client, you _can_ be deafened while
     /// not being muted.
     ///
@@ -161,6 +191,7 @@ impl Call {
     /// will _only_ update whether the connection is internally deafened.
     ///
     /// [`standalone`]: Call::standalone
+    /// [`set_deaf`]: Call::set_deaf
     #[instrument(skip(self))]
     pub async fn deafen(&mut self, deaf: bool) -> JoinResult<()> {
         self.self_deaf = deaf;
@@ -168,6 +199,19 @@ impl Call {
         self.update().await
     }
 
+    /// Sets whether future connections are to be deafened, without sending
+    /// a voice state update.
+    ///
+    /// The new value is sent to Discord alongside the next join, channel move,
+    /// or call to [`deafen`] or [`mute`].
+    ///
+    /// [`deafen`]: Call::deafen
+    /// [`mute`]: Call::mute
+    #[instrument(skip(self))]
+    pub fn set_deaf(&mut self, deaf: bool) {
+        self.self_deaf = deaf;
+    }
+
     /// Returns whether the current connection is self-deafened in this server.
     ///
     /// This is purely cosmetic.
@@ -176,6 +220,19 @@ impl Call {
         self.self_deaf
     }
 
+    /// Returns whether the gateway last reported this user as self-deafened in this server.
+    ///
+    /// Unlike [`is_deaf`], which is the state requested by this `Call`, this reflects
+    /// the most recent voice state update received from Discord. Comparing the two
+    /// shows whether a deafen has taken effect, e.g., after a reconnect or a change
+    /// made outside of Songbird. This is `None` until a voice state update is received.
+    ///
+    /// [`is_deaf`]: Call::is_deaf
+    #[instrument(skip(self))]
+    pub fn gateway_deaf(&self) -> Option<bool> {
+        self.gateway_flags.map(|(deaf, _)| deaf)
+    }
+
     async fn should_actually_join<F, G>(
         &mut self,
         completion_generator: F,
@@ -228,7 +285,7 @@ impl Call {
         let (gw_tx, gw_rx) = flume::unbounded();
 
         let do_conn = self
-            .should_actually_join(|_| (), &gw_tx, channel_id)
+            .should_actually_join(|_| Ok(()), &gw_tx, channel_id)
             .await?;
 
         if do_conn {
@@ -236,12 +293,19 @@ impl Call {
                 ConnectionProgress::new(self.guild_id, self.user_id, channel_id),
                 Return::Conn(gw_tx, tx),
             ));
+            self.join_progress = JoinProgress::default();
 
             let timeout = self.config().gateway_timeout;
-
-            self.update()
-                .await
-                .map(|()| Join::new(rx.into_recv_async(), gw_rx.into_recv_async(), timeout))
+            let progress = self.join_progress.clone();
+
+            self.update().await.map(|()| {
+                Join::new(
+                    rx.into_recv_async(),
+                    gw_rx.into_recv_async(),
+                    timeout,
+                    progress,
+                )
+            })
         } else {
             // Skipping the gateway connection implies that the current connection is complete
             // AND the channel is a match.
@@ -255,10 +319,68 @@ impl Call {
                 rx.into_recv_async(),
                 gw_rx.into_recv_async(),
                 None,
+                JoinProgress::default(),
             ))
         }
     }
 
+    #[cfg(feature = "driver")]
+    /// Move an existing voice connection to the given voice channel by its Id.
+    ///
+    /// Unlike [`join`], this never leaves the current channel: the driver, its tracks,
+    /// and its event handlers are all preserved, and the driver attempts to resume its
+    /// existing session once Discord confirms the move.
+    ///
+    /// This function acts as a future in two stages, in the same manner as [`join`].
+    /// To prevent deadlock, any mutexes around this Call *must* be released before
+    /// the second stage is awaited.
+    ///
+    /// Returns [`JoinError::NotConnected`] if this Call is not in, or joining, any channel.
+    ///
+    /// When using [`Songbird::move_to`], this pattern is correctly handled for you.
+    ///
+    /// [`join`]: Call::join
+    /// [`Songbird::move_to`]: crate::Songbird::move_to
+    #[instrument(skip(self))]
+    #[inline]
+    pub async fn move_to<C>(&mut self, channel_id: C) -> JoinResult<Join>
+    where
+        C: Into<ChannelId> + Debug,
+    {
+        self._move_to(channel_id.into()).await
+    }
+
+    #[cfg(feature = "driver")]
+    async fn _move_to(&mut self, channel_id: ChannelId) -> JoinResult<Join> {
+        match &self.connection {
+            None => return Err(JoinError::NotConnected),
+            Some((progress, _)) if progress.channel_id() == channel_id =>
+                return self._join(channel_id).await,
+            Some(_) => {},
+        }
+
+        let (tx, rx) = flume::unbounded();
+        let (gw_tx, gw_rx) = flume::unbounded();
+
+        self.connection = Some((
+            ConnectionProgress::new(self.guild_id, self.user_id, channel_id),
+            Return::Conn(gw_tx, tx),
+        ));
+        self.join_progress = JoinProgress::default();
+
+        let timeout = self.config().gateway_timeout;
+        let progress = self.join_progress.clone();
+
+        self.update().await.map(|()| {
+            Join::new(
+                rx.into_recv_async(),
+                gw_rx.into_recv_async(),
+                timeout,
+                progress,
+            )
+        })
+    }
+
     /// Join the selected voice channel, *without* running/starting an RTP
     /// session or running the driver.
     ///
@@ -288,7 +410,7 @@ impl Call {
 
         let do_conn = self
             .should_actually_join(
-                |call| call.connection.as_ref().unwrap().0.info().unwrap(),
+                |call| Ok(call.connection.as_ref().unwrap().0.info().unwrap()),
                 &tx,
                 channel_id,
             )
@@ -299,14 +421,20 @@ impl Call {
                 ConnectionProgress::new(self.guild_id, self.user_id, channel_id),
                 Return::Info(tx),
             ));
+            self.join_progress = JoinProgress::default();
 
             let timeout = self.config().gateway_timeout;
+            let progress = self.join_progress.clone();
 
             self.update()
                 .await
-                .map(|()| JoinGateway::new(rx.into_recv_async(), timeout))
+                .map(|()| JoinGateway::new(rx.into_recv_async(), timeout, progress))
         } else {
-            Ok(JoinGateway::new(rx.into_recv_async(), None))
+            Ok(JoinGateway::new(
+                rx.into_recv_async(),
+                None,
+                JoinProgress::default(),
+            ))
         }
     }
 
@@ -333,6 +461,16 @@ impl Call {
         }
     }
 
+    /// Returns heartbeat latency, UDP keepalive, and RTP counter information about
+    /// the driver's current voice connection.
+    ///
+    /// This does not block, and returns `None` if the driver has no active connection.
+    #[cfg(feature = "driver")]
+    #[must_use]
+    pub fn connection_info(&self) -> Option<ConnectionStats> {
+        self.driver.connection_info()
+    }
+
     /// Leaves the current voice channel, disconnecting from it.
     ///
     /// This does _not_ forget settings, like whether to be self-deafened or
@@ -351,6 +489,19 @@ impl Call {
         self.update().await
     }
 
+    /// Fades out and stops all audio over `fade`, and then leaves the current
+    /// voice channel as in [`leave`].
+    ///
+    /// See [`Driver::stop_with_fade`] for details.
+    ///
+    /// [`leave`]: Call::leave
+    #[cfg(feature = "driver")]
+    #[instrument(skip(self))]
+    pub async fn leave_with_fade(&mut self, fade: Duration) -> JoinResult<()> {
+        self.driver.stop_with_fade(fade).await;
+        self.leave().await
+    }
+
     fn leave_local(&mut self) {
         self.connection = None;
 
@@ -358,14 +509,38 @@ impl Call {
         self.driver.leave();
     }
 
+    /// Registers a global event handler which fires only when Discord forcibly
+    /// ends this call, e.g., when the bot is kicked or its channel is deleted.
+    ///
+    /// The handler receives the [`EventContext::DriverDisconnect`] which caused it to fire.
+    /// Transient network failures, user-requested disconnects, and failed (re)connection
+    /// attempts are filtered out: see [`DisconnectData::is_forced`] for details. Bots will
+    /// typically [`leave`] in response, rather than attempting to reconnect.
+    ///
+    /// [`DisconnectData::is_forced`]: crate::events::context_data::DisconnectData::is_forced
+    /// [`leave`]: Call::leave
+    #[cfg(feature = "driver")]
+    #[instrument(skip(self, action))]
+    pub fn add_forced_leave_event<F: EventHandler + 'static>(&mut self, action: F) {
+        self.driver.add_global_event(
+            Event::Core(CoreEvent::DriverDisconnect),
+            ForcedLeave(action),
+        );
+    }
+
     /// Sets whether the current connection is to be muted.
     ///
     /// If there is no live voice connection, then this only acts as a settings
     /// update for future connections.
     ///
+    /// As with [`deafen`], this flag is preserved across rejoins, channel moves,
+    /// and reconnections. Use [`set_mute`] to change it without sending an update.
+    ///
     /// **Note**: If the `Call` was created via [`standalone`], then this
     /// will _only_ update whether the connection is internally muted.
     ///
+    /// [`deafen`]: Call::deafen
+    /// [`set_mute`]: Call::set_mute
     /// [`standalone`]: Call::standalone
     #[instrument(skip(self))]
     pub async fn mute(&mut self, mute: bool) -> JoinResult<()> {
@@ -377,12 +552,40 @@ impl Call {
         self.update().await
     }
 
+    /// Sets whether future connections are to be muted, without sending
+    /// a voice state update.
+    ///
+    /// If the `"driver"` feature is enabled, the driver stops sending audio immediately.
+    /// The new value is sent to Discord alongside the next join, channel move,
+    /// or call to [`deafen`] or [`mute`].
+    ///
+    /// [`deafen`]: Call::deafen
+    /// [`mute`]: Call::mute
+    #[instrument(skip(self))]
+    pub fn set_mute(&mut self, mute: bool) {
+        self.self_mute = mute;
+
+        #[cfg(feature = "driver")]
+        self.driver.mute(mute);
+    }
+
     /// Returns whether the current connection is self-muted in this server.
     #[instrument(skip(self))]
     pub fn is_mute(&self) -> bool {
         self.self_mute
     }
 
+    /// Returns whether the gateway last reported this user as self-muted in this server.
+    ///
+    /// See [`gateway_deaf`] for how this differs from [`is_mute`].
+    ///
+    /// [`gateway_deaf`]: Call::gateway_deaf
+    /// [`is_mute`]: Call::is_mute
+    #[instrument(skip(self))]
+    pub fn gateway_mute(&self) -> Option<bool> {
+        self.gateway_flags.map(|(_, mute)| mute)
+    }
+
     /// Updates the voice server data.
     ///
     /// You should only need to use this if you initialized the `Call` via
@@ -391,6 +594,8 @@ impl Call {
     /// [`standalone`]: Call::standalone
     #[instrument(skip(self, token))]
     pub fn update_server(&mut self, endpoint: String, token: String) {
+        self.join_progress.record_server();
+
         let try_conn = if let Some((ref mut progress, _)) = self.connection.as_mut() {
             progress.apply_server_update(endpoint, token)
         } else {
@@ -417,7 +622,28 @@ impl Call {
         self._update_state(session_id, channel_id.map(Into::into));
     }
 
+    /// Records the self-deafen and self-mute flags of the current user, as reported
+    /// by a voice state update.
+    ///
+    /// You should only need to use this if you initialized the `Call` via
+    /// [`standalone`].
+    ///
+    /// [`standalone`]: Call::standalone
+    #[instrument(skip(self))]
+    pub fn update_voice_flags(&mut self, self_deaf: bool, self_mute: bool) {
+        self.gateway_flags = Some((self_deaf, self_mute));
+    }
+
     fn _update_state(&mut self, session_id: String, channel_id: Option<ChannelId>) {
+        self.join_progress.record_state();
+
+        // Any pending join fails if Discord places us anywhere but its target channel.
+        if let Some((progress, ret)) = &self.connection {
+            if progress.in_progress() && Some(progress.channel_id()) != channel_id {
+                ret.fail_gateway(JoinError::MovedOut { channel_id });
+            }
+        }
+
         if let Some(channel_id) = channel_id {
             let try_conn = if let Some((ref mut progress, _)) = self.connection.as_mut() {
                 progress.apply_state_update(session_id, channel_id)
@@ -434,6 +660,12 @@ impl Call {
         }
     }
 
+    /// Returns the error to report if a join attempt has not completed after `elapsed`.
+    #[cfg(feature = "driver")]
+    pub(crate) fn join_timeout_error(&self, elapsed: Duration) -> JoinError {
+        self.join_progress.timeout_error(elapsed)
+    }
+
     /// Send an update for the current session over WS.
     ///
     /// Does nothing if initialized via [`standalone`].
@@ -473,6 +705,20 @@ impl Call {
     }
 }
 
+#[cfg(feature = "driver")]
+struct ForcedLeave<F>(F);
+
+#[cfg(feature = "driver")]
+#[async_trait]
+impl<F: EventHandler> EventHandler for ForcedLeave<F> {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        match ctx {
+            EventContext::DriverDisconnect(data) if data.is_forced() => self.0.act(ctx).await,
+            _ => None,
+        }
+    }
+}
+
 #[cfg(feature = "driver")]
 impl Deref for Call {
     type Target = Driver;
@@ -488,3 +734,183 @@ impl DerefMut for Call {
         &mut self.driver
     }
 }
+
+#[cfg(all(test, feature = "driver"))]
+mod tests {
+    use super::*;
+    use crate::{
+        driver::{
+            retry::{Retry, Strategy},
+            Scheduler,
+            SchedulerConfig,
+        },
+        events::context_data::DisconnectKind,
+        shards::test_fixtures::RecordUpdates,
+    };
+    use async_trait::async_trait;
+    use std::{num::NonZeroU64, sync::Arc};
+
+    fn channel(n: u64) -> ChannelId {
+        ChannelId(NonZeroU64::new(n).unwrap())
+    }
+
+    fn test_call() -> (Call, Arc<RecordUpdates>) {
+        test_call_with_config(Config::default())
+    }
+
+    fn test_call_with_config(config: Config) -> (Call, Arc<RecordUpdates>) {
+        let updates = Arc::new(RecordUpdates::default());
+        let id = |n| NonZeroU64::new(n).unwrap();
+        let call = Call::from_config(
+            GuildId(id(1)),
+            Shard::Generic(updates.clone()),
+            UserId(id(2)),
+            config,
+        );
+
+        (call, updates)
+    }
+
+    #[tokio::test]
+    async fn move_to_switches_channel_without_leaving() {
+        let (mut call, updates) = test_call();
+        let (first, second) = (channel(3), channel(4));
+
+        assert!(matches!(
+            call.move_to(second).await,
+            Err(JoinError::NotConnected)
+        ));
+
+        let _join = call.join(first).await.unwrap();
+        let _move = call.move_to(second).await.unwrap();
+
+        assert_eq!(call.current_channel(), Some(second));
+        assert_eq!(
+            *updates.0.lock(),
+            vec![(Some(first), false, false), (Some(second), false, false)]
+        );
+    }
+
+    #[tokio::test]
+    async fn connection_info_is_none_without_connection() {
+        let (mut call, _updates) = test_call();
+
+        assert!(call.connection_info().is_none());
+
+        let _join = call.join(channel(3)).await.unwrap();
+
+        assert!(call.connection_info().is_none());
+    }
+
+    #[tokio::test]
+    async fn voice_state_flags_survive_rejoin() {
+        let (mut call, updates) = test_call();
+        let channel = channel(3);
+
+        call.set_deaf(true);
+        assert!(call.is_deaf());
+        assert!(updates.0.lock().is_empty());
+
+        let _join = call.join(channel).await.unwrap();
+        call.mute(true).await.unwrap();
+        call.leave().await.unwrap();
+        let _join = call.join(channel).await.unwrap();
+
+        assert_eq!(
+            *updates.0.lock(),
+            vec![
+                (Some(channel), true, false),
+                (Some(channel), true, true),
+                (None, true, true),
+                (Some(channel), true, true),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn gateway_flags_track_voice_state_updates() {
+        let (mut call, _updates) = test_call();
+        assert_eq!(call.gateway_deaf(), None);
+        assert_eq!(call.gateway_mute(), None);
+
+        let _join = call.join(channel(3)).await.unwrap();
+        call.deafen(true).await.unwrap();
+
+        // Discord has not yet confirmed the deafen.
+        call.update_voice_flags(false, false);
+        assert!(call.is_deaf());
+        assert_eq!(call.gateway_deaf(), Some(false));
+
+        call.update_voice_flags(true, false);
+        assert_eq!(call.gateway_deaf(), Some(true));
+        assert_eq!(call.gateway_mute(), Some(false));
+    }
+
+    struct NotifyDisconnect(flume::Sender<DisconnectKind>);
+
+    #[async_trait]
+    impl EventHandler for NotifyDisconnect {
+        async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+            if let EventContext::DriverDisconnect(data) = ctx {
+                _ = self.0.send(data.kind);
+            }
+            None
+        }
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn global_events_added_before_join_fire_on_connection() {
+        let config = Config::default()
+            .driver_retry(Retry {
+                strategy: Strategy::Every(Duration::ZERO),
+                retry_limit: Some(0),
+            })
+            .scheduler(Scheduler::new(SchedulerConfig::default()));
+        let (mut call, _updates) = test_call_with_config(config);
+        let channel = channel(3);
+
+        let (tx, rx) = flume::unbounded();
+        call.add_global_event(CoreEvent::DriverDisconnect.into(), NotifyDisconnect(tx));
+
+        // Nothing listens here, so the driver's connection attempt fails immediately.
+        let join = call.join(channel).await.unwrap();
+        call.update_state("session".into(), Some(channel));
+        call.update_server("127.0.0.1:1".into(), "token".into());
+        assert!(matches!(join.await, Err(JoinError::Driver(_))));
+
+        assert_eq!(rx.recv_async().await.unwrap(), DisconnectKind::Connect);
+    }
+
+    #[tokio::test]
+    async fn join_errors_explain_gateway_failures() {
+        let config = Config::default().gateway_timeout(Some(Duration::from_millis(50)));
+        let (mut call, _updates) = test_call_with_config(config);
+        let (first, second) = (channel(3), channel(4));
+
+        let join = call.join(first).await.unwrap();
+        call.update_state("session".into(), Some(second));
+        assert!(matches!(
+            join.await,
+            Err(JoinError::MovedOut { channel_id: Some(c) }) if c == second
+        ));
+
+        let join = call.join(first).await.unwrap();
+        call.update_state::<ChannelId>("session".into(), None);
+        assert!(matches!(
+            join.await,
+            Err(JoinError::MovedOut { channel_id: None })
+        ));
+
+        let join = call.join(first).await.unwrap();
+        assert!(matches!(join.await, Err(JoinError::TimedOut { .. })));
+
+        let join = call.join(first).await.unwrap();
+        call.update_server("endpoint".into(), "token".into());
+        let err = join.await.unwrap_err();
+        assert!(
+            matches!(err, JoinError::MissingVoiceState { elapsed } if elapsed == Duration::from_millis(50))
+        );
+        assert!(err.should_leave_server());
+    }
+}
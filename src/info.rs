@@ -1,4 +1,7 @@
-use crate::id::{ChannelId, GuildId, UserId};
+use crate::{
+    error::ConnectionInfoError,
+    id::{ChannelId, GuildId, UserId},
+};
 use std::fmt;
 
 #[derive(Clone, Debug)]
@@ -122,6 +125,48 @@ pub struct ConnectionInfo {
     pub user_id: UserId,
 }
 
+impl ConnectionInfo {
+    /// Creates a builder for connection info gathered by an external gateway,
+    /// such as when running the driver without serenity or twilight.
+    ///
+    /// [`ConnectionInfoBuilder::build`] checks that all necessary fields are
+    /// present, and normalises the voice server endpoint.
+    pub fn builder<G, U>(guild_id: G, user_id: U) -> ConnectionInfoBuilder
+    where
+        G: Into<GuildId>,
+        U: Into<UserId>,
+    {
+        ConnectionInfoBuilder {
+            channel_id: None,
+            endpoint: None,
+            guild_id: guild_id.into(),
+            session_id: None,
+            token: None,
+            user_id: user_id.into(),
+        }
+    }
+}
+
+#[cfg(feature = "driver")]
+impl ConnectionInfo {
+    /// Returns whether a live connection made with `self` may move onto `other` by
+    /// resuming its voice gateway session, rather than rebuilding from scratch.
+    ///
+    /// This requires the same voice session on the same voice server: resuming keeps
+    /// the existing UDP socket and cipher, which belong to the old server.
+    pub(crate) fn can_resume_onto(&self, other: &Self) -> bool {
+        let mut endpoint = self.endpoint.clone();
+        let mut other_endpoint = other.endpoint.clone();
+        strip_default_port(&mut endpoint);
+        strip_default_port(&mut other_endpoint);
+
+        self.guild_id == other.guild_id
+            && self.user_id == other.user_id
+            && self.session_id == other.session_id
+            && endpoint == other_endpoint
+    }
+}
+
 impl fmt::Debug for ConnectionInfo {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("ConnectionInfo")
@@ -135,6 +180,200 @@ impl fmt::Debug for ConnectionInfo {
     }
 }
 
+/// Validating builder for a [`ConnectionInfo`].
+///
+/// See [`ConnectionInfo::builder`].
+#[derive(Clone)]
+#[must_use]
+pub struct ConnectionInfoBuilder {
+    channel_id: Option<ChannelId>,
+    endpoint: Option<String>,
+    guild_id: GuildId,
+    session_id: Option<String>,
+    token: Option<String>,
+    user_id: UserId,
+}
+
+impl ConnectionInfoBuilder {
+    /// Sets the ID of the voice channel being joined.
+    pub fn channel_id<C: Into<ChannelId>>(mut self, channel_id: C) -> Self {
+        self.channel_id = Some(channel_id.into());
+        self
+    }
+
+    /// Sets the voice server endpoint, as sent in a `VOICE_SERVER_UPDATE`.
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Sets the voice session ID, as sent in a `VOICE_STATE_UPDATE`.
+    pub fn session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// Sets the voice session token, as sent in a `VOICE_SERVER_UPDATE`.
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Validates all fields and builds a [`ConnectionInfo`].
+    ///
+    /// Any `wss://` scheme, trailing slash, or default port (`:80`) is removed from
+    /// the endpoint, leaving the `host[:port]` pair which Discord sends.
+    ///
+    /// # Errors
+    /// Fails if the endpoint, session ID, or token are missing or empty, or if the
+    /// endpoint is not a valid `host[:port]` pair.
+    pub fn build(self) -> Result<ConnectionInfo, ConnectionInfoError> {
+        let endpoint = self
+            .endpoint
+            .filter(|e| !e.trim().is_empty())
+            .ok_or(ConnectionInfoError::MissingEndpoint)?;
+        let session_id = self
+            .session_id
+            .filter(|s| !s.is_empty())
+            .ok_or(ConnectionInfoError::MissingSessionId)?;
+        let token = self
+            .token
+            .filter(|t| !t.is_empty())
+            .ok_or(ConnectionInfoError::MissingToken)?;
+
+        Ok(ConnectionInfo {
+            channel_id: self.channel_id,
+            endpoint: normalise_endpoint(&endpoint)?,
+            guild_id: self.guild_id,
+            session_id,
+            token,
+            user_id: self.user_id,
+        })
+    }
+}
+
+impl fmt::Debug for ConnectionInfoBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectionInfoBuilder")
+            .field("channel_id", &self.channel_id)
+            .field("endpoint", &self.endpoint)
+            .field("guild_id", &self.guild_id)
+            .field("session_id", &self.session_id)
+            .field("token_is_some", &self.token.is_some())
+            .field("user_id", &self.user_id)
+            .finish()
+    }
+}
+
+/// Removes the default port from a voice server endpoint, which Discord's
+/// voice servers do not accept.
+pub(crate) fn strip_default_port(endpoint: &mut String) {
+    if endpoint.ends_with(":80") {
+        endpoint.truncate(endpoint.len() - 3);
+    }
+}
+
+fn normalise_endpoint(endpoint: &str) -> Result<String, ConnectionInfoError> {
+    let invalid = || ConnectionInfoError::InvalidEndpoint(endpoint.to_string());
+
+    let trimmed = endpoint.trim();
+    let trimmed = trimmed.strip_prefix("wss://").unwrap_or(trimmed);
+    let mut out = trimmed.strip_suffix('/').unwrap_or(trimmed).to_string();
+    strip_default_port(&mut out);
+
+    let (host, port) = match out.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (out.as_str(), None),
+    };
+
+    let host_valid = !host.is_empty()
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    let port_valid = port.map_or(true, |p| p.parse::<u16>().is_ok_and(|p| p != 0));
+
+    if host_valid && port_valid {
+        Ok(out)
+    } else {
+        Err(invalid())
+    }
+}
+
+/// A voice server or voice state update received from Discord's gateway, as seen by
+/// the [`Songbird`] manager before it is applied to a [`Call`].
+///
+/// See [`Songbird::set_gateway_update_hook`].
+///
+/// [`Songbird`]: crate::Songbird
+/// [`Call`]: crate::Call
+/// [`Songbird::set_gateway_update_hook`]: crate::Songbird::set_gateway_update_hook
+#[derive(Clone)]
+#[non_exhaustive]
+pub enum GatewayUpdate {
+    /// A `VOICE_SERVER_UPDATE`, assigning a voice server to a guild.
+    Server {
+        /// ID of the guild which this update applies to.
+        guild_id: GuildId,
+        /// URL of the assigned voice websocket gateway server.
+        ///
+        /// Discord sends `None` when the previous server has gone away and a new
+        /// one has not yet been allocated. Such updates are ignored by songbird.
+        endpoint: Option<String>,
+        /// Ephemeral secret used to validate the session.
+        token: String,
+    },
+    /// A `VOICE_STATE_UPDATE` concerning this bot's user.
+    State {
+        /// ID of the guild which this update applies to, if any.
+        guild_id: Option<GuildId>,
+        /// ID of the voice channel which the bot is now in.
+        ///
+        /// `None` if the bot has left, or been removed from, its voice channel.
+        channel_id: Option<ChannelId>,
+        /// Unique string describing this session for validation/authentication purposes.
+        session_id: String,
+    },
+}
+
+impl GatewayUpdate {
+    /// Returns the ID of the guild which this update applies to, if known.
+    #[must_use]
+    pub fn guild_id(&self) -> Option<GuildId> {
+        match self {
+            Self::Server { guild_id, .. } => Some(*guild_id),
+            Self::State { guild_id, .. } => *guild_id,
+        }
+    }
+}
+
+impl fmt::Debug for GatewayUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Server {
+                guild_id, endpoint, ..
+            } => f
+                .debug_struct("Server")
+                .field("guild_id", guild_id)
+                .field("endpoint", endpoint)
+                .field("token", &"<secret>")
+                .finish(),
+            Self::State {
+                guild_id,
+                channel_id,
+                session_id,
+            } => f
+                .debug_struct("State")
+                .field("guild_id", guild_id)
+                .field("channel_id", channel_id)
+                .field("session_id", session_id)
+                .finish(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct Partial {
     pub channel_id: ChannelId,
@@ -201,3 +440,73 @@ impl Partial {
         self.finalise()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::num::NonZeroU64;
+
+    fn builder() -> ConnectionInfoBuilder {
+        ConnectionInfo::builder(NonZeroU64::new(1).unwrap(), NonZeroU64::new(2).unwrap())
+            .session_id("session")
+            .token("token")
+    }
+
+    #[test]
+    fn builder_normalises_endpoints() {
+        for (endpoint, expected) in [
+            ("c-lhr01.discord.media:443", "c-lhr01.discord.media:443"),
+            ("c-lhr01.discord.media:80", "c-lhr01.discord.media"),
+            ("wss://c-lhr01.discord.media/", "c-lhr01.discord.media"),
+            (" wss://c-lhr01.discord.media:80/ ", "c-lhr01.discord.media"),
+        ] {
+            let info = builder().endpoint(endpoint).build().unwrap();
+            assert_eq!(info.endpoint, expected);
+        }
+    }
+
+    #[test]
+    fn builder_rejects_malformed_info() {
+        for endpoint in [
+            "https://c-lhr01.discord.media",
+            "c-lhr01.discord.media:port",
+            "c-lhr01..discord.media",
+            "c-lhr01.discord.media/?v=4",
+            ":443",
+        ] {
+            assert_eq!(
+                builder().endpoint(endpoint).build(),
+                Err(ConnectionInfoError::InvalidEndpoint(endpoint.into()))
+            );
+        }
+
+        assert_eq!(builder().build(), Err(ConnectionInfoError::MissingEndpoint));
+        assert_eq!(
+            builder().endpoint("a.discord.media").token("").build(),
+            Err(ConnectionInfoError::MissingToken)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "driver")]
+    fn handover_requires_same_voice_server() {
+        let info = builder().endpoint("c-lhr01.discord.media").build().unwrap();
+
+        let mut moved = info.clone();
+        moved.channel_id = NonZeroU64::new(3).map(Into::into);
+        moved.token = "new-token".into();
+        assert!(info.can_resume_onto(&moved));
+
+        let mut same_server = moved.clone();
+        same_server.endpoint = "c-lhr01.discord.media:80".into();
+        assert!(info.can_resume_onto(&same_server));
+
+        let mut new_server = moved.clone();
+        new_server.endpoint = "c-ams02.discord.media".into();
+        assert!(!info.can_resume_onto(&new_server));
+
+        let mut new_session = moved;
+        new_session.session_id = "other-session".into();
+        assert!(!info.can_resume_onto(&new_session));
+    }
+}
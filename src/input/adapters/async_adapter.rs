@@ -17,19 +17,25 @@ use std::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
+    time::Duration,
 };
 use symphonia_core::io::MediaSource;
 use tokio::{
     io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt},
     sync::Notify,
+    time::Instant,
 };
 
+/// Time between checks for new data when waiting for a growing stream.
+const GROWTH_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
 struct AsyncAdapterSink {
     bytes_in: HeapProducer<u8>,
     req_rx: Receiver<AdapterRequest>,
     resp_tx: Sender<AdapterResponse>,
     stream: Box<dyn AsyncMediaSource>,
     notify_rx: Arc<Notify>,
+    growth_timeout: Option<Duration>,
 }
 
 impl AsyncAdapterSink {
@@ -51,6 +57,12 @@ impl AsyncAdapterSink {
             if !pause_buf_moves {
                 if !hit_end && read_region.is_empty() {
                     if let Ok(n) = self.stream.read(&mut inner_buf).await {
+                        let n = match self.growth_timeout {
+                            Some(timeout) if n == 0 =>
+                                self.wait_for_growth(&mut inner_buf, seen_bytes, timeout)
+                                    .await,
+                            _ => n,
+                        };
                         read_region = 0..n;
                         if n == 0 {
                             drop(self.resp_tx.send_async(AdapterResponse::ReadZero).await);
@@ -127,6 +139,36 @@ impl AsyncAdapterSink {
             }
         }
     }
+
+    /// Waits for more data to be appended to a stream which has reached its end,
+    /// returning the number of bytes then read into `buf`.
+    ///
+    /// Returns `0` if no data arrives within `timeout`, or if the sync half is dropped.
+    async fn wait_for_growth(
+        &mut self,
+        buf: &mut [u8],
+        seen_bytes: u64,
+        timeout: Duration,
+    ) -> usize {
+        let deadline = Instant::now() + timeout;
+
+        while Instant::now() < deadline && !self.req_rx.is_disconnected() {
+            tokio::time::sleep(GROWTH_POLL_INTERVAL).await;
+
+            // Some streams (e.g., HTTP responses) close at their end, and must be
+            // re-requested to see any new data. Others can simply be read again.
+            if let Ok(stream) = self.stream.try_resume(seen_bytes).await {
+                self.stream = stream;
+            }
+
+            match self.stream.read(buf).await {
+                Ok(0) | Err(_) => {},
+                Ok(n) => return n,
+            }
+        }
+
+        0
+    }
 }
 
 /// An adapter for converting an async media source into a synchronous one
@@ -152,6 +194,30 @@ impl AsyncAdapterStream {
     /// between the async and sync halves.
     #[must_use]
     pub fn new(stream: Box<dyn AsyncMediaSource>, buf_len: usize) -> AsyncAdapterStream {
+        Self::new_inner(stream, buf_len, None)
+    }
+
+    /// Wrap and pull from an async stream which may still be growing (e.g., a file which
+    /// is still being written), as in [`Self::new`].
+    ///
+    /// When the end of `stream` is reached, this waits up to `growth_timeout` for more
+    /// data to arrive before reporting the end of the stream. Streams which end when
+    /// their end is reached are recreated via [`AsyncMediaSource::try_resume`].
+    /// Reads from this adapter block while waiting.
+    #[must_use]
+    pub fn new_growing(
+        stream: Box<dyn AsyncMediaSource>,
+        buf_len: usize,
+        growth_timeout: Duration,
+    ) -> AsyncAdapterStream {
+        Self::new_inner(stream, buf_len, Some(growth_timeout))
+    }
+
+    fn new_inner(
+        stream: Box<dyn AsyncMediaSource>,
+        buf_len: usize,
+        growth_timeout: Option<Duration>,
+    ) -> AsyncAdapterStream {
         let (bytes_in, bytes_out) = SharedRb::new(buf_len).split();
         let (resp_tx, resp_rx) = flume::unbounded();
         let (req_tx, req_rx) = flume::unbounded();
@@ -165,6 +231,7 @@ impl AsyncAdapterStream {
             resp_tx,
             stream,
             notify_rx,
+            growth_timeout,
         };
         let stream = AsyncAdapterStream {
             bytes_out,
@@ -0,0 +1,256 @@
+use super::RawAdapter;
+use crate::input::Input;
+use flume::{Receiver, TryRecvError};
+use futures::{Stream, StreamExt};
+use std::io::{ErrorKind as IoErrorKind, Read, Result as IoResult, Seek, SeekFrom};
+use symphonia_core::{conv::IntoSample, io::MediaSource};
+
+/// Default number of chunks buffered between an [`AsyncPcmStream`]'s source and the mixer.
+const DEFAULT_CHUNK_BUFFER: usize = 64;
+
+/// An adapter which plays interleaved PCM audio produced by an async [`Stream`].
+///
+/// Each item yielded by the stream is a chunk of interleaved samples at the sample
+/// rate and channel count given on creation, which the mixer resamples as needed.
+/// Chunks may be any length, but should contain whole frames (i.e., a multiple of the
+/// channel count). Samples may be `f32` or any other type convertible by symphonia,
+/// such as `i16`.
+///
+/// This suits sources which produce audio in bursts, such as text-to-speech services.
+/// If the stream has not produced audio in time for the mixer, silence is played
+/// rather than ending the track. Once the stream ends, the track ends once all buffered
+/// audio is played, unless [`end_on_close`] is disabled.
+///
+/// The stream is polled on a background task, so this must be created within the
+/// context of a Tokio runtime.
+///
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use futures::stream;
+/// use songbird::input::{AsyncPcmStream, Input};
+///
+/// let chunks = stream::iter(vec![vec![0i16; 1_920], vec![0i16; 1_920]]);
+/// let input: Input = AsyncPcmStream::new(chunks, 24_000, 2).into();
+/// # }
+/// ```
+///
+/// [`end_on_close`]: AsyncPcmStream::end_on_close
+pub struct AsyncPcmStream {
+    rx: Receiver<Vec<u8>>,
+    chunk: Vec<u8>,
+    chunk_pos: usize,
+    bytes_read: u64,
+    frame_bytes: usize,
+    silence_bytes: usize,
+    sample_rate: u32,
+    channel_count: u32,
+    end_on_close: bool,
+}
+
+impl AsyncPcmStream {
+    /// Creates an adapter around a stream of interleaved PCM chunks, with the given
+    /// sample rate and channel count.
+    ///
+    /// # Panics
+    /// Panics if called outside of a Tokio runtime, or if `channel_count` is `0`.
+    #[must_use]
+    pub fn new<St, S>(stream: St, sample_rate: u32, channel_count: u32) -> Self
+    where
+        St: Stream<Item = Vec<S>> + Send + Unpin + 'static,
+        S: IntoSample<f32> + Send + 'static,
+    {
+        Self::with_buffer(stream, sample_rate, channel_count, DEFAULT_CHUNK_BUFFER)
+    }
+
+    /// Creates an adapter as in [`Self::new`], which buffers at most `chunk_buffer`
+    /// chunks from the stream before waiting for the mixer to consume them.
+    ///
+    /// # Panics
+    /// Panics if called outside of a Tokio runtime, or if `channel_count` is `0`.
+    #[must_use]
+    pub fn with_buffer<St, S>(
+        mut stream: St,
+        sample_rate: u32,
+        channel_count: u32,
+        chunk_buffer: usize,
+    ) -> Self
+    where
+        St: Stream<Item = Vec<S>> + Send + Unpin + 'static,
+        S: IntoSample<f32> + Send + 'static,
+    {
+        assert!(
+            channel_count > 0,
+            "A PCM stream must have at least one channel."
+        );
+
+        let (tx, rx) = flume::bounded(chunk_buffer.max(1));
+
+        tokio::spawn(async move {
+            while let Some(chunk) = stream.next().await {
+                let bytes = chunk
+                    .into_iter()
+                    .flat_map(|s| IntoSample::<f32>::into_sample(s).to_le_bytes())
+                    .collect();
+
+                if tx.send_async(bytes).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let frame_bytes = std::mem::size_of::<f32>() * channel_count as usize;
+
+        Self {
+            rx,
+            chunk: Vec::new(),
+            chunk_pos: 0,
+            bytes_read: 0,
+            frame_bytes,
+            // Cover underruns in 20ms blocks, to match how the mixer reads audio.
+            silence_bytes: frame_bytes * (sample_rate as usize / 50).max(1),
+            sample_rate,
+            channel_count,
+            end_on_close: true,
+        }
+    }
+
+    /// Sets whether the track ends once the stream has ended and its audio has
+    /// been played.
+    ///
+    /// If `false`, the track plays silence after the stream ends until it is
+    /// stopped. Defaults to `true`.
+    #[must_use]
+    pub fn end_on_close(mut self, end_on_close: bool) -> Self {
+        self.end_on_close = end_on_close;
+        self
+    }
+
+    /// Writes silence into `buf`, keeping reads aligned to whole frames.
+    fn read_silence(&mut self, buf: &mut [u8]) -> usize {
+        let partial = (self.bytes_read % self.frame_bytes as u64) as usize;
+        let target = buf.len().min(self.silence_bytes);
+
+        let mut len = target - ((partial + target) % self.frame_bytes);
+        if len == 0 {
+            len = buf.len().min(self.frame_bytes - partial);
+        }
+
+        buf[..len].fill(0);
+        len
+    }
+}
+
+impl Read for AsyncPcmStream {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        while self.chunk_pos >= self.chunk.len() {
+            match self.rx.try_recv() {
+                Ok(chunk) => {
+                    self.chunk = chunk;
+                    self.chunk_pos = 0;
+                },
+                Err(TryRecvError::Disconnected) if self.end_on_close => return Ok(0),
+                Err(_) => {
+                    let n = self.read_silence(buf);
+                    self.bytes_read += n as u64;
+                    return Ok(n);
+                },
+            }
+        }
+
+        let n = buf.len().min(self.chunk.len() - self.chunk_pos);
+        buf[..n].copy_from_slice(&self.chunk[self.chunk_pos..][..n]);
+        self.chunk_pos += n;
+        self.bytes_read += n as u64;
+
+        Ok(n)
+    }
+}
+
+impl Seek for AsyncPcmStream {
+    fn seek(&mut self, _pos: SeekFrom) -> IoResult<u64> {
+        Err(IoErrorKind::Unsupported.into())
+    }
+}
+
+impl MediaSource for AsyncPcmStream {
+    fn is_seekable(&self) -> bool {
+        false
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        None
+    }
+}
+
+impl From<AsyncPcmStream> for Input {
+    fn from(val: AsyncPcmStream) -> Self {
+        let (sample_rate, channel_count) = (val.sample_rate, val.channel_count);
+        RawAdapter::new(val, sample_rate, channel_count).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream;
+    use std::time::Duration;
+
+    async fn wait_for_chunks(pcm: &AsyncPcmStream, n: usize) {
+        while pcm.rx.len() < n && !pcm.rx.is_disconnected() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn pcm_chunks_are_passed_through_then_end() {
+        let mut pcm = AsyncPcmStream::new(stream::iter(vec![vec![0.5f32, -0.5]]), 48_000, 2);
+        wait_for_chunks(&pcm, 1).await;
+
+        let mut buf = [0u8; 16];
+        assert_eq!(pcm.read(&mut buf).unwrap(), 8);
+        assert_eq!(&buf[..4], &0.5f32.to_le_bytes());
+        assert_eq!(&buf[4..8], &(-0.5f32).to_le_bytes());
+
+        wait_for_chunks(&pcm, usize::MAX).await;
+        assert_eq!(pcm.read(&mut buf).unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn underrun_plays_aligned_silence() {
+        let (tx, rx) = flume::unbounded::<Vec<i16>>();
+        let mut pcm = AsyncPcmStream::new(rx.into_stream(), 50, 2);
+
+        // A chunk ending partway through a frame.
+        tx.send(vec![i16::MIN, 0, 0]).unwrap();
+        wait_for_chunks(&pcm, 1).await;
+
+        let mut buf = [1u8; 13];
+        assert_eq!(pcm.read(&mut buf).unwrap(), 12);
+        assert_eq!(&buf[..4], &(-1.0f32).to_le_bytes());
+
+        // Silence first completes the partial frame, then fills whole frames.
+        assert_eq!(pcm.read(&mut buf).unwrap(), 4);
+        assert_eq!(pcm.read(&mut buf).unwrap(), 8);
+        assert!(buf[..8].iter().all(|b| *b == 0));
+
+        drop(tx);
+        wait_for_chunks(&pcm, usize::MAX).await;
+        assert_eq!(pcm.read(&mut buf).unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn closed_stream_can_play_silence_forever() {
+        let mut pcm = AsyncPcmStream::new(stream::iter(Vec::<Vec<f32>>::new()), 48_000, 1)
+            .end_on_close(false);
+        wait_for_chunks(&pcm, usize::MAX).await;
+
+        let mut buf = [1u8; 8];
+        assert_eq!(pcm.read(&mut buf).unwrap(), 8);
+        assert_eq!(buf, [0; 8]);
+    }
+}
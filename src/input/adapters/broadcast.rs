@@ -0,0 +1,303 @@
+use super::cached::{CodecCacheError, ToAudioBytes};
+use crate::{
+    constants::{MONO_FRAME_BYTE_SIZE, SAMPLE_RATE_RAW},
+    input::{
+        codecs::{CODEC_REGISTRY, PROBE},
+        Input,
+        LiveInput,
+        RawAdapter,
+    },
+};
+use flume::{Receiver, Sender};
+use parking_lot::Mutex;
+use std::{
+    io::{ErrorKind as IoErrorKind, Read, Result as IoResult, Seek, SeekFrom},
+    sync::Arc,
+};
+use symphonia_core::{audio::Channels, io::MediaSource};
+use tracing::{debug, trace};
+
+/// Number of 20ms frames which may be queued for each subscriber by default.
+const DEFAULT_CAPACITY: usize = 10;
+
+/// Registers new subscribers with the decode thread, until it finishes.
+type Subscribe = Mutex<Option<Sender<Sender<Arc<[u8]>>>>>;
+
+/// A wrapper around an existing [`Input`] which decodes it exactly once, and
+/// distributes the same audio to any number of subscribers.
+///
+/// This is intended for synchronised playback of one (typically live) source
+/// across many calls. Unlike [`Decompressed`] or [`Memory`], audio is not stored
+/// past the point that every subscriber has read it: each subscriber holds a
+/// bounded queue of shared 20ms frames, so memory use does not grow with the
+/// length of the source.
+///
+/// Subscribers receive audio from the point at which they subscribe, rather
+/// than from the start of the source. The source is read at the pace of the
+/// slowest subscriber, and decoding waits while there are none. As a result,
+/// a subscriber which stops being read (e.g., a paused track) holds back every
+/// other subscriber once its queue fills: stop or drop such tracks rather than
+/// pausing them. Subscribers cannot seek.
+///
+/// ```
+/// # use songbird::input::{Broadcast, File};
+/// # async fn example() -> Result<(), songbird::input::cached::CodecCacheError> {
+/// let broadcast = Broadcast::new(File::new("live.mp3").into()).await?;
+///
+/// // Play `first` and `second` on different calls' drivers.
+/// let first = broadcast.subscribe();
+/// let second = broadcast.subscribe();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Decompressed`]: super::cached::Decompressed
+/// [`Memory`]: super::cached::Memory
+#[derive(Clone, Debug)]
+pub struct Broadcast {
+    subscribe_tx: Arc<Subscribe>,
+    chan_count: usize,
+    capacity: usize,
+}
+
+impl Broadcast {
+    /// Decode an existing [`Input`] on a dedicated thread, sharing its audio between subscribers.
+    ///
+    /// [`Input`]: Input
+    pub async fn new(source: Input) -> Result<Self, CodecCacheError> {
+        Self::with_capacity(source, DEFAULT_CAPACITY).await
+    }
+
+    /// Decode an existing [`Input`] on a dedicated thread, sharing its audio between subscribers.
+    ///
+    /// Each subscriber may have up to `capacity` 20ms frames queued (minimum 1).
+    ///
+    /// [`Input`]: Input
+    pub async fn with_capacity(source: Input, capacity: usize) -> Result<Self, CodecCacheError> {
+        let input = match source {
+            Input::Lazy(mut r) => {
+                let created = if r.should_create_async() {
+                    r.create_async().await.map_err(CodecCacheError::from)
+                } else {
+                    tokio::task::spawn_blocking(move || r.create().map_err(CodecCacheError::from))
+                        .await
+                        .map_err(CodecCacheError::from)
+                        .and_then(|v| v)
+                };
+
+                created.map(LiveInput::Raw)
+            },
+            Input::Live(LiveInput::Parsed(_), _) => Err(CodecCacheError::StreamNotAtStart),
+            Input::Live(a, _rec) => Ok(a),
+        }?;
+
+        let promoted =
+            tokio::task::spawn_blocking(move || input.promote(&CODEC_REGISTRY, &PROBE)).await??;
+
+        // If success, guaranteed to be Parsed
+        let LiveInput::Parsed(parsed) = promoted else {
+            unreachable!()
+        };
+
+        let chan_count = parsed
+            .decoder
+            .codec_params()
+            .channels
+            .map(Channels::count)
+            .ok_or(CodecCacheError::UnknownChannelCount)?;
+
+        let source = ToAudioBytes::new(parsed, Some(chan_count));
+        let (tx, subscribe_rx) = flume::unbounded();
+        let subscribe_tx = Arc::new(Mutex::new(Some(tx)));
+        let finished = Arc::downgrade(&subscribe_tx);
+
+        std::thread::spawn(move || {
+            trace!("Broadcast thread started.");
+            runner(source, chan_count * MONO_FRAME_BYTE_SIZE, &subscribe_rx);
+
+            // Close registration, then drop any subscribers who arrived too late
+            // so that they see the end of the stream.
+            if let Some(subscribe_tx) = finished.upgrade() {
+                *subscribe_tx.lock() = None;
+            }
+            drop(subscribe_rx.drain());
+            trace!("Broadcast thread finished.");
+        });
+
+        Ok(Self {
+            subscribe_tx,
+            chan_count,
+            capacity: capacity.max(1),
+        })
+    }
+
+    /// Creates a new subscriber to this source, which will receive audio
+    /// from the next decoded frame onwards.
+    ///
+    /// If the source has already ended, the subscriber will be empty.
+    #[must_use]
+    pub fn subscribe(&self) -> BroadcastReceiver {
+        let (tx, rx) = flume::bounded(self.capacity);
+        if let Some(subscribe_tx) = &*self.subscribe_tx.lock() {
+            drop(subscribe_tx.send(tx));
+        }
+
+        BroadcastReceiver {
+            rx,
+            chan_count: self.chan_count,
+            frame: Arc::from([]),
+            pos: 0,
+        }
+    }
+
+    /// Returns the number of channels in the shared audio.
+    #[must_use]
+    pub fn channels(&self) -> usize {
+        self.chan_count
+    }
+}
+
+/// One subscriber's view of a [`Broadcast`] source, as `f32` PCM data.
+///
+/// This is most easily played by converting into an [`Input`].
+#[derive(Debug)]
+pub struct BroadcastReceiver {
+    rx: Receiver<Arc<[u8]>>,
+    chan_count: usize,
+    frame: Arc<[u8]>,
+    pos: usize,
+}
+
+impl Read for BroadcastReceiver {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        while self.pos >= self.frame.len() {
+            match self.rx.recv() {
+                Ok(frame) => {
+                    self.frame = frame;
+                    self.pos = 0;
+                },
+                Err(_) => return Ok(0),
+            }
+        }
+
+        let n = (&self.frame[self.pos..]).read(buf)?;
+        self.pos += n;
+
+        Ok(n)
+    }
+}
+
+impl Seek for BroadcastReceiver {
+    fn seek(&mut self, _pos: SeekFrom) -> IoResult<u64> {
+        Err(IoErrorKind::Unsupported.into())
+    }
+}
+
+impl MediaSource for BroadcastReceiver {
+    fn is_seekable(&self) -> bool {
+        false
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        None
+    }
+}
+
+impl From<BroadcastReceiver> for Input {
+    fn from(val: BroadcastReceiver) -> Input {
+        let chan_count = val.chan_count as u32;
+        RawAdapter::new(val, SAMPLE_RATE_RAW as u32, chan_count).into()
+    }
+}
+
+fn runner(mut source: ToAudioBytes, frame_len: usize, subscribe_rx: &Receiver<Sender<Arc<[u8]>>>) {
+    let mut subscribers: Vec<Sender<Arc<[u8]>>> = vec![];
+
+    loop {
+        // Don't consume any audio until someone is listening.
+        if subscribers.is_empty() {
+            match subscribe_rx.recv() {
+                Ok(tx) => subscribers.push(tx),
+                Err(_) => break,
+            }
+        }
+        subscribers.extend(subscribe_rx.try_iter());
+
+        let frame = match read_frame(&mut source, frame_len) {
+            Ok(Some(frame)) => frame,
+            Ok(None) => break,
+            Err(e) => {
+                debug!("Broadcast source failed: {e:?}");
+                break;
+            },
+        };
+
+        subscribers.retain(|tx| tx.send(frame.clone()).is_ok());
+    }
+}
+
+fn read_frame(source: &mut ToAudioBytes, frame_len: usize) -> IoResult<Option<Arc<[u8]>>> {
+    let mut frame = vec![0u8; frame_len];
+    let mut filled = 0;
+
+    while filled < frame_len {
+        match source.read(&mut frame[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == IoErrorKind::Interrupted => {},
+            Err(e) => return Err(e),
+        }
+    }
+
+    frame.truncate(filled);
+
+    Ok((filled != 0).then(|| frame.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::{MONO_FRAME_SIZE, STEREO_FRAME_BYTE_SIZE},
+        test_utils,
+    };
+    use std::io::Cursor;
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn late_subscribers_start_from_now() {
+        let floats = test_utils::make_sine(20 * MONO_FRAME_SIZE, true);
+        let input = RawAdapter::new(Cursor::new(floats.clone()), 48_000, 2).into();
+
+        let broadcast = Broadcast::with_capacity(input, 2).await.unwrap();
+        assert_eq!(broadcast.channels(), 2);
+
+        let mut first = broadcast.subscribe();
+        let mut first_out = vec![0u8; 4 * STEREO_FRAME_BYTE_SIZE];
+        first.read_exact(&mut first_out).unwrap();
+
+        let mut second = broadcast.subscribe();
+        let second_thread = std::thread::spawn(move || {
+            let mut out = vec![];
+            second.read_to_end(&mut out).unwrap();
+            out
+        });
+
+        first.read_to_end(&mut first_out).unwrap();
+        let second_out = second_thread.join().unwrap();
+
+        // The first subscriber hears the whole source...
+        assert_eq!(first_out, floats);
+
+        // ...while the second joins partway through, in lockstep.
+        assert!(!second_out.is_empty());
+        assert!(second_out.len() <= floats.len() - 4 * STEREO_FRAME_BYTE_SIZE);
+        assert_eq!(second_out.len() % STEREO_FRAME_BYTE_SIZE, 0);
+        assert!(first_out.ends_with(&second_out));
+
+        // Subscribers arriving after the end receive nothing.
+        let mut out = vec![];
+        broadcast.subscribe().read_to_end(&mut out).unwrap();
+        assert!(out.is_empty());
+    }
+}
@@ -28,6 +28,7 @@ use std::{
         Result as IoResult,
         Seek,
         SeekFrom,
+        Write,
     },
     mem,
     sync::atomic::{AtomicUsize, Ordering},
@@ -227,6 +228,43 @@ impl Compressed {
             raw: self.raw.new_handle(),
         }
     }
+
+    /// Write out this source as a DCA1 file, from the beginning of the stream.
+    ///
+    /// This blocks until the entire input has been compressed, and so should be
+    /// called from a blocking context such as [`tokio::task::spawn_blocking`].
+    ///
+    /// Returns the number of bytes written.
+    pub fn write_dca<W: Write + ?Sized>(&self, writer: &mut W) -> IoResult<u64> {
+        let mut handle = self.new_handle();
+        std::io::copy(&mut handle, writer)
+    }
+}
+
+/// Compress an [`Input`] using Opus, writing the output to `writer` as a DCA1 file.
+///
+/// The produced file contains the input's metadata, and may be played back via
+/// [`File`] or any other source which is probed using [`PROBE`]. The `writer` is
+/// returned once the whole stream has been written.
+///
+/// The outer `Result` reports failures to compress `source`, while the inner
+/// `Result` reports failures to write the compressed stream to `writer`.
+///
+/// [`File`]: crate::input::File
+/// [`PROBE`]: static@PROBE
+pub async fn to_dca<W>(
+    source: Input,
+    bitrate: Bitrate,
+    mut writer: W,
+) -> Result<IoResult<W>, CodecCacheError>
+where
+    W: Write + Send + 'static,
+{
+    let compressed = Compressed::new(source, bitrate).await?;
+
+    tokio::task::spawn_blocking(move || compressed.write_dca(&mut writer).map(|_| writer))
+        .await
+        .map_err(Into::into)
 }
 
 fn create_metadata(
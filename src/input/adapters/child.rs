@@ -2,10 +2,14 @@ use crate::input::{AudioStream, Input, LiveInput};
 use std::{
     io::{Read, Result as IoResult},
     mem,
-    process::Child,
+    process::{Child, Output},
 };
 use symphonia_core::io::{MediaSource, ReadOnlySource};
-use tokio::runtime::Handle;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt},
+    process::Child as TokioChild,
+    runtime::Handle,
+};
 use tracing::debug;
 
 /// Handle for a child process which ensures that any subprocesses are properly closed
@@ -71,6 +75,67 @@ impl Drop for ChildContainer {
     }
 }
 
+/// Handle for a child process awaited by an async task, which kills and reaps the
+/// process if dropped before it exits (i.e., if the awaiting future is cancelled).
+pub(crate) struct AsyncChildContainer(Option<TokioChild>);
+
+impl AsyncChildContainer {
+    /// Waits for the child to exit, collecting any piped stdout and stderr.
+    pub(crate) async fn output(mut self) -> IoResult<Output> {
+        async fn read_all(pipe: Option<impl AsyncRead + Unpin>) -> IoResult<Vec<u8>> {
+            let mut out = vec![];
+            if let Some(mut pipe) = pipe {
+                pipe.read_to_end(&mut out).await?;
+            }
+            Ok(out)
+        }
+
+        let Some(child) = self.0.as_mut() else {
+            unreachable!("Child is only taken on exit or drop.")
+        };
+
+        let stdout = read_all(child.stdout.take());
+        let stderr = read_all(child.stderr.take());
+        let (status, stdout, stderr) = tokio::try_join!(child.wait(), stdout, stderr)?;
+
+        // The child has exited, and so needs no cleanup.
+        self.0 = None;
+
+        Ok(Output {
+            status,
+            stdout,
+            stderr,
+        })
+    }
+}
+
+impl From<TokioChild> for AsyncChildContainer {
+    fn from(child: TokioChild) -> Self {
+        Self(Some(child))
+    }
+}
+
+impl Drop for AsyncChildContainer {
+    fn drop(&mut self) {
+        let Some(mut child) = self.0.take() else {
+            return;
+        };
+
+        if let Err(e) = child.start_kill() {
+            debug!("Error killing child process: {:?}", e);
+        }
+
+        // Without a runtime, the process is left for Tokio to reap in the background.
+        if let Ok(handle) = Handle::try_current() {
+            handle.spawn(async move {
+                if let Err(e) = child.wait().await {
+                    debug!("Error awaiting child process: {:?}", e);
+                }
+            });
+        }
+    }
+}
+
 fn cleanup_child_processes(mut children: Vec<Child>) {
     let attempt = if let Some(child) = children.last_mut() {
         child.kill()
@@ -0,0 +1,486 @@
+use crate::{
+    constants::SAMPLE_RATE_RAW,
+    input::{AudioStreamError, Input, LiveInput, MakePlayableError, Parsed},
+};
+use std::{
+    io::ErrorKind as IoErrorKind,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use symphonia_core::{
+    audio::AudioBufferRef,
+    codecs::{
+        CodecDescriptor,
+        CodecParameters,
+        CodecRegistry,
+        Decoder,
+        DecoderOptions,
+        FinalizeResult,
+    },
+    errors::{Error as SymphError, Result as SymphResult},
+    formats::{Cue, FormatOptions, FormatReader, Packet, SeekMode, SeekTo, SeekedTo, Track},
+    io::MediaSourceStream,
+    meta::Metadata,
+    probe::Probe,
+    units::{Time, TimeBase},
+};
+use tokio::runtime::Handle as TokioHandle;
+
+/// Track ID used for all packets of a concatenated input.
+const CONCAT_TRACK_ID: u32 = 0;
+
+/// Timestamps of a concatenated input are counted in samples at the mixer's sample rate.
+const CONCAT_TIME_BASE: TimeBase = TimeBase {
+    numer: 1,
+    denom: SAMPLE_RATE_RAW as u32,
+};
+
+/// Adapter which plays several [`Input`]s back-to-back as a single stream.
+///
+/// The resulting [`Input`] behaves as one track: its position runs continuously across
+/// segment boundaries, and seeks are mapped onto the correct segment and offset. Each
+/// segment is decoded (and resampled) according to its own format, and may be passed
+/// through directly to the driver if it is Opus-encoded with 20ms frames.
+///
+/// All segments are made playable before playback begins, and so any lazy inputs will
+/// open their streams at this point. Seeking past a segment is only possible once its
+/// length is known, either from its headers or because it has been played to the end.
+///
+/// ```
+/// # use songbird::input::{codecs::*, ConcatInput, File};
+/// # async fn example() -> Result<(), songbird::input::MakePlayableError> {
+/// let input = ConcatInput::new(vec![
+///     File::new("intro.opus").into(),
+///     File::new("main.mp3").into(),
+///     File::new("outro.opus").into(),
+/// ])
+/// .make_playable_async(&CODEC_REGISTRY, &PROBE)
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ConcatInput {
+    inputs: Vec<Input>,
+}
+
+impl ConcatInput {
+    /// Creates a sequence of inputs, to be played in the given order.
+    #[must_use]
+    pub fn new(inputs: Vec<Input>) -> Self {
+        Self { inputs }
+    }
+
+    /// Adds an input to the end of this sequence.
+    #[must_use]
+    pub fn then(mut self, input: impl Into<Input>) -> Self {
+        self.inputs.push(input.into());
+        self
+    }
+
+    /// Makes all inputs in this sequence playable, combining them into a single [`Input`].
+    ///
+    /// *This is a blocking operation.* See [`Input::make_playable`] for details.
+    pub fn make_playable(
+        self,
+        codecs: &CodecRegistry,
+        probe: &Probe,
+        handle: &TokioHandle,
+    ) -> Result<Input, MakePlayableError> {
+        let parsed = self
+            .inputs
+            .into_iter()
+            .map(|input| input.make_playable(codecs, probe, handle).map(into_parsed))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        concat(parsed)
+    }
+
+    /// Makes all inputs in this sequence playable, combining them into a single [`Input`].
+    ///
+    /// Blocking I/O is placed on tokio blocking threads, as in [`Input::make_playable_async`].
+    pub async fn make_playable_async(
+        self,
+        codecs: &'static CodecRegistry,
+        probe: &'static Probe,
+    ) -> Result<Input, MakePlayableError> {
+        let mut parsed = Vec::with_capacity(self.inputs.len());
+
+        for input in self.inputs {
+            parsed.push(into_parsed(input.make_playable_async(codecs, probe).await?));
+        }
+
+        concat(parsed)
+    }
+}
+
+fn into_parsed(input: Input) -> Parsed {
+    match input {
+        Input::Live(LiveInput::Parsed(parsed), _) => parsed,
+        _ => unreachable!("Playable inputs are always parsed."),
+    }
+}
+
+fn concat(parsed: Vec<Parsed>) -> Result<Input, MakePlayableError> {
+    if parsed.is_empty() {
+        return Err(MakePlayableError::Create(AudioStreamError::Fail(
+            "no inputs were given to concatenate".into(),
+        )));
+    }
+
+    let current = Arc::new(AtomicUsize::new(0));
+    let supports_backseek = parsed.iter().all(|p| p.supports_backseek);
+
+    let mut segments = Vec::with_capacity(parsed.len());
+    let mut decoders = Vec::with_capacity(parsed.len());
+    let mut params = Vec::with_capacity(parsed.len());
+    let mut meta = None;
+
+    for p in parsed {
+        let track = p.format.tracks().iter().find(|t| t.id == p.track_id);
+        let time_base = track.and_then(|t| t.codec_params.time_base);
+        let len = track
+            .and_then(|t| t.codec_params.n_frames)
+            .zip(time_base)
+            .map(|(n, tb)| time_to_duration(tb.calc_time(n)));
+
+        let mut seg_params = p.decoder.codec_params().clone();
+        seg_params.time_base = Some(CONCAT_TIME_BASE);
+        seg_params.n_frames = None;
+        params.push(seg_params);
+
+        segments.push(Segment {
+            format: p.format,
+            track_id: p.track_id,
+            time_base,
+            len,
+            end_ts: 0,
+            started: false,
+        });
+        decoders.push(p.decoder);
+        meta.get_or_insert(p.meta);
+    }
+
+    segments[0].started = true;
+
+    let total_len = segments
+        .iter()
+        .map(|s| s.len)
+        .sum::<Option<Duration>>()
+        .map(duration_to_ts);
+
+    let mut track_params = params[0].clone();
+    track_params.n_frames = total_len;
+
+    let format = ConcatReader {
+        segments,
+        tracks: vec![Track::new(CONCAT_TRACK_ID, track_params)],
+        current: current.clone(),
+    };
+
+    let decoder = ConcatDecoder {
+        decoders,
+        params,
+        current,
+        last: 0,
+    };
+
+    Ok(Input::Live(
+        LiveInput::Parsed(Parsed {
+            format: Box::new(format),
+            decoder: Box::new(decoder),
+            track_id: CONCAT_TRACK_ID,
+            meta: meta.expect("At least one input is present."),
+            supports_backseek,
+        }),
+        None,
+    ))
+}
+
+fn time_to_duration(time: Time) -> Duration {
+    Duration::from_secs_f64(time.seconds as f64 + time.frac)
+}
+
+fn duration_to_ts(time: Duration) -> u64 {
+    (time.as_nanos() * SAMPLE_RATE_RAW as u128 / 1_000_000_000) as u64
+}
+
+struct Segment {
+    format: Box<dyn FormatReader>,
+    track_id: u32,
+    time_base: Option<TimeBase>,
+    /// Length of this segment, if known from its headers or from playing it out.
+    len: Option<Duration>,
+    /// End timestamp of the last packet read, in this segment's time base.
+    end_ts: u64,
+    /// Whether this segment has been read from, and must be rewound before replaying.
+    started: bool,
+}
+
+impl Segment {
+    fn seek(&mut self, mode: SeekMode, time: Duration) -> SymphResult<Duration> {
+        let seeked = self.format.seek(
+            mode,
+            SeekTo::Time {
+                time: Time::from(time.as_secs_f64()),
+                track_id: Some(self.track_id),
+            },
+        )?;
+
+        self.end_ts = seeked.actual_ts;
+
+        Ok(self
+            .time_base
+            .map_or(time, |tb| time_to_duration(tb.calc_time(seeked.actual_ts))))
+    }
+}
+
+/// Demuxer for a [`ConcatInput`], reading packets from each segment in turn.
+struct ConcatReader {
+    segments: Vec<Segment>,
+    tracks: Vec<Track>,
+    /// Index of the segment being read, shared with the [`ConcatDecoder`].
+    current: Arc<AtomicUsize>,
+}
+
+impl ConcatReader {
+    fn advance(&mut self) -> SymphResult<()> {
+        let idx = self.current.load(Ordering::Relaxed);
+        let seg = &mut self.segments[idx];
+
+        if let Some(tb) = seg.time_base {
+            seg.len
+                .get_or_insert(time_to_duration(tb.calc_time(seg.end_ts)));
+        }
+
+        let next = &mut self.segments[idx + 1];
+        if next.started {
+            next.seek(SeekMode::Accurate, Duration::ZERO)?;
+        }
+        next.started = true;
+
+        self.current.store(idx + 1, Ordering::Relaxed);
+
+        Ok(())
+    }
+}
+
+impl FormatReader for ConcatReader {
+    fn try_new(_source: MediaSourceStream, _options: &FormatOptions) -> SymphResult<Self> {
+        Err(SymphError::Unsupported(
+            "concatenated inputs must be built from other inputs",
+        ))
+    }
+
+    fn cues(&self) -> &[Cue] {
+        &[]
+    }
+
+    fn metadata(&mut self) -> Metadata<'_> {
+        let idx = self.current.load(Ordering::Relaxed);
+        self.segments[idx].format.metadata()
+    }
+
+    fn seek(&mut self, mode: SeekMode, to: SeekTo) -> SymphResult<SeekedTo> {
+        let (target, required_ts) = match to {
+            SeekTo::Time { time, .. } => {
+                let target = time_to_duration(time);
+                (target, duration_to_ts(target))
+            },
+            SeekTo::TimeStamp { ts, .. } => (time_to_duration(CONCAT_TIME_BASE.calc_time(ts)), ts),
+        };
+
+        // Find the segment containing `target`. We can't see past a segment of unknown
+        // length, so the seek is made relative to the start of such a segment.
+        let mut start = Duration::ZERO;
+        let mut idx = 0;
+        while idx + 1 < self.segments.len() {
+            match self.segments[idx].len {
+                Some(len) if target >= start + len => {
+                    start += len;
+                    idx += 1;
+                },
+                _ => break,
+            }
+        }
+
+        let landed = self.segments[idx].seek(mode, target.saturating_sub(start))?;
+
+        for (i, seg) in self.segments.iter_mut().enumerate() {
+            seg.started = i <= idx;
+        }
+        self.current.store(idx, Ordering::Relaxed);
+
+        Ok(SeekedTo {
+            track_id: CONCAT_TRACK_ID,
+            required_ts,
+            actual_ts: duration_to_ts(start + landed),
+        })
+    }
+
+    fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+
+    fn next_packet(&mut self) -> SymphResult<Packet> {
+        loop {
+            let idx = self.current.load(Ordering::Relaxed);
+            let seg = &mut self.segments[idx];
+
+            match seg.format.next_packet() {
+                Ok(pkt) if pkt.track_id() == seg.track_id => {
+                    seg.end_ts = pkt.ts + pkt.dur;
+
+                    // Timestamps remain in the segment's own time base, as child
+                    // decoders expect.
+                    return Ok(Packet::new_trimmed_from_boxed_slice(
+                        CONCAT_TRACK_ID,
+                        pkt.ts,
+                        pkt.dur,
+                        pkt.trim_start,
+                        pkt.trim_end,
+                        pkt.data,
+                    ));
+                },
+                Ok(_) => {},
+                Err(SymphError::IoError(e))
+                    if e.kind() == IoErrorKind::UnexpectedEof && idx + 1 < self.segments.len() =>
+                    self.advance()?,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn into_inner(self: Box<Self>) -> MediaSourceStream {
+        let idx = self.current.load(Ordering::Relaxed);
+        self.segments
+            .into_iter()
+            .nth(idx)
+            .expect("Current segment always exists.")
+            .format
+            .into_inner()
+    }
+}
+
+/// Decoder for a [`ConcatInput`], using the decoder of whichever segment is being read.
+struct ConcatDecoder {
+    decoders: Vec<Box<dyn Decoder>>,
+    /// Codec parameters of each segment, with timestamps in the concatenated time base.
+    params: Vec<CodecParameters>,
+    current: Arc<AtomicUsize>,
+    /// Index of the decoder which produced the last decoded packet.
+    last: usize,
+}
+
+impl Decoder for ConcatDecoder {
+    fn try_new(_params: &CodecParameters, _options: &DecoderOptions) -> SymphResult<Self> {
+        Err(SymphError::Unsupported(
+            "concatenated inputs must be built from other inputs",
+        ))
+    }
+
+    fn supported_codecs() -> &'static [CodecDescriptor] {
+        &[]
+    }
+
+    fn reset(&mut self) {
+        self.decoders[self.current.load(Ordering::Relaxed)].reset();
+    }
+
+    fn codec_params(&self) -> &CodecParameters {
+        &self.params[self.current.load(Ordering::Relaxed)]
+    }
+
+    fn decode(&mut self, packet: &Packet) -> SymphResult<AudioBufferRef<'_>> {
+        let idx = self.current.load(Ordering::Relaxed);
+
+        // A segment may be revisited after a seek, so clear out any stale state.
+        if idx != self.last {
+            self.decoders[idx].reset();
+            self.last = idx;
+        }
+
+        self.decoders[idx].decode(packet)
+    }
+
+    fn finalize(&mut self) -> FinalizeResult {
+        self.decoders[self.current.load(Ordering::Relaxed)].finalize()
+    }
+
+    fn last_decoded(&self) -> AudioBufferRef<'_> {
+        self.decoders[self.last].last_decoded()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::test_data::{FILE_WAV_TARGET, FILE_WEBM_TARGET},
+        driver::Driver,
+        input::{codecs::*, File},
+        tracks::{PlayMode, Track},
+        Config,
+    };
+
+    async fn wav_then_webm() -> Input {
+        ConcatInput::new(vec![File::new(FILE_WAV_TARGET).into()])
+            .then(File::new(FILE_WEBM_TARGET))
+            .make_playable_async(&CODEC_REGISTRY, &PROBE)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn concat_plays_all_segments() {
+        let input = wav_then_webm().await;
+        let Input::Live(LiveInput::Parsed(mut parsed), _) = input else {
+            unreachable!()
+        };
+
+        // Read through both segments, checking that each is decoded by its own codec.
+        let mut rates = vec![];
+        while let Ok(pkt) = parsed.format.next_packet() {
+            let rate = parsed.decoder.decode(&pkt).unwrap().spec().rate;
+            if rates.last() != Some(&rate) {
+                rates.push(rate);
+            }
+        }
+
+        assert_eq!(rates, vec![44_100, 48_000]);
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn concat_seeks_across_segments() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.clone());
+
+        // The WAV file is ~4.56s long.
+        let handle = driver.play(Track::from(wav_then_webm().await).pause());
+        t_handle.spawn_ticker();
+
+        let target = Duration::from_secs(6);
+        let landed = handle.seek_async(target).await.unwrap();
+        assert!(landed.abs_diff(target) < Duration::from_millis(100));
+
+        let landed = handle.seek_async(Duration::from_secs(1)).await.unwrap();
+        assert!(landed.abs_diff(Duration::from_secs(1)) < Duration::from_millis(100));
+
+        handle.play().unwrap();
+        let state = handle.get_info().await.unwrap();
+        assert!(!matches!(state.playing, PlayMode::Errored(_)));
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn empty_concat_fails() {
+        assert!(ConcatInput::new(vec![])
+            .make_playable_async(&CODEC_REGISTRY, &PROBE)
+            .await
+            .is_err());
+    }
+}
@@ -1,6 +1,9 @@
 mod async_adapter;
+mod async_pcm;
+mod broadcast;
 pub mod cached;
 mod child;
+mod concat;
 mod raw_adapter;
 
-pub use self::{async_adapter::*, child::*, raw_adapter::*};
+pub use self::{async_adapter::*, async_pcm::*, broadcast::*, child::*, concat::*, raw_adapter::*};
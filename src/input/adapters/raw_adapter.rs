@@ -1,7 +1,7 @@
 use crate::input::{AudioStream, Input, LiveInput};
 use byteorder::{LittleEndian, WriteBytesExt};
 use std::io::{ErrorKind as IoErrorKind, Read, Result as IoResult, Seek, SeekFrom, Write};
-use symphonia::core::io::MediaSource;
+use symphonia::core::{audio::SignalSpec, io::MediaSource};
 
 // format header is a magic string, followed by two LE u32s (sample rate, channel count)
 const FMT_HEADER: &[u8; 16] = b"SbirdRaw\0\0\0\0\0\0\0\0";
@@ -12,6 +12,10 @@ const FMT_HEADER: &[u8; 16] = b"SbirdRaw\0\0\0\0\0\0\0\0";
 /// the symphonia backend, particularly those with unknown length (making WAV
 /// unsuitable).
 ///
+/// Sources need not match the driver's output format: mono or stereo audio at any
+/// sample rate is resampled to 48kHz as part of mixing, so callers should *not*
+/// resample audio themselves beforehand.
+///
 /// The format is described in [`RawReader`].
 ///
 /// [`RawReader`]: crate::input::codecs::RawReader
@@ -23,6 +27,8 @@ pub struct RawAdapter<A> {
 
 impl<A: MediaSource> RawAdapter<A> {
     /// Wrap an input PCM byte source to be readable by symphonia.
+    ///
+    /// `channel_count` must be `1` or `2`.
     pub fn new(audio_source: A, sample_rate: u32, channel_count: u32) -> Self {
         let mut prepend: [u8; 16] = *FMT_HEADER;
         let mut write_space = &mut prepend[8..];
@@ -40,6 +46,14 @@ impl<A: MediaSource> RawAdapter<A> {
             pos: 0,
         }
     }
+
+    /// Wrap an input PCM byte source to be readable by symphonia, taking its sample
+    /// rate and channel count from `spec`.
+    ///
+    /// `spec` must describe a mono or stereo signal.
+    pub fn with_spec(audio_source: A, spec: SignalSpec) -> Self {
+        Self::new(audio_source, spec.rate, spec.channels.count() as u32)
+    }
 }
 
 impl<A: MediaSource> Read for RawAdapter<A> {
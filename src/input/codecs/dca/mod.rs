@@ -315,7 +315,12 @@ impl FormatReader for DcaReader {
 #[cfg(test)]
 mod tests {
     use crate::input::input_tests::*;
-    use crate::{constants::test_data::FILE_DCA_TARGET, input::File};
+    use crate::{
+        constants::test_data::{FILE_DCA_TARGET, FILE_WAV_TARGET},
+        input::{cached::to_dca, AudioStream, File, Input, LiveInput},
+    };
+    use audiopus::Bitrate;
+    use std::io::{Cursor, ErrorKind as IoErrorKind, Result as IoResult, Write};
 
     // NOTE: this covers youtube audio in a non-copyright-violating way, since
     // those depend on an HttpRequest internally anyhow.
@@ -342,4 +347,48 @@ mod tests {
     async fn opus_passthrough_when_other_tracks_paused() {
         track_plays_passthrough_when_is_only_active(|| File::new(FILE_DCA_TARGET)).await;
     }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn written_dca_track_plays() {
+        let file = File::new(FILE_WAV_TARGET);
+        let bytes = to_dca(file.into(), Bitrate::BitsPerSecond(128_000), vec![])
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(&bytes[..4], b"DCA1");
+
+        track_plays_passthrough(move || {
+            let input = Box::new(Cursor::new(bytes));
+            Input::Live(LiveInput::Raw(AudioStream { input, hint: None }), None)
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn dca_write_failures_are_reported_separately() {
+        struct FailingWriter;
+
+        impl Write for FailingWriter {
+            fn write(&mut self, _buf: &[u8]) -> IoResult<usize> {
+                Err(IoErrorKind::BrokenPipe.into())
+            }
+
+            fn flush(&mut self) -> IoResult<()> {
+                Ok(())
+            }
+        }
+
+        let file = File::new(FILE_WAV_TARGET);
+        let written = to_dca(file.into(), Bitrate::BitsPerSecond(128_000), FailingWriter)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            written.err().map(|e| e.kind()),
+            Some(IoErrorKind::BrokenPipe)
+        );
+    }
 }
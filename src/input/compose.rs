@@ -37,4 +37,32 @@ pub trait Compose: Send {
     async fn aux_metadata(&mut self) -> Result<AuxMetadata, AudioStreamError> {
         Err(AudioStreamError::Unsupported)
     }
+
+    /// Reports how well the audio streams created by this source support seeking.
+    ///
+    /// This allows, for instance, a seek bar to be disabled for a source before it
+    /// is played. Some sources must contact a remote server to find this out, and
+    /// any failure to do so is reported as [`SeekSupport::None`].
+    ///
+    /// Defaults to [`SeekSupport::None`] if a source cannot determine its seek support.
+    async fn seek_support(&mut self) -> SeekSupport {
+        SeekSupport::None
+    }
+}
+
+/// Seeking capabilities of a lazily instantiated audio source.
+///
+/// See [`Compose::seek_support`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum SeekSupport {
+    /// The source cannot seek, e.g., because it is a live stream.
+    None,
+    /// The source can only be read from its start.
+    ///
+    /// Forward seeks read through and discard audio up to the target position,
+    /// while backward seeks recreate the source. Both may be slow.
+    ForwardOnly,
+    /// The source can jump directly to any position, e.g., using HTTP range requests.
+    Full,
 }
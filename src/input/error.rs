@@ -1,3 +1,4 @@
+use super::AuxMetadata;
 use std::{error::Error, fmt::Display, time::Duration};
 use symphonia_core::errors::Error as SymphError;
 
@@ -12,12 +13,25 @@ pub enum AudioStreamError {
     /// The operation failed, and should be retried after a given time.
     ///
     /// Create operations invoked by the driver will retry on the first tick
-    /// after this time has passed.
+    /// after this time has passed, up to the limit set by [`Config::input_retry`].
+    /// A zero duration defers entirely to that configuration's backoff.
+    ///
+    /// [`Config::input_retry`]: crate::Config::input_retry
     RetryIn(Duration),
     /// The operation failed, and should not be retried.
     Fail(Box<dyn Error + Send + Sync>),
     /// The operation was not supported, and will never succeed.
     Unsupported,
+    /// The source refused access to the requested resource (e.g., an HTTP
+    /// `401 Unauthorized` or `403 Forbidden` response).
+    ///
+    /// This will not succeed until any credentials supplied with the request are changed.
+    Unauthorized,
+    /// The source resolved to a playlist of several tracks, rather than a single track.
+    ///
+    /// This holds the metadata of each entry, whose `source_url`s may be used to
+    /// create inputs for each track individually.
+    Playlist(Vec<AuxMetadata>),
 }
 
 impl Display for AudioStreamError {
@@ -27,6 +41,9 @@ impl Display for AudioStreamError {
             Self::RetryIn(t) => f.write_fmt(format_args!("retry in {:.2}s", t.as_secs_f32())),
             Self::Fail(why) => f.write_fmt(format_args!("{why}")),
             Self::Unsupported => f.write_str("operation was not supported"),
+            Self::Unauthorized => f.write_str("access to the resource was denied"),
+            Self::Playlist(entries) =>
+                write!(f, "source is a playlist of {} entries", entries.len()),
         }
     }
 }
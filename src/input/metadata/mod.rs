@@ -1,6 +1,9 @@
 use crate::error::JsonError;
 use std::time::Duration;
-use symphonia_core::{meta::Metadata as ContainerMetadata, probe::ProbedMetadata};
+use symphonia_core::{
+    meta::{Metadata as ContainerMetadata, MetadataRevision, StandardTagKey},
+    probe::ProbedMetadata,
+};
 
 pub(crate) mod ffprobe;
 pub(crate) mod ytdl;
@@ -54,6 +57,54 @@ impl AuxMetadata {
         Ok(output.into_aux_metadata())
     }
 
+    /// Extract metadata and stream details from a parsed audio file.
+    ///
+    /// Tags are read from both the probed (e.g., ID3) and format-level metadata, where
+    /// format-level tags take precedence. Channel count, sample rate, and duration are
+    /// taken from the selected track's codec parameters.
+    pub fn from_parsed(parsed: &mut Parsed) -> Self {
+        let params = parsed.decoder.codec_params();
+
+        let mut out = Self {
+            channels: params.channels.and_then(|c| u8::try_from(c.count()).ok()),
+            sample_rate: params.sample_rate,
+            duration: params.time_base.zip(params.n_frames).map(|(tb, n)| {
+                let time = tb.calc_time(n);
+                Duration::from_secs(time.seconds) + Duration::from_secs_f64(time.frac)
+            }),
+            ..Default::default()
+        };
+
+        if let Some(meta) = parsed.meta.get() {
+            out.apply_revision(meta.current());
+        }
+
+        out.apply_revision(parsed.format.metadata().current());
+
+        out
+    }
+
+    fn apply_revision(&mut self, revision: Option<&MetadataRevision>) {
+        let Some(revision) = revision else {
+            return;
+        };
+
+        for tag in revision.tags() {
+            let dest = match tag.std_key {
+                Some(StandardTagKey::TrackTitle) => {
+                    self.title = Some(tag.value.to_string());
+                    &mut self.track
+                },
+                Some(StandardTagKey::Artist) => &mut self.artist,
+                Some(StandardTagKey::Album) => &mut self.album,
+                Some(StandardTagKey::Date) => &mut self.date,
+                _ => continue,
+            };
+
+            *dest = Some(tag.value.to_string());
+        }
+    }
+
     /// Move all fields from an [`AuxMetadata`] object into a new one.
     #[must_use]
     pub fn take(&mut self) -> Self {
@@ -108,3 +159,31 @@ impl<'a> From<&'a mut Parsed> for Metadata<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        constants::test_data::FILE_SHORT_MP3_TARGET,
+        input::{codecs::*, File, Input},
+    };
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn aux_metadata_from_parsed_reads_tags() {
+        let mut input = Input::from(File::new(FILE_SHORT_MP3_TARGET));
+        assert!(input.aux_metadata_from_parsed().is_none());
+
+        let mut input = input
+            .make_playable_async(&CODEC_REGISTRY, &PROBE)
+            .await
+            .unwrap();
+        let meta = input.aux_metadata_from_parsed().unwrap();
+
+        assert_eq!(meta.track.as_deref(), Some("Ting!"));
+        assert_eq!(meta.artist.as_deref(), Some("FelixMcFelix"));
+        assert_eq!(meta.album.as_deref(), Some("Songbird"));
+        assert_eq!(meta.channels, Some(1));
+        assert_eq!(meta.sample_rate, Some(44_100));
+        assert!(meta.duration.is_some());
+    }
+}
@@ -13,6 +13,7 @@
 //! * Any owned byte slice: `&'static [u8]`, `Bytes`, or `Vec<u8>`,
 //! * [`File`] offers a lazy way to open local audio files,
 //! * [`HttpRequest`] streams a given file from a URL using the reqwest HTTP library,
+//! * [`HlsInput`] streams the segments of an HLS (`.m3u8`) playlist, including live streams,
 //! * [`YoutubeDl`] uses `yt-dlp` (or any other `youtube-dl`-like program) to scrape
 //!   a target URL for a usable audio stream, before opening an [`HttpRequest`].
 //!
@@ -21,9 +22,12 @@
 //! * [`cached::*`], which allow seeking and shared caching of an input stream (storing
 //!   it in memory in a variety of formats),
 //! * [`ChildContainer`] for managing audio given by a process chain,
-//! * [`RawAdapter`], for feeding in a synchronous `f32`-PCM stream, and
+//! * [`RawAdapter`], for feeding in a synchronous `f32`-PCM stream,
 //! * [`AsyncAdapterStream`], for passing bytes from an `AsyncRead` (`+ AsyncSeek`) stream
-//!   into the mixer.
+//!   into the mixer,
+//! * [`AsyncPcmStream`], for playing PCM chunks produced by an async `Stream`,
+//! * [`Broadcast`], for decoding one input once and playing it on many calls in lockstep, and
+//! * [`ConcatInput`], for playing several inputs back-to-back as a single track.
 //!
 //! ## Opus frame passthrough.
 //! Some sources, such as [`Compressed`] or any WebM/Opus/DCA file, support
@@ -32,6 +36,7 @@
 //! cases, this can greatly reduce the CPU cost required by the driver.
 //!
 //! This functionality requires that:
+//!  * passthrough is enabled in the driver's [`Config`],
 //!  * only one track is active (including paused tracks),
 //!  * that track's input supports direct Opus frame reads,
 //!  * this input's frames are all sized to 20ms.
@@ -43,6 +48,7 @@
 //! [symphonia]: https://docs.rs/symphonia
 //! [`Read`]: https://doc.rust-lang.org/std/io/trait.Read.html
 //! [`Compressed`]: cached::Compressed
+//! [`Config`]: crate::Config
 //! [DCA1]: https://github.com/bwmarrin/dca
 //! [`registry::*`]: registry
 //! [`cached::*`]: cached
@@ -79,7 +85,10 @@ pub use self::{
 pub use symphonia_core as core;
 
 use std::{error::Error, io::Cursor};
-use symphonia_core::{codecs::CodecRegistry, probe::Probe};
+use symphonia_core::{
+    codecs::CodecRegistry,
+    probe::{Hint, Probe},
+};
 use tokio::runtime::Handle as TokioHandle;
 
 /// An audio source, which can be live or lazily initialised.
@@ -196,6 +205,27 @@ pub enum Input {
 }
 
 impl Input {
+    /// Creates a live input from an in-memory byte buffer, as with `From<T>`, using a
+    /// file `extension` (e.g., `"mp3"`) to guide format detection.
+    ///
+    /// This makes probing more reliable for headerless formats, or those
+    /// whose leading bytes are ambiguous.
+    #[must_use]
+    pub fn from_bytes_with_hint<T: AsRef<[u8]> + Send + Sync + 'static>(
+        val: T,
+        extension: &str,
+    ) -> Self {
+        let mut hint = Hint::new();
+        hint.with_extension(extension);
+
+        let raw_src = LiveInput::Raw(AudioStream {
+            input: Box::new(Cursor::new(val)),
+            hint: Some(hint),
+        });
+
+        Input::Live(raw_src, None)
+    }
+
     /// Requests auxiliary metadata which can be accessed without parsing the file.
     ///
     /// This method will never be called by songbird but allows, for instance, access to metadata
@@ -211,6 +241,17 @@ impl Input {
         }
     }
 
+    /// Builds [`AuxMetadata`] from the tags and codec parameters of a parsed input.
+    ///
+    /// This complements [`Self::aux_metadata`], and does not require a [`Compose`].
+    /// See [`AuxMetadata::from_parsed`] for the fields which are filled in.
+    ///
+    /// Returns `None` unless this input is both [`Self::Live`] and has been fully parsed,
+    /// e.g., via [`Self::make_playable`].
+    pub fn aux_metadata_from_parsed(&mut self) -> Option<AuxMetadata> {
+        self.parsed_mut().map(AuxMetadata::from_parsed)
+    }
+
     /// Tries to get any information about this audio stream acquired during parsing.
     ///
     /// Only exists when this input is both [`Self::Live`] and has been fully parsed.
@@ -223,6 +264,46 @@ impl Input {
         }
     }
 
+    /// Parses this input in place on the current thread, and returns any metadata
+    /// found while doing so.
+    ///
+    /// Unlike [`Self::make_playable`], this does not require a tokio runtime. Any
+    /// [`Input::Live`] source (e.g., one made `From<Vec<u8>>`) can be probed, as can an
+    /// [`Input::Lazy`] whose [`Compose`] can be created synchronously. Other lazy
+    /// inputs fail with [`AudioStreamError::Unsupported`], and are left unchanged.
+    ///
+    /// *This is a blocking operation. If you wish to use this from an async task, you
+    /// must do so within `spawn_blocking`.*
+    pub fn probe_blocking(
+        &mut self,
+        codecs: &CodecRegistry,
+        probe: &Probe,
+    ) -> Result<Metadata<'_>, MakePlayableError> {
+        if matches!(self, Self::Lazy(lazy) if lazy.should_create_async()) {
+            return Err(AudioStreamError::Unsupported.into());
+        }
+
+        if !self.is_playable() {
+            let (live, lazy) = match std::mem::replace(self, Self::from(&[][..])) {
+                Self::Lazy(mut lazy) => match lazy.create() {
+                    Ok(created) => (LiveInput::Raw(created), Some(lazy)),
+                    Err(e) => {
+                        *self = Self::Lazy(lazy);
+                        return Err(e.into());
+                    },
+                },
+                Self::Live(live, lazy) => (live, lazy),
+            };
+
+            *self = Self::Live(live.promote(codecs, probe)?, lazy);
+        }
+
+        match self {
+            Self::Live(LiveInput::Parsed(parsed), _) => Ok(parsed.into()),
+            _ => unreachable!(),
+        }
+    }
+
     /// Initialises (but does not parse) an [`Input::Lazy`] into an [`Input::Live`],
     /// placing blocking I/O on the current thread.
     ///
@@ -381,3 +462,51 @@ impl<T: AsRef<[u8]> + Send + Sync + 'static> From<T> for Input {
         Input::Live(raw_src, None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::codecs::{CODEC_REGISTRY, PROBE};
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn bytes_with_hint_are_playable() {
+        let bytes = std::fs::read(crate::constants::test_data::FILE_SHORT_MP3_TARGET).unwrap();
+        let input = Input::from_bytes_with_hint(bytes, "mp3");
+
+        match &input {
+            Input::Live(LiveInput::Raw(stream), None) => assert!(stream.hint.is_some()),
+            _ => panic!("Bytes should create a raw live input."),
+        }
+
+        let input = input
+            .make_playable_async(&CODEC_REGISTRY, &PROBE)
+            .await
+            .unwrap();
+        assert!(input.is_playable());
+    }
+
+    #[test]
+    fn live_inputs_probe_without_runtime() {
+        let bytes = std::fs::read(crate::constants::test_data::FILE_WAV_TARGET).unwrap();
+        let mut input = Input::from(bytes);
+
+        let meta = input.probe_blocking(&CODEC_REGISTRY, &PROBE).unwrap();
+        assert!(meta.probe.get().is_none());
+        assert!(input.is_playable());
+
+        // Probing a parsed input reuses its existing state.
+        assert!(input.probe_blocking(&CODEC_REGISTRY, &PROBE).is_ok());
+    }
+
+    #[test]
+    fn async_lazy_inputs_are_not_probed() {
+        let mut input: Input = File::new(crate::constants::test_data::FILE_WAV_TARGET).into();
+
+        assert!(matches!(
+            input.probe_blocking(&CODEC_REGISTRY, &PROBE),
+            Err(MakePlayableError::Create(AudioStreamError::Unsupported))
+        ));
+        assert!(matches!(input, Input::Lazy(_)));
+    }
+}
@@ -1,11 +1,29 @@
-use crate::input::{AudioStream, AudioStreamError, Compose, Input};
-use std::{error::Error, ffi::OsStr, path::Path};
+use crate::input::{
+    AsyncAdapterStream,
+    AsyncMediaSource,
+    AudioStream,
+    AudioStreamError,
+    Compose,
+    Input,
+};
+use async_trait::async_trait;
+use std::{
+    error::Error,
+    ffi::OsStr,
+    io::{Result as IoResult, SeekFrom},
+    path::Path,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
 use symphonia_core::{io::MediaSource, probe::Hint};
+use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
 
 /// A lazily instantiated local file.
 #[derive(Clone, Debug)]
 pub struct File<P: AsRef<Path>> {
     path: P,
+    growth_timeout: Option<Duration>,
 }
 
 impl<P: AsRef<Path>> File<P> {
@@ -13,7 +31,22 @@ impl<P: AsRef<Path>> File<P> {
     ///
     /// This is infallible as the path is only checked during creation.
     pub fn new(path: P) -> Self {
-        Self { path }
+        Self {
+            path,
+            growth_timeout: None,
+        }
+    }
+
+    #[must_use]
+    /// Treats this file as one which is still being written (e.g., a live `WebM`
+    /// recording), so that playback continues as data is appended.
+    ///
+    /// On reaching the end of the file, playback waits up to `timeout` for more
+    /// data before the file is considered to have ended. A writer which stalls for
+    /// longer than this will end the track early.
+    pub fn growing(mut self, timeout: Duration) -> Self {
+        self.growth_timeout = Some(timeout);
+        self
     }
 }
 
@@ -38,7 +71,15 @@ impl<P: AsRef<Path> + Send + Sync> Compose for File<P> {
             .await
             .map_err(|io| AudioStreamError::Fail(Box::new(io)))?;
 
-        let input = Box::new(file.into_std().await);
+        let input: Box<dyn MediaSource> = if let Some(timeout) = self.growth_timeout {
+            Box::new(AsyncAdapterStream::new_growing(
+                Box::new(GrowingFile(file)),
+                64 * 1024,
+                timeout,
+            ))
+        } else {
+            Box::new(file.into_std().await)
+        };
 
         let mut hint = Hint::default();
         if let Some(ext) = self.path.as_ref().extension().and_then(OsStr::to_str) {
@@ -84,3 +125,67 @@ impl<P: AsRef<Path> + Send + Sync> Compose for File<P> {
     //         .map_err(|e| AudioStreamError::Fail(Box::new(e)))
     // }
 }
+
+/// A file whose final length is not yet known.
+struct GrowingFile(tokio::fs::File);
+
+impl AsyncRead for GrowingFile {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<IoResult<()>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
+
+impl AsyncSeek for GrowingFile {
+    fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> IoResult<()> {
+        Pin::new(&mut self.0).start_seek(position)
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<u64>> {
+        Pin::new(&mut self.0).poll_complete(cx)
+    }
+}
+
+#[async_trait]
+impl AsyncMediaSource for GrowingFile {
+    fn is_seekable(&self) -> bool {
+        true
+    }
+
+    async fn byte_len(&self) -> Option<u64> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[ntest::timeout(10_000)]
+    async fn growing_file_waits_for_appended_data() {
+        let path = crate::test_utils::temp_path("growing-file");
+        let mut writer = std::fs::File::create(&path).unwrap();
+        writer.write_all(b"first").unwrap();
+
+        let mut file = File::new(path.clone()).growing(Duration::from_secs(1));
+        let mut input = file.create_async().await.unwrap().input;
+
+        let reader = tokio::task::spawn_blocking(move || {
+            let mut out = vec![];
+            input.read_to_end(&mut out).unwrap();
+            out
+        });
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        writer.write_all(b" second").unwrap();
+
+        // The read only completes once the file stops growing.
+        assert_eq!(reader.await.unwrap(), b"first second");
+        drop(std::fs::remove_file(path));
+    }
+}
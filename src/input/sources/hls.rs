@@ -0,0 +1,933 @@
+use crate::input::{
+    AsyncAdapterStream,
+    AsyncMediaSource,
+    AudioStream,
+    AudioStreamError,
+    Compose,
+    Input,
+    SeekSupport,
+};
+use async_trait::async_trait;
+use futures::StreamExt;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, RANGE},
+    Client,
+    Response,
+    StatusCode,
+    Url,
+};
+use std::{
+    io::{Error as IoError, ErrorKind as IoErrorKind, Result as IoResult, SeekFrom},
+    path::Path,
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+use symphonia_core::{io::MediaSource, probe::Hint};
+use tokio::{
+    io::{AsyncRead, AsyncSeek, ReadBuf},
+    sync::mpsc::{self, Receiver, Sender},
+};
+use tracing::{debug, warn};
+
+/// Number of segment chunks buffered between the fetching task and the mixer.
+const CHUNK_BUFFER: usize = 16;
+
+/// Number of segments from the end of a live playlist at which playback begins.
+const LIVE_START_SEGMENTS: usize = 3;
+
+/// Number of consecutive failed reloads of a live playlist before the stream ends.
+const MAX_RELOAD_FAILURES: usize = 3;
+
+/// Shortest wait between reloads of a live playlist, whatever its target duration.
+const MIN_RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A lazily instantiated HTTP Live Streaming (HLS) source.
+///
+/// This fetches an `.m3u8` playlist, and streams its media segments back-to-back
+/// as a single input. If given a master playlist, an audio-only rendition is chosen
+/// where one is available, and otherwise the lowest-bandwidth variant stream is used.
+///
+/// Live playlists (those without an `#EXT-X-ENDLIST` tag) are reloaded as new
+/// segments are published, and playback begins near the live edge. Tracks from live
+/// playlists end once the playlist is ended by the server, or cannot be reloaded.
+/// VOD playlists are played in full, and cannot be seeked.
+///
+/// Segments are joined as-is, so must be in a format which symphonia can parse when
+/// concatenated, such as packed audio (e.g., ADTS AAC or MP3) or fragmented MP4.
+/// Initialisation sections given by `#EXT-X-MAP` are inserted before the first segment
+/// using them. Codec parameters must not change across `#EXT-X-DISCONTINUITY` tags.
+/// MPEG-TS and encrypted segments are not supported: such streams should be played
+/// via [`YoutubeDl`] or another external program.
+///
+/// [`YoutubeDl`]: super::YoutubeDl
+#[derive(Clone, Debug)]
+pub struct HlsInput {
+    /// A reqwest client instance used to fetch the playlist and its segments.
+    pub client: Client,
+    /// The URL of the HLS playlist.
+    pub request: String,
+    /// HTTP header fields to add to all playlist and segment requests.
+    pub headers: HeaderMap,
+    /// Whether segments which fail to download (e.g., with a 404 status) are skipped.
+    ///
+    /// If `false`, any failed segment ends the stream with an error.
+    ///
+    /// Defaults to `true`.
+    pub skip_failed_segments: bool,
+}
+
+impl HlsInput {
+    #[must_use]
+    /// Create a lazy HLS stream from a playlist URL.
+    pub fn new(client: Client, request: String) -> Self {
+        Self {
+            client,
+            request,
+            headers: HeaderMap::default(),
+            skip_failed_segments: true,
+        }
+    }
+
+    #[must_use]
+    /// Adds an HTTP header field to all created requests, replacing any existing
+    /// values for `key`.
+    pub fn header(mut self, key: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(key, value);
+        self
+    }
+
+    #[must_use]
+    /// Adds several HTTP header fields to all created requests, replacing any existing
+    /// values for their keys.
+    pub fn headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    #[must_use]
+    /// Sets whether segments which fail to download are skipped.
+    ///
+    /// See [`skip_failed_segments`] for details.
+    ///
+    /// [`skip_failed_segments`]: Self::skip_failed_segments
+    pub fn skip_failed_segments(mut self, skip: bool) -> Self {
+        self.skip_failed_segments = skip;
+        self
+    }
+
+    async fn get(&self, url: Url, range: Option<ByteRange>) -> Result<Response, AudioStreamError> {
+        let mut req = self.client.get(url).headers(self.headers.clone());
+
+        if let Some(range) = range {
+            req = req.header(
+                RANGE,
+                format!(
+                    "bytes={}-{}",
+                    range.offset,
+                    (range.offset + range.len).saturating_sub(1)
+                ),
+            );
+        }
+
+        let resp = req
+            .send()
+            .await
+            .map_err(|e| AudioStreamError::Fail(Box::new(e)))?;
+
+        if matches!(
+            resp.status(),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+        ) {
+            return Err(AudioStreamError::Unauthorized);
+        }
+
+        if !resp.status().is_success() {
+            let msg: Box<dyn std::error::Error + Send + Sync + 'static> =
+                format!("failed with http status code: {}", resp.status()).into();
+            return Err(AudioStreamError::Fail(msg));
+        }
+
+        Ok(resp)
+    }
+
+    async fn fetch_playlist(&self, url: Url) -> Result<Playlist, AudioStreamError> {
+        let resp = self.get(url, None).await?;
+
+        // Relative URIs are resolved against the playlist's final URL, after redirects.
+        let base = resp.url().clone();
+        let text = resp
+            .text()
+            .await
+            .map_err(|e| AudioStreamError::Fail(Box::new(e)))?;
+
+        Playlist::parse(&text, &base)
+    }
+
+    async fn fetch_media_playlist(&self, url: Url) -> Result<MediaPlaylist, AudioStreamError> {
+        match self.fetch_playlist(url).await? {
+            Playlist::Media(media) => Ok(media),
+            Playlist::Master(_) => Err(AudioStreamError::Fail(
+                "expected an HLS media playlist, but found a master playlist".into(),
+            )),
+        }
+    }
+
+    /// Fetches the media playlist to be played, choosing a variant if given a master playlist.
+    async fn resolve(&self) -> Result<(Url, MediaPlaylist), AudioStreamError> {
+        let url = Url::parse(&self.request).map_err(|e| AudioStreamError::Fail(Box::new(e)))?;
+
+        match self.fetch_playlist(url.clone()).await? {
+            Playlist::Media(media) => Ok((url, media)),
+            Playlist::Master(variants) => {
+                let url = Variant::choose(variants)
+                    .ok_or_else(|| {
+                        AudioStreamError::Fail("HLS master playlist had no variants".into())
+                    })?
+                    .uri;
+                let media = self.fetch_media_playlist(url.clone()).await?;
+                Ok((url, media))
+            },
+        }
+    }
+
+    /// Sends the body of a segment (or initialisation section) to the stream.
+    ///
+    /// Returns `Ok(false)` if the stream has been dropped.
+    async fn send_resource(
+        &self,
+        tx: &Sender<IoResult<Vec<u8>>>,
+        url: &Url,
+        range: Option<ByteRange>,
+    ) -> Result<bool, AudioStreamError> {
+        let mut body = self.get(url.clone(), range).await?.bytes_stream();
+
+        while let Some(chunk) = body.next().await {
+            let chunk = chunk.map_err(|e| AudioStreamError::Fail(Box::new(e)))?;
+            if tx.send(Ok(chunk.to_vec())).await.is_err() {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Fetches each segment of a media playlist in order, reloading it while live.
+    async fn stream_segments(
+        self,
+        url: Url,
+        mut playlist: MediaPlaylist,
+        tx: Sender<IoResult<Vec<u8>>>,
+    ) {
+        let start = if playlist.ended {
+            0
+        } else {
+            playlist.segments.len().saturating_sub(LIVE_START_SEGMENTS)
+        };
+        let mut next_seq = playlist
+            .segments
+            .get(start)
+            .map_or(playlist.media_sequence, |s| s.sequence);
+        let mut map = None;
+        let mut reload_failures = 0;
+
+        loop {
+            let mut played_any = false;
+            let first_seq = next_seq;
+
+            for segment in playlist.segments.iter().filter(|s| s.sequence >= first_seq) {
+                next_seq = segment.sequence + 1;
+                played_any = true;
+
+                let mut res = Ok(true);
+
+                if segment.map.is_some() && segment.map != map {
+                    map.clone_from(&segment.map);
+                    if let Some(map) = &map {
+                        res = self.send_resource(&tx, &map.uri, map.range).await;
+                    }
+                }
+
+                if matches!(res, Ok(true)) {
+                    res = self.send_resource(&tx, &segment.uri, segment.range).await;
+                }
+
+                match res {
+                    Ok(true) => {},
+                    Ok(false) => return,
+                    Err(e) if self.skip_failed_segments => {
+                        debug!("Skipping HLS segment {}: {:?}", segment.uri, e);
+                    },
+                    Err(e) => {
+                        _ = tx.send(Err(IoError::other(e))).await;
+                        return;
+                    },
+                }
+            }
+
+            if playlist.ended {
+                return;
+            }
+
+            tokio::time::sleep(playlist.reload_wait(played_any)).await;
+
+            if tx.is_closed() {
+                return;
+            }
+
+            match self.fetch_media_playlist(url.clone()).await {
+                Ok(new_playlist) => {
+                    reload_failures = 0;
+
+                    // Segments may have left the playlist before we could play them.
+                    next_seq = next_seq.max(new_playlist.media_sequence);
+                    playlist = new_playlist;
+                },
+                Err(e) => {
+                    reload_failures += 1;
+                    warn!("Failed to reload HLS playlist: {:?}", e);
+
+                    if reload_failures >= MAX_RELOAD_FAILURES {
+                        _ = tx.send(Err(IoError::other(e))).await;
+                        return;
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Compose for HlsInput {
+    fn create(&mut self) -> Result<AudioStream<Box<dyn MediaSource>>, AudioStreamError> {
+        Err(AudioStreamError::Unsupported)
+    }
+
+    async fn create_async(
+        &mut self,
+    ) -> Result<AudioStream<Box<dyn MediaSource>>, AudioStreamError> {
+        let (url, playlist) = self.resolve().await?;
+
+        if playlist.segments.is_empty() && playlist.ended {
+            return Err(AudioStreamError::Fail(
+                "HLS playlist contained no segments".into(),
+            ));
+        }
+
+        let hint = playlist.segments.first().and_then(|s| {
+            let uri = s.map.as_ref().map_or(&s.uri, |m| &m.uri);
+            let ext = Path::new(uri.path()).extension()?.to_str()?;
+
+            let mut hint = Hint::new();
+            hint.with_extension(if ext == "m4s" { "mp4" } else { ext });
+            Some(hint)
+        });
+
+        let (tx, rx) = mpsc::channel(CHUNK_BUFFER);
+        tokio::spawn(self.clone().stream_segments(url, playlist, tx));
+
+        let stream = HlsStream {
+            rx,
+            chunk: Vec::new(),
+            chunk_pos: 0,
+        };
+
+        Ok(AudioStream {
+            input: Box::new(AsyncAdapterStream::new(Box::new(stream), 64 * 1024)),
+            hint,
+        })
+    }
+
+    fn should_create_async(&self) -> bool {
+        true
+    }
+
+    async fn seek_support(&mut self) -> SeekSupport {
+        // Segments are streamed in order, so only complete playlists have
+        // a position to seek to.
+        match self.resolve().await {
+            Ok((_, playlist)) if playlist.ended => SeekSupport::ForwardOnly,
+            _ => SeekSupport::None,
+        }
+    }
+}
+
+impl From<HlsInput> for Input {
+    fn from(val: HlsInput) -> Self {
+        Input::Lazy(Box::new(val))
+    }
+}
+
+/// Bytes of a resource fetched by an HLS stream.
+struct HlsStream {
+    rx: Receiver<IoResult<Vec<u8>>>,
+    chunk: Vec<u8>,
+    chunk_pos: usize,
+}
+
+impl AsyncRead for HlsStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<IoResult<()>> {
+        let this = self.get_mut();
+
+        while this.chunk_pos >= this.chunk.len() {
+            match ready!(this.rx.poll_recv(cx)) {
+                Some(Ok(chunk)) => {
+                    this.chunk = chunk;
+                    this.chunk_pos = 0;
+                },
+                Some(Err(e)) => return Poll::Ready(Err(e)),
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+
+        let n = buf.remaining().min(this.chunk.len() - this.chunk_pos);
+        buf.put_slice(&this.chunk[this.chunk_pos..][..n]);
+        this.chunk_pos += n;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncSeek for HlsStream {
+    fn start_seek(self: Pin<&mut Self>, _position: SeekFrom) -> IoResult<()> {
+        Err(IoErrorKind::Unsupported.into())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<IoResult<u64>> {
+        unreachable!()
+    }
+}
+
+#[async_trait]
+impl AsyncMediaSource for HlsStream {
+    fn is_seekable(&self) -> bool {
+        false
+    }
+
+    async fn byte_len(&self) -> Option<u64> {
+        None
+    }
+}
+
+#[derive(Debug)]
+enum Playlist {
+    Master(Vec<Variant>),
+    Media(MediaPlaylist),
+}
+
+#[derive(Debug)]
+struct Variant {
+    uri: Url,
+    bandwidth: u64,
+    audio_only: bool,
+}
+
+impl Variant {
+    /// Picks the best audio-only rendition, or the cheapest variant containing audio.
+    fn choose(variants: Vec<Self>) -> Option<Self> {
+        if variants.iter().any(|v| v.audio_only) {
+            variants
+                .into_iter()
+                .filter(|v| v.audio_only)
+                .max_by_key(|v| v.bandwidth)
+        } else {
+            variants.into_iter().min_by_key(|v| v.bandwidth)
+        }
+    }
+}
+
+#[derive(Debug)]
+struct MediaPlaylist {
+    target_duration: Duration,
+    media_sequence: u64,
+    segments: Vec<Segment>,
+    ended: bool,
+}
+
+impl MediaPlaylist {
+    /// Returns how long to wait before reloading this (live) playlist.
+    ///
+    /// This is a full target duration if new segments were found, or half of one
+    /// otherwise, but never less than [`MIN_RELOAD_INTERVAL`].
+    fn reload_wait(&self, played_any: bool) -> Duration {
+        let wait = if played_any {
+            self.target_duration
+        } else {
+            self.target_duration / 2
+        };
+
+        wait.max(MIN_RELOAD_INTERVAL)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Segment {
+    uri: Url,
+    range: Option<ByteRange>,
+    map: Option<MediaMap>,
+    sequence: u64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct MediaMap {
+    uri: Url,
+    range: Option<ByteRange>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ByteRange {
+    len: u64,
+    offset: u64,
+}
+
+impl ByteRange {
+    /// Parses a byte range of the form `<len>[@<offset>]`.
+    fn parse(val: &str, default_offset: u64) -> Option<Self> {
+        let (len, offset) = match val.split_once('@') {
+            Some((len, offset)) => (len, Some(offset.parse().ok()?)),
+            None => (val, None),
+        };
+
+        Some(Self {
+            len: len.parse().ok()?,
+            offset: offset.unwrap_or(default_offset),
+        })
+    }
+}
+
+impl Playlist {
+    fn parse(text: &str, base: &Url) -> Result<Self, AudioStreamError> {
+        let fail =
+            |msg: &str| AudioStreamError::Fail(format!("invalid HLS playlist: {msg}").into());
+        let resolve = |uri: &str| {
+            base.join(uri)
+                .map_err(|e| AudioStreamError::Fail(Box::new(e)))
+        };
+
+        let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+
+        if lines.next() != Some("#EXTM3U") {
+            return Err(fail("missing #EXTM3U header"));
+        }
+
+        let mut variants = vec![];
+        let mut pending_variant = None;
+
+        let mut media = MediaPlaylist {
+            target_duration: Duration::ZERO,
+            media_sequence: 0,
+            segments: vec![],
+            ended: false,
+        };
+        let mut map = None;
+        let mut pending_range = None;
+        let mut last_range: Option<(Url, ByteRange)> = None;
+
+        for line in lines {
+            let (tag, value) = line.split_once(':').unwrap_or((line, ""));
+
+            match tag {
+                "#EXT-X-STREAM-INF" => {
+                    let attrs = parse_attributes(value);
+                    let bandwidth = attr(&attrs, "BANDWIDTH")
+                        .and_then(|b| b.parse().ok())
+                        .unwrap_or(u64::MAX);
+                    let audio_only = attr(&attrs, "CODECS").is_some_and(|codecs| {
+                        codecs.split(',').all(|c| {
+                            let c = c.trim();
+                            c.starts_with("mp4a") || c == "opus" || c == "flac" || c == "mp3"
+                        })
+                    });
+                    pending_variant = Some((bandwidth, audio_only));
+                },
+                "#EXT-X-MEDIA" => {
+                    let attrs = parse_attributes(value);
+                    if attr(&attrs, "TYPE") == Some("AUDIO") {
+                        if let Some(uri) = attr(&attrs, "URI") {
+                            // Prefer default renditions over alternatives.
+                            let default = attr(&attrs, "DEFAULT") == Some("YES");
+                            variants.push(Variant {
+                                uri: resolve(uri)?,
+                                bandwidth: u64::from(default),
+                                audio_only: true,
+                            });
+                        }
+                    }
+                },
+                "#EXT-X-TARGETDURATION" => {
+                    let secs = value
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|s| s.is_finite() && *s > 0.0)
+                        .ok_or_else(|| fail("bad target duration"))?;
+                    media.target_duration = Duration::from_secs_f64(secs);
+                },
+                "#EXT-X-MEDIA-SEQUENCE" => {
+                    media.media_sequence = value.parse().map_err(|_| fail("bad media sequence"))?;
+                },
+                "#EXT-X-KEY" => {
+                    let attrs = parse_attributes(value);
+                    if attr(&attrs, "METHOD") != Some("NONE") {
+                        return Err(AudioStreamError::Fail(
+                            "encrypted HLS streams are not supported".into(),
+                        ));
+                    }
+                },
+                "#EXT-X-MAP" => {
+                    let attrs = parse_attributes(value);
+                    let uri = attr(&attrs, "URI").ok_or_else(|| fail("#EXT-X-MAP without URI"))?;
+                    let range = attr(&attrs, "BYTERANGE")
+                        .map(|r| ByteRange::parse(r, 0).ok_or_else(|| fail("bad byte range")))
+                        .transpose()?;
+
+                    map = Some(MediaMap {
+                        uri: resolve(uri)?,
+                        range,
+                    });
+                },
+                "#EXT-X-BYTERANGE" => {
+                    pending_range = Some(value);
+                },
+                "#EXT-X-ENDLIST" => {
+                    media.ended = true;
+                },
+                _ if tag.starts_with('#') => {},
+                _ => {
+                    let uri = resolve(line)?;
+
+                    if let Some((bandwidth, audio_only)) = pending_variant.take() {
+                        variants.push(Variant {
+                            uri,
+                            bandwidth,
+                            audio_only,
+                        });
+                        continue;
+                    }
+
+                    // Byte ranges without an offset follow on from the previous
+                    // range of the same resource.
+                    let range = pending_range
+                        .take()
+                        .map(|r| {
+                            let default_offset = match &last_range {
+                                Some((last_uri, last)) if *last_uri == uri =>
+                                    last.offset + last.len,
+                                _ => 0,
+                            };
+                            ByteRange::parse(r, default_offset)
+                                .ok_or_else(|| fail("bad byte range"))
+                        })
+                        .transpose()?;
+                    last_range = range.map(|r| (uri.clone(), r));
+
+                    media.segments.push(Segment {
+                        uri,
+                        range,
+                        map: map.clone(),
+                        sequence: media.media_sequence + media.segments.len() as u64,
+                    });
+                },
+            }
+        }
+
+        if variants.is_empty() {
+            Ok(Self::Media(media))
+        } else {
+            Ok(Self::Master(variants))
+        }
+    }
+}
+
+/// Splits an HLS attribute list into key-value pairs, removing quotes.
+fn parse_attributes(list: &str) -> Vec<(&str, &str)> {
+    let mut out = vec![];
+    let mut rest = list;
+
+    while let Some((key, tail)) = rest.split_once('=') {
+        let (value, tail) = if let Some(quoted) = tail.strip_prefix('"') {
+            let end = quoted.find('"').unwrap_or(quoted.len());
+            let after = quoted.get(end + 1..).unwrap_or("");
+            (&quoted[..end], after.split_once(',').map_or("", |(_, t)| t))
+        } else {
+            tail.split_once(',').unwrap_or((tail, ""))
+        };
+
+        out.push((key.trim(), value));
+        rest = tail;
+    }
+
+    out
+}
+
+fn attr<'a>(attrs: &[(&str, &'a str)], key: &str) -> Option<&'a str> {
+    attrs.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::test_data::FILE_SHORT_MP3_TARGET,
+        input::input_tests::*,
+        test_utils::serve_http,
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    /// Serves each request with the body returned by `route` for its path, or a 404.
+    async fn serve(route: impl Fn(&str) -> Option<Vec<u8>> + Send + Sync + 'static) -> String {
+        let (addr, _requests) = serve_http(move |req| {
+            let path = req.split_whitespace().nth(1).unwrap_or("/");
+            let (status, body) = match route(path) {
+                Some(body) => ("200 OK", body),
+                None => ("404 Not Found", vec![]),
+            };
+
+            let mut out = format!(
+                "HTTP/1.1 {status}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                body.len()
+            )
+            .into_bytes();
+            out.extend_from_slice(&body);
+            out
+        })
+        .await;
+
+        format!("http://{addr}")
+    }
+
+    async fn read_all(mut hls: HlsInput) -> IoResult<Vec<u8>> {
+        let mut stream = hls.create_async().await.unwrap().input;
+
+        tokio::task::spawn_blocking(move || {
+            let mut out = vec![];
+            std::io::Read::read_to_end(&mut stream, &mut out).map(|_| out)
+        })
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn master_playlist_prefers_audio_renditions() {
+        let base = Url::parse("http://example.com/live/master.m3u8").unwrap();
+        let text = "#EXTM3U
+#EXT-X-STREAM-INF:BANDWIDTH=800000,CODECS=\"avc1.4d401e,mp4a.40.2\"
+video/low.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=128000,CODECS=\"mp4a.40.2\"
+audio/hi.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=64000,CODECS=\"mp4a.40.5\"
+audio/lo.m3u8
+";
+
+        let Ok(Playlist::Master(variants)) = Playlist::parse(text, &base) else {
+            panic!("Expected a master playlist.");
+        };
+        assert_eq!(variants.len(), 3);
+        assert_eq!(
+            Variant::choose(variants).unwrap().uri.as_str(),
+            "http://example.com/live/audio/hi.m3u8"
+        );
+
+        let video_only = "#EXTM3U
+#EXT-X-STREAM-INF:BANDWIDTH=800000
+high.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=200000
+low.m3u8
+";
+        let Ok(Playlist::Master(variants)) = Playlist::parse(video_only, &base) else {
+            panic!("Expected a master playlist.");
+        };
+        assert_eq!(
+            Variant::choose(variants).unwrap().uri.as_str(),
+            "http://example.com/live/low.m3u8"
+        );
+    }
+
+    #[test]
+    fn live_reloads_are_rate_limited() {
+        let base = Url::parse("http://example.com/a/index.m3u8").unwrap();
+
+        for duration in ["0", "-1", "0.0"] {
+            let text = format!("#EXTM3U\n#EXT-X-TARGETDURATION:{duration}\n");
+            assert!(Playlist::parse(&text, &base).is_err());
+        }
+
+        let Ok(Playlist::Media(untagged)) = Playlist::parse("#EXTM3U\nseg.ts\n", &base) else {
+            panic!("Expected a media playlist.");
+        };
+        assert_eq!(untagged.reload_wait(true), MIN_RELOAD_INTERVAL);
+        assert_eq!(untagged.reload_wait(false), MIN_RELOAD_INTERVAL);
+
+        let text = "#EXTM3U\n#EXT-X-TARGETDURATION:1.5\nseg.ts\n";
+        let Ok(Playlist::Media(short)) = Playlist::parse(text, &base) else {
+            panic!("Expected a media playlist.");
+        };
+        assert_eq!(short.reload_wait(true), Duration::from_millis(1500));
+        assert_eq!(short.reload_wait(false), MIN_RELOAD_INTERVAL);
+    }
+
+    #[test]
+    fn media_playlist_tracks_sequence_maps_and_ranges() {
+        let base = Url::parse("http://example.com/a/index.m3u8").unwrap();
+        let text = "#EXTM3U
+#EXT-X-TARGETDURATION:6
+#EXT-X-MEDIA-SEQUENCE:40
+#EXT-X-MAP:URI=\"init.mp4\",BYTERANGE=\"720@0\"
+#EXTINF:6.0,
+#EXT-X-BYTERANGE:1000@720
+media.mp4
+#EXTINF:6.0,
+#EXT-X-BYTERANGE:500
+media.mp4
+#EXT-X-DISCONTINUITY
+#EXTINF:5.5,
+/other/seg.m4s
+#EXT-X-ENDLIST
+";
+
+        let Ok(Playlist::Media(media)) = Playlist::parse(text, &base) else {
+            panic!("Expected a media playlist.");
+        };
+
+        assert!(media.ended);
+        assert_eq!(media.target_duration, Duration::from_secs(6));
+        assert_eq!(
+            media
+                .segments
+                .iter()
+                .map(|s| s.sequence)
+                .collect::<Vec<_>>(),
+            vec![40, 41, 42]
+        );
+        assert_eq!(
+            media.segments[0].map.as_ref().unwrap().range,
+            Some(ByteRange {
+                len: 720,
+                offset: 0
+            })
+        );
+        assert_eq!(
+            media.segments[0].range,
+            Some(ByteRange {
+                len: 1000,
+                offset: 720
+            })
+        );
+        assert_eq!(
+            media.segments[1].range,
+            Some(ByteRange {
+                len: 500,
+                offset: 1720
+            })
+        );
+        assert_eq!(
+            media.segments[2].uri.as_str(),
+            "http://example.com/other/seg.m4s"
+        );
+
+        let encrypted = "#EXTM3U\n#EXT-X-KEY:METHOD=AES-128,URI=\"key\"\nseg.aac\n";
+        assert!(Playlist::parse(encrypted, &base).is_err());
+        assert!(Playlist::parse("seg.aac\n", &base).is_err());
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn hls_track_plays() {
+        let audio = std::fs::read(FILE_SHORT_MP3_TARGET).unwrap();
+        let (first, second) = audio.split_at(audio.len() / 2);
+        let (first, second) = (first.to_vec(), second.to_vec());
+
+        let url = serve(move |path| match path {
+            "/index.m3u8" => Some(
+                b"#EXTM3U\n#EXT-X-TARGETDURATION:1\n#EXTINF:1,\n0.mp3\n#EXTINF:1,\n1.mp3\n\
+                  #EXT-X-ENDLIST\n"
+                    .to_vec(),
+            ),
+            "/0.mp3" => Some(first.clone()),
+            "/1.mp3" => Some(second.clone()),
+            _ => None,
+        })
+        .await;
+
+        track_plays_mixed(|| HlsInput::new(Client::new(), format!("{url}/index.m3u8"))).await;
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn failed_segments_are_skipped_or_abort() {
+        let url = serve(|path| match path {
+            "/index.m3u8" => Some(
+                b"#EXTM3U\n#EXT-X-TARGETDURATION:1\n#EXTINF:1,\na.aac\n#EXTINF:1,\n\
+                  missing.aac\n#EXTINF:1,\nb.aac\n#EXT-X-ENDLIST\n"
+                    .to_vec(),
+            ),
+            "/a.aac" => Some(b"first".to_vec()),
+            "/b.aac" => Some(b"second".to_vec()),
+            _ => None,
+        })
+        .await;
+        let playlist = format!("{url}/index.m3u8");
+
+        let hls = HlsInput::new(Client::new(), playlist.clone());
+        assert_eq!(read_all(hls).await.unwrap(), b"firstsecond");
+
+        let hls = HlsInput::new(Client::new(), playlist).skip_failed_segments(false);
+        assert!(read_all(hls).await.is_err());
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn live_playlists_are_reloaded_until_ended() {
+        let reloads = Arc::new(AtomicUsize::new(0));
+        let reloads_2 = reloads.clone();
+
+        let url = serve(move |path| match path {
+            "/live.m3u8" => Some(if reloads_2.fetch_add(1, Ordering::SeqCst) == 0 {
+                b"#EXTM3U\n#EXT-X-TARGETDURATION:1\n#EXT-X-MEDIA-SEQUENCE:7\n\
+                  #EXTINF:1,\n7.aac\n#EXTINF:1,\n8.aac\n"
+                    .to_vec()
+            } else {
+                b"#EXTM3U\n#EXT-X-TARGETDURATION:1\n#EXT-X-MEDIA-SEQUENCE:8\n\
+                  #EXTINF:1,\n8.aac\n#EXTINF:1,\n9.aac\n#EXT-X-ENDLIST\n"
+                    .to_vec()
+            }),
+            "/7.aac" => Some(b"7".to_vec()),
+            "/8.aac" => Some(b"8".to_vec()),
+            "/9.aac" => Some(b"9".to_vec()),
+            _ => None,
+        })
+        .await;
+
+        let hls = HlsInput::new(Client::new(), format!("{url}/live.m3u8"));
+        assert_eq!(read_all(hls).await.unwrap(), b"789");
+        assert_eq!(reloads.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn only_ended_playlists_can_seek() {
+        let url = serve(|path| match path {
+            "/vod.m3u8" => Some(
+                b"#EXTM3U\n#EXT-X-TARGETDURATION:1\n#EXTINF:1,\n0.aac\n#EXT-X-ENDLIST\n".to_vec(),
+            ),
+            "/live.m3u8" => Some(b"#EXTM3U\n#EXT-X-TARGETDURATION:1\n#EXTINF:1,\n0.aac\n".to_vec()),
+            _ => None,
+        })
+        .await;
+
+        let mut vod = HlsInput::new(Client::new(), format!("{url}/vod.m3u8"));
+        assert_eq!(vod.seek_support().await, SeekSupport::ForwardOnly);
+
+        let mut live = HlsInput::new(Client::new(), format!("{url}/live.m3u8"));
+        assert_eq!(live.seek_support().await, SeekSupport::None);
+
+        let mut missing = HlsInput::new(Client::new(), format!("{url}/missing.m3u8"));
+        assert_eq!(missing.seek_support().await, SeekSupport::None);
+    }
+}
@@ -5,22 +5,39 @@ use crate::input::{
     AudioStreamError,
     Compose,
     Input,
+    SeekSupport,
 };
 use async_trait::async_trait;
+use flume::Sender;
 use futures::TryStreamExt;
 use pin_project::pin_project;
 use reqwest::{
-    header::{HeaderMap, ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_TYPE, RANGE, RETRY_AFTER},
+    header::{
+        HeaderMap,
+        HeaderName,
+        HeaderValue,
+        ACCEPT_RANGES,
+        CONTENT_LENGTH,
+        CONTENT_RANGE,
+        CONTENT_TYPE,
+        RANGE,
+        RETRY_AFTER,
+    },
     Client,
+    StatusCode,
 };
 use std::{
+    future::Future,
     io::{Error as IoError, ErrorKind as IoErrorKind, Result as IoResult, SeekFrom},
     pin::Pin,
-    task::{Context, Poll},
+    task::{ready, Context, Poll},
     time::Duration,
 };
 use symphonia_core::{io::MediaSource, probe::Hint};
-use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncSeek, ReadBuf},
+    task::JoinHandle,
+};
 use tokio_util::io::StreamReader;
 
 /// A lazily instantiated HTTP request.
@@ -31,6 +48,10 @@ pub struct HttpRequest {
     /// The target URL of the required resource.
     pub request: String,
     /// HTTP header fields to add to any created requests.
+    ///
+    /// These are sent with the initial request, and with any range requests made
+    /// to resume the stream after a seek. Authorization headers are removed by
+    /// `reqwest` if the server redirects to a different host.
     pub headers: HeaderMap,
     /// Content length, used as an upper bound in range requests if known.
     ///
@@ -38,6 +59,26 @@ pub struct HttpRequest {
     /// `range: bytes=0-1023` instead of the simpler `range: bytes=0-` (such as
     /// Youtube).
     pub content_length: Option<u64>,
+    /// Destination for stream titles sent by internet radio servers as ICY
+    /// (Shoutcast) metadata.
+    ///
+    /// If set, requests ask the server to include ICY metadata. Metadata blocks are
+    /// removed from the audio stream, and each new `StreamTitle` is sent on this channel.
+    /// Streams carrying ICY metadata cannot be resumed after a seek.
+    ///
+    /// Defaults to `None`, where ICY metadata is not requested.
+    pub icy_titles: Option<Sender<String>>,
+    /// Time to wait for more data on reaching the end of a resource which is still
+    /// being written, such as a live `WebM` recording.
+    ///
+    /// If set, the end of each response is followed by range requests for any newly
+    /// written data, until none arrives within this timeout. This requires that
+    /// the server supports range requests, and that [`content_length`] is unset.
+    ///
+    /// Defaults to `None`, where the resource ends with its first response.
+    ///
+    /// [`content_length`]: Self::content_length
+    pub growth_timeout: Option<Duration>,
 }
 
 impl HttpRequest {
@@ -55,15 +96,64 @@ impl HttpRequest {
             request,
             headers,
             content_length: None,
+            icy_titles: None,
+            growth_timeout: None,
         }
     }
 
+    #[must_use]
+    /// Adds an HTTP header field to any created requests, replacing any existing
+    /// values for `key`.
+    ///
+    /// Credentials (such as an `Authorization` header) should be marked using
+    /// [`HeaderValue::set_sensitive`].
+    pub fn header(mut self, key: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(key, value);
+        self
+    }
+
+    #[must_use]
+    /// Adds several HTTP header fields to any created requests, replacing any existing
+    /// values for their keys.
+    pub fn headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    #[must_use]
+    /// Requests ICY metadata from internet radio streams, sending each new stream
+    /// title to `tx`.
+    ///
+    /// See [`icy_titles`] for details.
+    ///
+    /// [`icy_titles`]: Self::icy_titles
+    pub fn icy_titles(mut self, tx: Sender<String>) -> Self {
+        self.icy_titles = Some(tx);
+        self
+    }
+
+    #[must_use]
+    /// Treats the target resource as one which is still being written, waiting up to
+    /// `timeout` for more data whenever its current end is reached.
+    ///
+    /// See [`growth_timeout`] for details.
+    ///
+    /// [`growth_timeout`]: Self::growth_timeout
+    pub fn growing(mut self, timeout: Duration) -> Self {
+        self.growth_timeout = Some(timeout);
+        self
+    }
+
     async fn create_stream(
         &mut self,
         offset: Option<u64>,
     ) -> Result<(HttpStream, Option<Hint>), AudioStreamError> {
         let mut resp = self.client.get(&self.request).headers(self.headers.clone());
 
+        if self.icy_titles.is_some() {
+            resp = resp.header(ICY_METADATA, HeaderValue::from_static("1"));
+        }
+
         match (offset, self.content_length) {
             (Some(offset), None) => {
                 resp = resp.header(RANGE, format!("bytes={offset}-"));
@@ -82,6 +172,13 @@ impl HttpRequest {
             .await
             .map_err(|e| AudioStreamError::Fail(Box::new(e)))?;
 
+        if matches!(
+            resp.status(),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+        ) {
+            return Err(AudioStreamError::Unauthorized);
+        }
+
         if !resp.status().is_success() {
             let msg: Box<dyn std::error::Error + Send + Sync + 'static> =
                 format!("failed with http status code: {}", resp.status()).into();
@@ -115,13 +212,39 @@ impl HttpRequest {
                     out
                 });
 
-            let len = headers
+            let content_len: Option<u64> = headers
                 .get(CONTENT_LENGTH)
                 .and_then(|val| val.to_str().ok())
                 .and_then(|val| val.parse().ok());
 
+            // Servers which ignore a range request send the whole resource instead.
+            let offset = offset.unwrap_or(0);
+            let ranged = resp.status() == StatusCode::PARTIAL_CONTENT;
+            let len = if ranged {
+                headers
+                    .get(CONTENT_RANGE)
+                    .and_then(|val| val.to_str().ok())
+                    .and_then(|val| val.rsplit_once('/'))
+                    .and_then(|(_, total)| total.parse().ok())
+                    .or_else(|| content_len.map(|len| len + offset))
+            } else {
+                content_len
+            };
+
+            let icy = self.icy_titles.clone().and_then(|tx| {
+                headers
+                    .get(ICY_METAINT)
+                    .and_then(|val| val.to_str().ok())
+                    .and_then(|val| val.parse().ok())
+                    .filter(|&metaint| metaint > 0)
+                    .map(|metaint| (metaint, tx))
+            });
+
+            // Byte offsets into ICY streams also count metadata blocks, so they
+            // can't be mapped onto range requests.
             let resume = headers
                 .get(ACCEPT_RANGES)
+                .filter(|_| icy.is_none())
                 .and_then(|a| a.to_str().ok())
                 .and_then(|a| {
                     if a == "bytes" {
@@ -131,15 +254,35 @@ impl HttpRequest {
                     }
                 });
 
-            let stream = Box::new(StreamReader::new(
+            let mut stream: Box<dyn AsyncRead + Send + Sync + Unpin> = Box::new(StreamReader::new(
                 resp.bytes_stream()
                     .map_err(|e| IoError::new(IoErrorKind::Other, e)),
             ));
 
+            if let Some((metaint, tx)) = icy {
+                stream = Box::new(IcyStream::new(stream, metaint, tx));
+            }
+
+            if offset != 0 && !ranged {
+                // Fall back to reading from the start of the resource.
+                let skipped =
+                    tokio::io::copy(&mut (&mut stream).take(offset), &mut tokio::io::sink())
+                        .await
+                        .map_err(|e| AudioStreamError::Fail(Box::new(e)))?;
+
+                if skipped != offset {
+                    let msg: Box<dyn std::error::Error + Send + Sync + 'static> =
+                        "Resource ended before the requested offset.".into();
+                    return Err(AudioStreamError::Fail(msg));
+                }
+            }
+
             let input = HttpStream {
                 stream,
                 len,
+                pos: offset,
                 resume,
+                seek: None,
             };
 
             Ok((input, hint))
@@ -147,12 +290,129 @@ impl HttpRequest {
     }
 }
 
+const ICY_METADATA: HeaderName = HeaderName::from_static("icy-metadata");
+const ICY_METAINT: HeaderName = HeaderName::from_static("icy-metaint");
+
+/// Removes interleaved ICY metadata blocks from an audio stream.
+///
+/// Every `metaint` bytes of audio are followed by a single length byte `n`,
+/// and then `16 * n` bytes of metadata.
+struct IcyStream {
+    stream: Box<dyn AsyncRead + Send + Sync + Unpin>,
+    metaint: usize,
+    state: IcyState,
+    scratch: Vec<u8>,
+    tx: Sender<String>,
+}
+
+enum IcyState {
+    Audio(usize),
+    Length,
+    Metadata(usize),
+}
+
+impl IcyStream {
+    fn new(
+        stream: Box<dyn AsyncRead + Send + Sync + Unpin>,
+        metaint: usize,
+        tx: Sender<String>,
+    ) -> Self {
+        Self {
+            stream,
+            metaint,
+            state: IcyState::Audio(metaint),
+            scratch: Vec::new(),
+            tx,
+        }
+    }
+
+    fn parse_title(metadata: &[u8]) -> Option<String> {
+        const TITLE_START: &str = "StreamTitle='";
+
+        let metadata = String::from_utf8_lossy(metadata);
+        let metadata = metadata.trim_end_matches('\0');
+        let start = metadata.find(TITLE_START)? + TITLE_START.len();
+        let len = metadata[start..]
+            .find("';")
+            .unwrap_or(metadata[start..].len());
+
+        Some(metadata[start..][..len].to_string())
+    }
+}
+
+impl AsyncRead for IcyStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<IoResult<()>> {
+        let this = self.get_mut();
+
+        loop {
+            match this.state {
+                IcyState::Audio(0) => this.state = IcyState::Length,
+                IcyState::Audio(remaining) => {
+                    // `take` hides the read from `buf`, so it must be copied back in.
+                    let mut limited = buf.take(remaining);
+                    ready!(Pin::new(&mut this.stream).poll_read(cx, &mut limited))?;
+
+                    this.scratch.clear();
+                    this.scratch.extend_from_slice(limited.filled());
+                    buf.put_slice(&this.scratch);
+
+                    this.state = IcyState::Audio(remaining - this.scratch.len());
+                    return Poll::Ready(Ok(()));
+                },
+                IcyState::Length => {
+                    let mut len = [0u8; 1];
+                    let mut len_buf = ReadBuf::new(&mut len);
+                    ready!(Pin::new(&mut this.stream).poll_read(cx, &mut len_buf))?;
+
+                    if len_buf.filled().is_empty() {
+                        return Poll::Ready(Ok(()));
+                    }
+
+                    this.scratch.clear();
+                    this.state = match usize::from(len[0]) * 16 {
+                        0 => IcyState::Audio(this.metaint),
+                        n => IcyState::Metadata(n),
+                    };
+                },
+                IcyState::Metadata(total) => {
+                    let start = this.scratch.len();
+                    this.scratch.resize(total, 0);
+                    let mut meta_buf = ReadBuf::new(&mut this.scratch[start..]);
+                    let polled = Pin::new(&mut this.stream).poll_read(cx, &mut meta_buf);
+                    let read = meta_buf.filled().len();
+                    this.scratch.truncate(start + read);
+                    ready!(polled)?;
+
+                    if read == 0 {
+                        return Poll::Ready(Err(IoErrorKind::UnexpectedEof.into()));
+                    }
+
+                    if this.scratch.len() == total {
+                        if let Some(title) = Self::parse_title(&this.scratch) {
+                            drop(this.tx.send(title));
+                        }
+                        this.state = IcyState::Audio(this.metaint);
+                    }
+                },
+            }
+        }
+    }
+}
+
 #[pin_project]
 struct HttpStream {
     #[pin]
     stream: Box<dyn AsyncRead + Send + Sync + Unpin>,
     len: Option<u64>,
+    /// Offset of the next byte to be read from `stream`.
+    pos: u64,
     resume: Option<HttpRequest>,
+    /// A range request made to complete a seek.
+    seek: Option<JoinHandle<Result<HttpStream, AudioStreamError>>>,
 }
 
 impl AsyncRead for HttpStream {
@@ -161,24 +421,67 @@ impl AsyncRead for HttpStream {
         cx: &mut Context<'_>,
         buf: &mut ReadBuf<'_>,
     ) -> Poll<IoResult<()>> {
-        AsyncRead::poll_read(self.project().stream, cx, buf)
+        let this = self.project();
+        let start = buf.filled().len();
+        ready!(AsyncRead::poll_read(this.stream, cx, buf))?;
+        *this.pos += (buf.filled().len() - start) as u64;
+
+        Poll::Ready(Ok(()))
     }
 }
 
 impl AsyncSeek for HttpStream {
-    fn start_seek(self: Pin<&mut Self>, _position: SeekFrom) -> IoResult<()> {
-        Err(IoErrorKind::Unsupported.into())
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> IoResult<()> {
+        let this = self.get_mut();
+        let Some(mut resume) = this.resume.clone() else {
+            return Err(IoErrorKind::Unsupported.into());
+        };
+
+        let target = match position {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::Current(delta) => this.pos.checked_add_signed(delta),
+            SeekFrom::End(delta) => this.len.and_then(|len| len.checked_add_signed(delta)),
+        }
+        .ok_or_else(|| {
+            IoError::new(
+                IoErrorKind::InvalidInput,
+                "Invalid seek position, or unknown stream length.",
+            )
+        })?;
+
+        if target != this.pos {
+            this.seek = Some(tokio::spawn(async move {
+                resume.create_stream(Some(target)).await.map(|(s, _)| s)
+            }));
+        }
+
+        Ok(())
     }
 
-    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<IoResult<u64>> {
-        unreachable!()
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<u64>> {
+        let this = self.get_mut();
+        let Some(seek) = &mut this.seek else {
+            return Poll::Ready(Ok(this.pos));
+        };
+
+        let res = ready!(Pin::new(seek).poll(cx));
+        this.seek = None;
+
+        match res {
+            Ok(Ok(new_stream)) => {
+                *this = new_stream;
+                Poll::Ready(Ok(this.pos))
+            },
+            Ok(Err(e)) => Poll::Ready(Err(IoError::other(e))),
+            Err(e) => Poll::Ready(Err(IoError::other(e))),
+        }
     }
 }
 
 #[async_trait]
 impl AsyncMediaSource for HttpStream {
     fn is_seekable(&self) -> bool {
-        false
+        self.resume.is_some()
     }
 
     async fn byte_len(&self) -> Option<u64> {
@@ -209,8 +512,14 @@ impl Compose for HttpRequest {
     async fn create_async(
         &mut self,
     ) -> Result<AudioStream<Box<dyn MediaSource>>, AudioStreamError> {
-        self.create_stream(None).await.map(|(input, hint)| {
-            let stream = AsyncAdapterStream::new(Box::new(input), 64 * 1024);
+        self.create_stream(None).await.map(|(mut input, hint)| {
+            let stream = if let Some(timeout) = self.growth_timeout {
+                // The resource's current length is not its final length.
+                input.len = None;
+                AsyncAdapterStream::new_growing(Box::new(input), 64 * 1024, timeout)
+            } else {
+                AsyncAdapterStream::new(Box::new(input), 64 * 1024)
+            };
 
             AudioStream {
                 input: Box::new(stream) as Box<dyn MediaSource>,
@@ -222,6 +531,35 @@ impl Compose for HttpRequest {
     fn should_create_async(&self) -> bool {
         true
     }
+
+    async fn seek_support(&mut self) -> SeekSupport {
+        let Ok(resp) = self
+            .client
+            .head(&self.request)
+            .headers(self.headers.clone())
+            .send()
+            .await
+        else {
+            return SeekSupport::None;
+        };
+
+        if !resp.status().is_success() {
+            return SeekSupport::None;
+        }
+
+        // Range requests are needed to resume a stream after a seek, and are
+        // never made for ICY streams.
+        let ranges = resp
+            .headers()
+            .get(ACCEPT_RANGES)
+            .is_some_and(|a| a.as_bytes() == b"bytes");
+
+        if ranges && self.icy_titles.is_none() {
+            SeekSupport::Full
+        } else {
+            SeekSupport::ForwardOnly
+        }
+    }
 }
 
 impl From<HttpRequest> for Input {
@@ -238,7 +576,9 @@ mod tests {
     use crate::{
         constants::test_data::{HTTP_OPUS_TARGET, HTTP_TARGET, HTTP_WEBM_TARGET},
         input::input_tests::*,
+        test_utils::serve_http,
     };
+    use tokio::io::AsyncReadExt;
 
     #[tokio::test]
     #[ntest::timeout(10_000)]
@@ -295,4 +635,132 @@ mod tests {
     async fn http_webm_backward_seek_correct() {
         backward_seek_correct(|| HttpRequest::new(Client::new(), HTTP_WEBM_TARGET.into())).await;
     }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn http_denied_request_is_unauthorized() {
+        let (addr, requests) =
+            serve_http(|_| b"HTTP/1.1 401 Unauthorized\r\ncontent-length: 0\r\n\r\n".to_vec())
+                .await;
+
+        let mut req = HttpRequest::new(Client::new(), format!("http://{addr}/")).header(
+            reqwest::header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer abc123"),
+        );
+
+        assert!(matches!(
+            req.create_async().await,
+            Err(AudioStreamError::Unauthorized)
+        ));
+        assert!(requests
+            .recv_async()
+            .await
+            .unwrap()
+            .to_lowercase()
+            .contains("authorization: bearer abc123"));
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn http_icy_metadata_is_stripped() {
+        let (addr, requests) = serve_http(|_| {
+            let mut meta = b"StreamTitle='Artist - Song';".to_vec();
+            meta.resize(32, 0);
+            let mut body = b"abcd".to_vec();
+            body.push(2);
+            body.extend_from_slice(&meta);
+            body.extend_from_slice(b"efgh\0ij");
+
+            let mut out = format!(
+                "HTTP/1.1 200 OK\r\nicy-metaint: 4\r\ncontent-length: {}\r\n\r\n",
+                body.len()
+            )
+            .into_bytes();
+            out.extend_from_slice(&body);
+            out
+        })
+        .await;
+
+        let (tx, rx) = flume::unbounded();
+        let mut req = HttpRequest::new(Client::new(), format!("http://{addr}/")).icy_titles(tx);
+        let (mut stream, _) = req.create_stream(None).await.unwrap();
+
+        let mut audio = vec![];
+        stream.read_to_end(&mut audio).await.unwrap();
+
+        assert_eq!(audio, b"abcdefghij");
+        assert_eq!(rx.drain().collect::<Vec<_>>(), vec!["Artist - Song"]);
+        assert!(stream.resume.is_none());
+        assert!(requests
+            .recv_async()
+            .await
+            .unwrap()
+            .to_lowercase()
+            .contains("icy-metadata: 1"));
+    }
+
+    async fn seek_against_server(honour_ranges: bool) {
+        use tokio::io::AsyncSeekExt;
+
+        let body: Vec<u8> = (0..64).collect();
+
+        let server_body = body.clone();
+        let (addr, _requests) = serve_http(move |req| {
+            let start = req
+                .to_lowercase()
+                .lines()
+                .find_map(|l| l.strip_prefix("range: bytes=").map(str::to_owned))
+                .and_then(|r| r.split('-').next()?.parse::<usize>().ok())
+                .filter(|_| honour_ranges);
+
+            let head = if let Some(start) = start {
+                format!(
+                    "HTTP/1.1 206 Partial Content\r\naccept-ranges: bytes\r\ncontent-range: bytes {}-{}/{}\r\ncontent-length: {}\r\n\r\n",
+                    start,
+                    server_body.len() - 1,
+                    server_body.len(),
+                    server_body.len() - start,
+                )
+            } else {
+                format!(
+                    "HTTP/1.1 200 OK\r\naccept-ranges: bytes\r\ncontent-length: {}\r\n\r\n",
+                    server_body.len()
+                )
+            };
+
+            let mut out = head.into_bytes();
+            out.extend_from_slice(&server_body[start.unwrap_or(0)..]);
+            out
+        })
+        .await;
+
+        let mut req = HttpRequest::new(Client::new(), format!("http://{addr}/"));
+        let (mut stream, _) = req.create_stream(None).await.unwrap();
+        assert!(stream.is_seekable());
+
+        let mut head = [0u8; 4];
+        stream.read_exact(&mut head).await.unwrap();
+        assert_eq!(head, body[..4]);
+
+        assert_eq!(stream.seek(SeekFrom::Start(40)).await.unwrap(), 40);
+        let mut rest = vec![];
+        stream.read_to_end(&mut rest).await.unwrap();
+        assert_eq!(rest, body[40..]);
+
+        assert_eq!(stream.seek(SeekFrom::End(-8)).await.unwrap(), 56);
+        assert_eq!(stream.seek(SeekFrom::Current(-6)).await.unwrap(), 50);
+        let mut rest = vec![];
+        stream.read_to_end(&mut rest).await.unwrap();
+        assert_eq!(rest, body[50..]);
+    }
+
+    #[tokio::test]
+    async fn http_seek_uses_range_requests() {
+        seek_against_server(true).await;
+    }
+
+    #[tokio::test]
+    async fn http_seek_falls_back_when_ranges_are_ignored() {
+        seek_against_server(false).await;
+    }
 }
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// A shared cap on the number of child processes which lazy sources may run at once.
+///
+/// Clones of a `ProcessLimit` share the same pool of permits. Sources which would exceed
+/// the limit wait until another process exits, rather than failing. A permit is returned
+/// once its process exits, whether or not it succeeded, or if the waiting source is dropped.
+///
+/// ```
+/// use reqwest::Client;
+/// use songbird::input::{ProcessLimit, YoutubeDl};
+///
+/// let client = Client::new();
+/// let limit = ProcessLimit::new(4);
+///
+/// let ytdl = YoutubeDl::new(client, "https://www.youtube.com/watch?v=dQw4w9WgXcQ".into())
+///     .process_limit(limit.clone());
+/// ```
+#[derive(Clone, Debug)]
+pub struct ProcessLimit(Arc<Semaphore>);
+
+impl ProcessLimit {
+    /// Creates a limit allowing at most `max_processes` concurrent child processes.
+    ///
+    /// # Panics
+    /// Panics if `max_processes` is `0`, or exceeds [`Semaphore::MAX_PERMITS`].
+    ///
+    /// [`Semaphore::MAX_PERMITS`]: tokio::sync::Semaphore::MAX_PERMITS
+    #[must_use]
+    pub fn new(max_processes: usize) -> Self {
+        assert!(
+            max_processes > 0,
+            "A process limit must allow at least one process."
+        );
+        Self(Arc::new(Semaphore::new(max_processes)))
+    }
+
+    /// Returns the number of processes which may currently be started without waiting.
+    #[must_use]
+    pub fn available(&self) -> usize {
+        self.0.available_permits()
+    }
+
+    pub(crate) async fn acquire(&self) -> SemaphorePermit<'_> {
+        self.0
+            .acquire()
+            .await
+            .expect("Process limit semaphores are never closed.")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::test_data::YTDL_TARGET,
+        input::{Compose, YoutubeDl},
+    };
+    use reqwest::Client;
+    use std::time::Duration;
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn process_limit_queues_and_releases() {
+        let limit = ProcessLimit::new(1);
+        let held = limit.acquire().await;
+
+        let mut ytdl = YoutubeDl::new_ytdl_like("yt-dlq", Client::new(), YTDL_TARGET.into())
+            .process_limit(limit.clone());
+        let task = tokio::spawn(async move { ytdl.aux_metadata().await });
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!task.is_finished());
+
+        drop(held);
+        assert!(task.await.unwrap().is_err());
+        assert_eq!(limit.available(), 1);
+    }
+}
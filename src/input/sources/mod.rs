@@ -1,5 +1,7 @@
 mod file;
+mod hls;
 mod http;
+mod limit;
 mod ytdl;
 
-pub use self::{file::*, http::*, ytdl::*};
+pub use self::{file::*, hls::*, http::*, limit::*, ytdl::*};
@@ -1,18 +1,21 @@
 use crate::input::{
     metadata::ytdl::Output,
+    AsyncChildContainer,
     AudioStream,
     AudioStreamError,
     AuxMetadata,
     Compose,
     HttpRequest,
     Input,
+    ProcessLimit,
+    SeekSupport,
 };
 use async_trait::async_trait;
 use reqwest::{
     header::{HeaderMap, HeaderName, HeaderValue},
     Client,
 };
-use std::{error::Error, io::ErrorKind};
+use std::{borrow::Cow, error::Error, io::ErrorKind, process::Stdio, time::Duration};
 use symphonia_core::io::MediaSource;
 use tokio::process::Command;
 
@@ -33,10 +36,11 @@ enum QueryType {
 /// [`HttpRequest`]: super::HttpRequest
 #[derive(Clone, Debug)]
 pub struct YoutubeDl {
-    program: &'static str,
+    program: Cow<'static, str>,
     client: Client,
     metadata: Option<AuxMetadata>,
     query: QueryType,
+    limit: Option<ProcessLimit>,
 }
 
 impl YoutubeDl {
@@ -53,12 +57,17 @@ impl YoutubeDl {
     ///
     /// [`new`]: Self::new
     #[must_use]
-    pub fn new_ytdl_like(program: &'static str, client: Client, url: String) -> Self {
+    pub fn new_ytdl_like(
+        program: impl Into<Cow<'static, str>>,
+        client: Client,
+        url: String,
+    ) -> Self {
         Self {
-            program,
+            program: program.into(),
             client,
             metadata: None,
             query: QueryType::Url(url),
+            limit: None,
         }
     }
 
@@ -72,15 +81,30 @@ impl YoutubeDl {
     /// Creates a request to search youtube for an optionally specified number of videos matching `query`,
     /// using `program`.
     #[must_use]
-    pub fn new_search_ytdl_like(program: &'static str, client: Client, query: String) -> Self {
+    pub fn new_search_ytdl_like(
+        program: impl Into<Cow<'static, str>>,
+        client: Client,
+        query: String,
+    ) -> Self {
         Self {
-            program,
+            program: program.into(),
             client,
             metadata: None,
             query: QueryType::Search(query),
+            limit: None,
         }
     }
 
+    /// Restricts the number of `program` processes run at once by this and any other
+    /// requests sharing `limit`.
+    ///
+    /// Requests which would exceed the limit wait for a running process to exit.
+    #[must_use]
+    pub fn process_limit(mut self, limit: ProcessLimit) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
     /// Runs a search for the given query, returning a list of up to `n_results`
     /// possible matches which are `AuxMetadata` objects containing a valid URL.
     ///
@@ -103,6 +127,21 @@ impl YoutubeDl {
         })
     }
 
+    /// Returns the metadata of every track at this request's URL, such as all entries in
+    /// a playlist.
+    ///
+    /// Playlists cannot be played directly: creating an [`Input`] from a playlist URL
+    /// fails with [`AudioStreamError::Playlist`]. Each entry's `source_url` should instead
+    /// be played using its own `YoutubeDl`. For single tracks, this returns one result, and
+    /// for searches, this returns up to 5 matches as in [`Self::search`].
+    pub async fn playlist_metadata(&mut self) -> Result<Vec<AuxMetadata>, AudioStreamError> {
+        match self.query(5).await {
+            Ok(out) => Ok(out.iter().map(Output::as_aux_metadata).collect()),
+            Err(AudioStreamError::Playlist(entries)) => Ok(entries),
+            Err(e) => Err(e),
+        }
+    }
+
     async fn query(&mut self, n_results: usize) -> Result<Vec<Output>, AudioStreamError> {
         let new_query;
         let query_str = match &self.query {
@@ -120,10 +159,19 @@ impl YoutubeDl {
             "--no-playlist",
         ];
 
-        let mut output = Command::new(self.program)
+        // Held until the child exits, or this future is dropped (killing the child).
+        let _permit = match &self.limit {
+            Some(limit) => Some(limit.acquire().await),
+            None => None,
+        };
+
+        let child = Command::new(&*self.program)
             .args(ytdl_args)
-            .output()
-            .await
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
             .map_err(|e| {
                 AudioStreamError::Fail(if e.kind() == ErrorKind::NotFound {
                     format!("could not find executable '{}' on path", self.program).into()
@@ -132,7 +180,18 @@ impl YoutubeDl {
                 })
             })?;
 
+        // If resolution is cancelled, this kills and reaps the child.
+        let mut output = AsyncChildContainer::from(child)
+            .output()
+            .await
+            .map_err(|e| AudioStreamError::Fail(Box::new(e)))?;
+
         if !output.status.success() {
+            // Rate limiting is transient, so let the driver retry with its own backoff.
+            if output.stderr.windows(14).any(|w| w == b"HTTP Error 429") {
+                return Err(AudioStreamError::RetryIn(Duration::ZERO));
+            }
+
             return Err(AudioStreamError::Fail(
                 format!(
                     "{} failed with non-zero status code: {}",
@@ -151,6 +210,13 @@ impl YoutubeDl {
             .collect::<Result<Vec<Output>, _>>()
             .map_err(|e| AudioStreamError::Fail(Box::new(e)))?;
 
+        // Only searches may return several results: a URL giving several is a playlist.
+        if matches!(self.query, QueryType::Url(_)) && out.len() > 1 {
+            return Err(AudioStreamError::Playlist(
+                out.iter().map(Output::as_aux_metadata).collect(),
+            ));
+        }
+
         let meta = out
             .first()
             .ok_or_else(|| {
@@ -162,23 +228,9 @@ impl YoutubeDl {
 
         Ok(out)
     }
-}
-
-impl From<YoutubeDl> for Input {
-    fn from(val: YoutubeDl) -> Self {
-        Input::Lazy(Box::new(val))
-    }
-}
 
-#[async_trait]
-impl Compose for YoutubeDl {
-    fn create(&mut self) -> Result<AudioStream<Box<dyn MediaSource>>, AudioStreamError> {
-        Err(AudioStreamError::Unsupported)
-    }
-
-    async fn create_async(
-        &mut self,
-    ) -> Result<AudioStream<Box<dyn MediaSource>>, AudioStreamError> {
+    /// Resolves the first result of this query into a request for its audio stream.
+    async fn resolve(&mut self) -> Result<HttpRequest, AudioStreamError> {
         // panic safety: `query` should have ensured > 0 results if `Ok`
         let mut results = self.query(1).await?;
         let result = results.swap_remove(0);
@@ -194,14 +246,33 @@ impl Compose for YoutubeDl {
             }));
         }
 
-        let mut req = HttpRequest {
+        Ok(HttpRequest {
             client: self.client.clone(),
             request: result.url,
             headers,
             content_length: result.filesize,
-        };
+            icy_titles: None,
+            growth_timeout: None,
+        })
+    }
+}
+
+impl From<YoutubeDl> for Input {
+    fn from(val: YoutubeDl) -> Self {
+        Input::Lazy(Box::new(val))
+    }
+}
 
-        req.create_async().await
+#[async_trait]
+impl Compose for YoutubeDl {
+    fn create(&mut self) -> Result<AudioStream<Box<dyn MediaSource>>, AudioStreamError> {
+        Err(AudioStreamError::Unsupported)
+    }
+
+    async fn create_async(
+        &mut self,
+    ) -> Result<AudioStream<Box<dyn MediaSource>>, AudioStreamError> {
+        self.resolve().await?.create_async().await
     }
 
     fn should_create_async(&self) -> bool {
@@ -221,6 +292,13 @@ impl Compose for YoutubeDl {
             AudioStreamError::Fail(msg)
         })
     }
+
+    async fn seek_support(&mut self) -> SeekSupport {
+        match self.resolve().await {
+            Ok(mut req) => req.seek_support().await,
+            Err(_) => SeekSupport::None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -264,6 +342,92 @@ mod tests {
         assert!(ytdl.aux_metadata().await.is_err());
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    #[ntest::timeout(20_000)]
+    async fn playlist_url_is_reported() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Stand-in for yt-dlp, printing one line of JSON per playlist entry.
+        let path = crate::test_utils::temp_path("fake-ytdl-playlist");
+        std::fs::write(
+            &path,
+            r#"#!/bin/sh
+for i in 1 2 3; do echo "{\"url\": \"$i\", \"title\": \"Track $i\"}"; done
+"#,
+        )
+        .unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let program = path.to_str().unwrap().to_owned();
+
+        let mut ytdl = YoutubeDl::new_ytdl_like(program, Client::new(), YTDL_TARGET.into());
+        assert!(matches!(
+            ytdl.aux_metadata().await,
+            Err(AudioStreamError::Playlist(entries)) if entries.len() == 3
+        ));
+
+        let entries = ytdl.playlist_metadata().await.unwrap();
+        assert_eq!(entries[2].title.as_deref(), Some("Track 3"));
+        _ = std::fs::remove_file(path);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    #[ntest::timeout(20_000)]
+    async fn cancelled_resolution_reaps_process() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Stand-in for yt-dlp which never completes, recording its PID.
+        let pid_path = crate::test_utils::temp_path("fake-ytdl-hang.pid");
+        _ = std::fs::remove_file(&pid_path);
+
+        let path = crate::test_utils::temp_path("fake-ytdl-hang");
+        std::fs::write(
+            &path,
+            format!(
+                "#!/bin/sh\necho $$ > '{}'\nexec sleep 30\n",
+                pid_path.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let program = path.to_str().unwrap().to_owned();
+
+        let mut ytdl = YoutubeDl::new_ytdl_like(program, Client::new(), YTDL_TARGET.into());
+
+        let pid = {
+            let resolve = ytdl.create_async();
+            tokio::pin!(resolve);
+
+            loop {
+                tokio::select! {
+                    _ = &mut resolve => panic!("Resolution should not complete."),
+                    () = tokio::time::sleep(Duration::from_millis(10)) => {
+                        let pid = std::fs::read_to_string(&pid_path).unwrap_or_default();
+                        if let Ok(pid) = pid.trim().parse::<u32>() {
+                            break pid;
+                        }
+                    },
+                }
+            }
+        };
+
+        // The resolution future is now dropped: the child must be killed *and* reaped,
+        // so no zombie process remains.
+        _ = std::fs::remove_file(&path);
+        _ = std::fs::remove_file(&pid_path);
+
+        let proc_path = format!("/proc/{pid}");
+        for _ in 0..200 {
+            if !std::path::Path::new(&proc_path).exists() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        panic!("Child process {pid} was not reaped.");
+    }
+
     #[tokio::test]
     #[ntest::timeout(20_000)]
     async fn ytdl_search_plays() {
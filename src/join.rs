@@ -16,6 +16,10 @@ use core::{
 };
 use flume::r#async::RecvFut;
 use pin_project::pin_project;
+use std::sync::{
+    atomic::{AtomicU8, Ordering},
+    Arc,
+};
 use tokio::time::{self, Timeout};
 
 #[cfg(feature = "driver")]
@@ -34,7 +38,7 @@ use tokio::time::{self, Timeout};
 #[pin_project]
 pub struct Join {
     #[pin]
-    gw: JoinClass<()>,
+    gw: JoinClass<JoinResult<()>>,
     #[pin]
     driver: JoinClass<ConnectionResult<()>>,
     state: JoinState,
@@ -44,12 +48,13 @@ pub struct Join {
 impl Join {
     pub(crate) fn new(
         driver: RecvFut<'static, ConnectionResult<()>>,
-        gw_recv: RecvFut<'static, ()>,
+        gw_recv: RecvFut<'static, JoinResult<()>>,
         timeout: Option<Duration>,
+        progress: JoinProgress,
     ) -> Self {
         Self {
-            gw: JoinClass::new(gw_recv, timeout),
-            driver: JoinClass::new(driver, None),
+            gw: JoinClass::new(gw_recv, timeout, progress),
+            driver: JoinClass::new(driver, None, JoinProgress::default()),
             state: JoinState::BeforeGw,
         }
     }
@@ -63,7 +68,7 @@ impl Future for Join {
         let this = self.project();
 
         if *this.state == JoinState::BeforeGw {
-            let poll = this.gw.poll(cx);
+            let poll = this.gw.poll(cx).map(|res| res.and_then(convert::identity));
             match poll {
                 Poll::Ready(a) if a.is_ok() => {
                     *this.state = JoinState::AfterGw;
@@ -118,13 +123,17 @@ enum JoinState {
 #[pin_project]
 pub struct JoinGateway {
     #[pin]
-    inner: JoinClass<ConnectionInfo>,
+    inner: JoinClass<JoinResult<ConnectionInfo>>,
 }
 
 impl JoinGateway {
-    pub(crate) fn new(recv: RecvFut<'static, ConnectionInfo>, timeout: Option<Duration>) -> Self {
+    pub(crate) fn new(
+        recv: RecvFut<'static, JoinResult<ConnectionInfo>>,
+        timeout: Option<Duration>,
+        progress: JoinProgress,
+    ) -> Self {
         Self {
-            inner: JoinClass::new(recv, timeout),
+            inner: JoinClass::new(recv, timeout, progress),
         }
     }
 }
@@ -133,21 +142,59 @@ impl Future for JoinGateway {
     type Output = JoinResult<ConnectionInfo>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        self.project().inner.poll(cx)
+        self.project()
+            .inner
+            .poll(cx)
+            .map(|res| res.and_then(convert::identity))
+    }
+}
+
+/// Records which of Discord's gateway responses a [`Call`] has received while
+/// joining a channel, so that timeouts can be attributed to a likely cause.
+///
+/// [`Call`]: crate::Call
+#[derive(Clone, Debug, Default)]
+pub(crate) struct JoinProgress(Arc<AtomicU8>);
+
+impl JoinProgress {
+    const SERVER: u8 = 1;
+    const STATE: u8 = 1 << 1;
+
+    pub(crate) fn record_server(&self) {
+        self.0.fetch_or(Self::SERVER, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_state(&self) {
+        self.0.fetch_or(Self::STATE, Ordering::Relaxed);
+    }
+
+    /// Returns the error to report if joining has not completed after `elapsed`.
+    pub(crate) fn timeout_error(&self, elapsed: Duration) -> JoinError {
+        // Voice server updates are always delivered, but voice state updates
+        // require the `GUILD_VOICE_STATES` intent.
+        if self.0.load(Ordering::Relaxed) == Self::SERVER {
+            JoinError::MissingVoiceState { elapsed }
+        } else {
+            JoinError::TimedOut { elapsed }
+        }
     }
 }
 
 #[allow(clippy::large_enum_variant)]
 #[pin_project(project = JoinClassProj)]
 enum JoinClass<T: 'static> {
-    WithTimeout(#[pin] Timeout<RecvFut<'static, T>>),
+    WithTimeout(#[pin] Timeout<RecvFut<'static, T>>, Duration, JoinProgress),
     Vanilla(RecvFut<'static, T>),
 }
 
 impl<T: 'static> JoinClass<T> {
-    pub(crate) fn new(recv: RecvFut<'static, T>, timeout: Option<Duration>) -> Self {
+    pub(crate) fn new(
+        recv: RecvFut<'static, T>,
+        timeout: Option<Duration>,
+        progress: JoinProgress,
+    ) -> Self {
         match timeout {
-            Some(t) => JoinClass::WithTimeout(time::timeout(t, recv)),
+            Some(t) => JoinClass::WithTimeout(time::timeout(t, recv), t, progress),
             None => JoinClass::Vanilla(recv),
         }
     }
@@ -161,9 +208,9 @@ where
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         match self.project() {
-            JoinClassProj::WithTimeout(t) => t
+            JoinClassProj::WithTimeout(t, elapsed, progress) => t
                 .poll(cx)
-                .map_err(|_| JoinError::TimedOut)
+                .map_err(|_| progress.timeout_error(*elapsed))
                 .map_ok(|res| res.map_err(|_| JoinError::Dropped))
                 .map(|m| m.and_then(convert::identity)),
             JoinClassProj::Vanilla(t) => Pin::new(t).poll(cx).map_err(|_| JoinError::Dropped),
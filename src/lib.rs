@@ -133,4 +133,4 @@ pub use crate::{handler::*, manager::*};
 pub use crate::serenity::*;
 
 pub use config::Config;
-pub use info::ConnectionInfo;
+pub use info::{ConnectionInfo, ConnectionInfoBuilder, GatewayUpdate};
@@ -1,3 +1,5 @@
+#[cfg(feature = "driver")]
+use crate::driver::Scheduler;
 #[cfg(feature = "serenity")]
 use crate::shards::SerenitySharder;
 use crate::{
@@ -7,10 +9,12 @@ use crate::{
     Call,
     Config,
     ConnectionInfo,
+    GatewayUpdate,
 };
 #[cfg(feature = "serenity")]
 use async_trait::async_trait;
 use dashmap::DashMap;
+use derivative::Derivative;
 #[cfg(feature = "serenity")]
 use futures::channel::mpsc::UnboundedSender as Sender;
 use once_cell::sync::OnceCell;
@@ -24,6 +28,8 @@ use serenity::{
     },
 };
 use std::sync::Arc;
+#[cfg(feature = "driver")]
+use std::time::Duration;
 use tokio::sync::Mutex;
 #[cfg(feature = "serenity")]
 use tracing::debug;
@@ -36,18 +42,25 @@ struct ClientData {
     user_id: UserId,
 }
 
+type GatewayUpdateHook = Arc<dyn Fn(&GatewayUpdate) + Send + Sync>;
+
 /// A shard-aware struct responsible for managing [`Call`]s.
 ///
 /// This manager transparently maps guild state and a source of shard information
 /// into individual calls, and forwards state updates which affect call state.
 ///
 /// [`Call`]: Call
-#[derive(Debug)]
+#[derive(Derivative)]
+#[derivative(Debug)]
 pub struct Songbird {
     client_data: OnceCell<ClientData>,
     calls: DashMap<GuildId, Arc<Mutex<Call>>>,
     sharder: Sharder,
     config: PRwLock<Config>,
+    #[cfg(feature = "driver")]
+    guild_schedulers: DashMap<GuildId, Scheduler>,
+    #[derivative(Debug = "ignore")]
+    update_hook: PRwLock<Option<GatewayUpdateHook>>,
 }
 
 impl Songbird {
@@ -75,6 +88,9 @@ impl Songbird {
             calls: DashMap::new(),
             sharder: Sharder::Serenity(SerenitySharder::default()),
             config: config.initialise_disposer().into(),
+            #[cfg(feature = "driver")]
+            guild_schedulers: DashMap::new(),
+            update_hook: PRwLock::new(None),
         })
     }
 
@@ -117,6 +133,9 @@ impl Songbird {
             calls: DashMap::new(),
             sharder: Sharder::Twilight(sender_map),
             config: config.initialise_disposer().into(),
+            #[cfg(feature = "driver")]
+            guild_schedulers: DashMap::new(),
+            update_hook: PRwLock::new(None),
         }
     }
 
@@ -135,6 +154,40 @@ impl Songbird {
             .ok();
     }
 
+    /// Sets a hook which is called with every voice server and voice state update
+    /// for this bot's user, before it is applied to its guild's [`Call`].
+    ///
+    /// This is intended for diagnosing connection problems: e.g., if calls to
+    /// [`join`] never complete and no updates are observed here, then the necessary
+    /// gateway intents may be missing or events may not be reaching this manager.
+    /// Updates are reported whether or not a [`Call`] exists for their guild.
+    ///
+    /// The hook is called inline while processing gateway events, and so should
+    /// return quickly. This replaces any previously set hook.
+    ///
+    /// [`Call`]: Call
+    /// [`join`]: Songbird::join
+    pub fn set_gateway_update_hook<F>(&self, hook: F)
+    where
+        F: Fn(&GatewayUpdate) + Send + Sync + 'static,
+    {
+        *self.update_hook.write() = Some(Arc::new(hook));
+    }
+
+    /// Removes any hook set by [`Self::set_gateway_update_hook`].
+    pub fn clear_gateway_update_hook(&self) {
+        *self.update_hook.write() = None;
+    }
+
+    #[cfg(any(feature = "serenity", feature = "twilight"))]
+    fn report_update(&self, update: impl FnOnce() -> GatewayUpdate) {
+        // Clone out the hook so that it may safely replace itself.
+        let hook = self.update_hook.read().clone();
+        if let Some(hook) = hook {
+            hook(&update());
+        }
+    }
+
     /// Retrieves a [`Call`] for the given guild, if one already exists.
     ///
     /// [`Call`]: Call
@@ -174,12 +227,14 @@ impl Songbird {
                         .get_shard(shard)
                         .expect("Failed to get shard handle: shard_count incorrect?");
 
-                    let call = Call::from_config(
-                        guild_id,
-                        shard_handle,
-                        info.user_id,
-                        self.config.read().clone(),
-                    );
+                    #[allow(unused_mut)]
+                    let mut config = self.config.read().clone();
+                    #[cfg(feature = "driver")]
+                    if let Some(scheduler) = self.guild_schedulers.get(&guild_id) {
+                        config.scheduler = Some(scheduler.clone());
+                    }
+
+                    let call = Call::from_config(guild_id, shard_handle, info.user_id, config);
 
                     Arc::new(Mutex::new(call))
                 })
@@ -205,6 +260,66 @@ impl Songbird {
         *config = new_config;
     }
 
+    #[cfg(feature = "driver")]
+    /// Returns a lightweight reference to the audio scheduler used by drivers
+    /// created from this manager's shared configuration.
+    ///
+    /// This is [`DEFAULT_SCHEDULER`] unless a custom scheduler has been configured,
+    /// and can be used to monitor the number of live and idle mixers alongside
+    /// per-worker compute costs.
+    ///
+    /// Calls whose configuration has since been changed via [`Driver::set_config`]
+    /// may use a different scheduler, as may guilds given their own scheduler via
+    /// [`Self::set_guild_scheduler`].
+    ///
+    /// [`DEFAULT_SCHEDULER`]: static@crate::driver::DEFAULT_SCHEDULER
+    /// [`Driver::set_config`]: crate::driver::Driver::set_config
+    #[must_use]
+    pub fn scheduler(&self) -> Scheduler {
+        self.config.read().get_scheduler()
+    }
+
+    #[cfg(feature = "driver")]
+    /// Sets the audio scheduler used by calls created for a guild, overriding the
+    /// scheduler of this manager's shared configuration. Passing `None` removes
+    /// any override.
+    ///
+    /// This allows groups of guilds to be isolated from one another: e.g., guilds
+    /// which play many expensive tracks can be given a dedicated [`Scheduler`],
+    /// whose threads cannot starve mixers belonging to other guilds. A single
+    /// scheduler may be given to many guilds, as [`Scheduler`] is a cheap handle.
+    ///
+    /// As with [`Self::set_config`], this applies to calls created after this
+    /// method is called. An existing call for the guild keeps its scheduler until
+    /// it is removed via [`Self::remove`].
+    ///
+    /// A scheduler's threads run for as long as any handle to it exists, including
+    /// those held by this manager, by a [`Config`], and by each [`Driver`] it runs.
+    /// Idle schedulers with no remaining mixers cost one parked thread. Once every
+    /// handle has been dropped, the scheduler's threads exit.
+    ///
+    /// [`Driver`]: crate::driver::Driver
+    pub fn set_guild_scheduler<G: Into<GuildId>>(&self, guild_id: G, scheduler: Option<Scheduler>) {
+        let guild_id = guild_id.into();
+        if let Some(scheduler) = scheduler {
+            self.guild_schedulers.insert(guild_id, scheduler);
+        } else {
+            self.guild_schedulers.remove(&guild_id);
+        }
+    }
+
+    #[cfg(feature = "driver")]
+    /// Returns the audio scheduler which will be used by calls created for a guild.
+    ///
+    /// This is the scheduler set via [`Self::set_guild_scheduler`] if one exists,
+    /// and [`Self::scheduler`] otherwise.
+    #[must_use]
+    pub fn guild_scheduler<G: Into<GuildId>>(&self, guild_id: G) -> Scheduler {
+        self.guild_schedulers
+            .get(&guild_id.into())
+            .map_or_else(|| self.scheduler(), |s| s.clone())
+    }
+
     #[cfg(feature = "driver")]
     /// Connects to a target by retrieving its relevant [`Call`] and
     /// connecting, or creating the handler if required.
@@ -258,6 +373,150 @@ impl Songbird {
         }
     }
 
+    #[cfg(feature = "driver")]
+    /// Connects to a target as in [`join`], failing if the voice connection is not
+    /// fully established within `timeout`.
+    ///
+    /// The returned future resolves only once both Discord's gateway response has
+    /// arrived *and* the [`Driver`] has completed its WebSocket and UDP handshakes
+    /// (i.e., when [`CoreEvent::DriverConnect`] would fire). `timeout` bounds both
+    /// stages together, and elapsing returns [`JoinError::TimedOut`] (or
+    /// [`JoinError::MissingVoiceState`], if this looks to be the cause). No event
+    /// handlers are registered on the [`Call`] to achieve this.
+    ///
+    /// As with [`join`], an `Err(..)` value will still create a [`Call`] accessible
+    /// via [`get`], and its driver may continue to connect in the background.
+    /// Users should [`leave`] before re-attempting connection.
+    ///
+    /// [`Call`]: Call
+    /// [`Driver`]: crate::driver::Driver
+    /// [`CoreEvent::DriverConnect`]: crate::events::CoreEvent::DriverConnect
+    /// [`join`]: Songbird::join
+    /// [`get`]: Songbird::get
+    /// [`leave`]: Songbird::leave
+    #[inline]
+    pub async fn join_and_wait<C, G>(
+        &self,
+        guild_id: G,
+        channel_id: C,
+        timeout: Duration,
+    ) -> JoinResult<Arc<Mutex<Call>>>
+    where
+        C: Into<ChannelId>,
+        G: Into<GuildId>,
+    {
+        let guild_id = guild_id.into();
+
+        match tokio::time::timeout(timeout, self.join(guild_id, channel_id)).await {
+            Ok(res) => res,
+            Err(_) => Err(match self.get(guild_id) {
+                Some(call) => call.lock().await.join_timeout_error(timeout),
+                None => JoinError::TimedOut { elapsed: timeout },
+            }),
+        }
+    }
+
+    #[cfg(feature = "driver")]
+    /// Retrieves the [`Call`] for the given target if it is already in the given
+    /// channel, or otherwise connects it to that channel.
+    ///
+    /// * If no [`Call`] exists for the guild, or it is not in any voice channel, then
+    ///   this behaves as [`join`].
+    /// * If the [`Call`] is already in (or joining) `channel_id`, then it is returned
+    ///   immediately. No messages are sent over the gateway, and any in-progress
+    ///   connection is not awaited.
+    /// * If the [`Call`] is in a different voice channel, then it is moved into
+    ///   `channel_id` as in [`move_to`], preserving its driver and tracks. This never
+    ///   returns an error just because the channel differs.
+    ///
+    /// NOTE: an `Err(..)` value will still create a [`Call`] accessible via [`get`].
+    ///
+    /// [`Call`]: Call
+    /// [`join`]: Songbird::join
+    /// [`move_to`]: Songbird::move_to
+    /// [`get`]: Songbird::get
+    #[inline]
+    pub async fn get_or_join<C, G>(
+        &self,
+        guild_id: G,
+        channel_id: C,
+    ) -> JoinResult<Arc<Mutex<Call>>>
+    where
+        C: Into<ChannelId>,
+        G: Into<GuildId>,
+    {
+        self._get_or_join(guild_id.into(), channel_id.into()).await
+    }
+
+    #[cfg(feature = "driver")]
+    async fn _get_or_join(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> JoinResult<Arc<Mutex<Call>>> {
+        let call = self.get_or_insert(guild_id);
+
+        let stage_1 = {
+            let mut handler = call.lock().await;
+            match handler.current_channel() {
+                Some(current) if current == channel_id => None,
+                Some(_) => Some(handler.move_to(channel_id).await),
+                None => Some(handler.join(channel_id).await),
+            }
+        };
+
+        match stage_1 {
+            None => Ok(call),
+            Some(Ok(chan)) => chan.await.map(|()| call),
+            Some(Err(e)) => Err(e),
+        }
+    }
+
+    #[cfg(feature = "driver")]
+    /// Moves the [`Call`] for the given target into another voice channel, keeping
+    /// its driver, tracks, and event handlers.
+    ///
+    /// The returned future resolves once Discord has confirmed the move and the
+    /// [`Driver`] has resumed its session (or reconnected) in the new channel.
+    ///
+    /// Returns [`JoinError::NoCall`] if there is no [`Call`] for this guild, and
+    /// [`JoinError::NotConnected`] if that call is not in any voice channel.
+    ///
+    /// This is a wrapper around [getting][`get`] a handler and calling
+    /// [`move_to`] on it.
+    ///
+    /// [`Call`]: Call
+    /// [`Driver`]: crate::driver::Driver
+    /// [`get`]: Songbird::get
+    /// [`move_to`]: Call::move_to
+    #[inline]
+    pub async fn move_to<C, G>(&self, guild_id: G, channel_id: C) -> JoinResult<Arc<Mutex<Call>>>
+    where
+        C: Into<ChannelId>,
+        G: Into<GuildId>,
+    {
+        self._move_to(guild_id.into(), channel_id.into()).await
+    }
+
+    #[cfg(feature = "driver")]
+    async fn _move_to(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> JoinResult<Arc<Mutex<Call>>> {
+        let call = self.get(guild_id).ok_or(JoinError::NoCall)?;
+
+        let stage_1 = {
+            let mut handler = call.lock().await;
+            handler.move_to(channel_id).await
+        };
+
+        match stage_1 {
+            Ok(chan) => chan.await.map(|()| call),
+            Err(e) => Err(e),
+        }
+    }
+
     /// Partially connects to a target by retrieving its relevant [`Call`] and
     /// connecting, or creating the handler if required.
     ///
@@ -383,6 +642,12 @@ impl Songbird {
         match event {
             TwilightEvent::VoiceServerUpdate(v) => {
                 let guild_id = GuildId::from(v.guild_id);
+                self.report_update(|| GatewayUpdate::Server {
+                    guild_id,
+                    endpoint: v.endpoint.clone(),
+                    token: v.token.clone(),
+                });
+
                 let call = self.get(guild_id);
 
                 if let Some(call) = call {
@@ -401,10 +666,17 @@ impl Songbird {
                     return;
                 }
 
+                self.report_update(|| GatewayUpdate::State {
+                    guild_id: v.0.guild_id.map(GuildId::from),
+                    channel_id: v.0.channel_id.map(ChannelId::from),
+                    session_id: v.0.session_id.clone(),
+                });
+
                 let call = v.0.guild_id.map(GuildId::from).and_then(|id| self.get(id));
 
                 if let Some(call) = call {
                     let mut handler = call.lock().await;
+                    handler.update_voice_flags(v.0.self_deaf, v.0.self_mute);
                     handler.update_state(v.0.session_id.clone(), v.0.channel_id);
                 }
             },
@@ -444,6 +716,12 @@ impl VoiceGatewayManager for Songbird {
     }
 
     async fn server_update(&self, guild_id: SerenityGuild, endpoint: &Option<String>, token: &str) {
+        self.report_update(|| GatewayUpdate::Server {
+            guild_id: guild_id.into(),
+            endpoint: endpoint.clone(),
+            token: token.to_string(),
+        });
+
         if let Some(call) = self.get(guild_id) {
             let mut handler = call.lock().await;
             if let Some(endpoint) = endpoint {
@@ -459,8 +737,15 @@ impl VoiceGatewayManager for Songbird {
             return;
         }
 
+        self.report_update(|| GatewayUpdate::State {
+            guild_id: Some(guild_id.into()),
+            channel_id: voice_state.channel_id.map(ChannelId::from),
+            session_id: voice_state.session_id.clone(),
+        });
+
         if let Some(call) = self.get(guild_id) {
             let mut handler = call.lock().await;
+            handler.update_voice_flags(voice_state.self_deaf, voice_state.self_mute);
             handler.update_state(voice_state.session_id.clone(), voice_state.channel_id);
         }
     }
@@ -505,3 +790,114 @@ impl<'a> Iterator for Iter<'a> {
 fn shard_id(guild_id: u64, shard_count: u64) -> u64 {
     (guild_id >> 22) % shard_count
 }
+
+#[cfg(all(test, feature = "driver"))]
+mod tests {
+    use super::*;
+    use crate::{
+        driver::SchedulerConfig,
+        shards::{test_fixtures::RecordUpdates, GenericSharder, VoiceUpdate},
+    };
+    use std::num::NonZeroU64;
+
+    struct OneShard(Arc<RecordUpdates>);
+
+    impl GenericSharder for OneShard {
+        fn get_shard(&self, _shard_id: u64) -> Option<Arc<dyn VoiceUpdate + Send + Sync>> {
+            Some(self.0.clone())
+        }
+    }
+
+    fn id(n: u64) -> NonZeroU64 {
+        NonZeroU64::new(n).unwrap()
+    }
+
+    fn test_manager(sharder: Sharder, config: Config) -> Songbird {
+        let manager = Songbird {
+            client_data: OnceCell::new(),
+            calls: DashMap::new(),
+            sharder,
+            config: config.initialise_disposer().into(),
+            guild_schedulers: DashMap::new(),
+            update_hook: PRwLock::new(None),
+        };
+        manager.initialise_client_data(1, UserId(id(1)));
+
+        manager
+    }
+
+    #[tokio::test]
+    async fn get_or_join_reuses_call_in_same_channel() {
+        let updates = Arc::new(RecordUpdates::default());
+        let manager = test_manager(
+            Sharder::Generic(Arc::new(OneShard(updates.clone()))),
+            Config::default(),
+        );
+
+        let (guild, channel) = (GuildId(id(2)), ChannelId(id(3)));
+        let call = manager.get_or_insert(guild);
+        let _join = call.lock().await.join(channel).await.unwrap();
+
+        let found = manager.get_or_join(guild, channel).await.unwrap();
+        assert!(Arc::ptr_eq(&call, &found));
+        assert_eq!(*updates.0.lock(), vec![(Some(channel), false, false)]);
+    }
+
+    #[tokio::test]
+    async fn guild_schedulers_apply_to_new_calls() {
+        let updates = Arc::new(RecordUpdates::default());
+        let manager = test_manager(
+            Sharder::Generic(Arc::new(OneShard(updates))),
+            Config::default().scheduler(Scheduler::new(SchedulerConfig::default())),
+        );
+
+        let dedicated = Scheduler::new(SchedulerConfig::default());
+        let (isolated, shared) = (GuildId(id(2)), GuildId(id(3)));
+        manager.set_guild_scheduler(isolated, Some(dedicated.clone()));
+        assert_eq!(manager.guild_scheduler(isolated).total_tasks(), 0);
+
+        let _isolated_call = manager.get_or_insert(isolated);
+        let _shared_call = manager.get_or_insert(shared);
+
+        for _ in 0..100 {
+            if dedicated.total_tasks() == 1 && manager.scheduler().total_tasks() == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(dedicated.total_tasks(), 1);
+        assert_eq!(manager.scheduler().total_tasks(), 1);
+
+        manager.set_guild_scheduler(isolated, None);
+        assert_eq!(manager.guild_scheduler(isolated).total_tasks(), 1);
+        assert_eq!(dedicated.total_tasks(), 1);
+    }
+
+    #[cfg(feature = "serenity")]
+    #[tokio::test]
+    async fn gateway_updates_are_reported_before_use() {
+        let manager = test_manager(
+            Sharder::Serenity(SerenitySharder::default()),
+            Config::default(),
+        );
+
+        let seen = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let seen_hook = seen.clone();
+        manager.set_gateway_update_hook(move |update| seen_hook.lock().push(update.clone()));
+
+        let endpoint = Some("voice.example".to_string());
+        manager
+            .server_update(SerenityGuild::new(2), &endpoint, "hunter2")
+            .await;
+
+        let seen = seen.lock();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].guild_id(), Some(GuildId(id(2))));
+        assert!(matches!(
+            &seen[0],
+            GatewayUpdate::Server { endpoint: Some(e), token, .. }
+                if e == "voice.example" && token == "hunter2"
+        ));
+        assert!(!format!("{:?}", seen[0]).contains("hunter2"));
+    }
+}
@@ -294,3 +294,26 @@ impl SerenityShardHandle {
         }
     }
 }
+
+#[cfg(all(test, feature = "driver"))]
+pub(crate) mod test_fixtures {
+    use super::*;
+
+    /// Records every voice state update sent to it, in order.
+    #[derive(Default)]
+    pub(crate) struct RecordUpdates(pub parking_lot::Mutex<Vec<(Option<ChannelId>, bool, bool)>>);
+
+    #[async_trait]
+    impl VoiceUpdate for RecordUpdates {
+        async fn update_voice_state(
+            &self,
+            _guild_id: GuildId,
+            channel_id: Option<ChannelId>,
+            self_deaf: bool,
+            self_mute: bool,
+        ) -> JoinResult<()> {
+            self.0.lock().push((channel_id, self_deaf, self_mute));
+            Ok(())
+        }
+    }
+}
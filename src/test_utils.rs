@@ -1,7 +1,7 @@
 #![allow(missing_docs)]
 
 use byteorder::{LittleEndian, WriteBytesExt};
-use std::mem;
+use std::{mem, path::PathBuf};
 
 #[must_use]
 pub fn make_sine(float_len: usize, stereo: bool) -> Vec<u8> {
@@ -69,3 +69,50 @@ pub fn make_pcm_sine(i16_len: usize, stereo: bool) -> Vec<u8> {
         out
     }
 }
+
+/// Returns a path in the system temporary directory which is unique to this process
+/// and `name`, so that concurrent test runs do not collide.
+#[must_use]
+pub fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("songbird-{}-{name}", std::process::id()))
+}
+
+/// Runs a minimal HTTP/1.1 server on localhost, returning its address (`host:port`)
+/// and a receiver of the head of each request it is sent.
+///
+/// Each connection is answered once with the raw bytes built by `respond` from the
+/// request's head, and then closed.
+#[cfg(all(test, feature = "driver"))]
+pub async fn serve_http<F>(respond: F) -> (String, flume::Receiver<String>)
+where
+    F: Fn(&str) -> Vec<u8> + Send + 'static,
+{
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let (tx, rx) = flume::unbounded();
+
+    tokio::spawn(async move {
+        while let Ok((mut conn, _)) = listener.accept().await {
+            let mut head = vec![];
+            let mut buf = [0u8; 1024];
+            while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                match conn.read(&mut buf).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => head.extend_from_slice(&buf[..n]),
+                }
+            }
+
+            let head = String::from_utf8_lossy(&head).into_owned();
+            let response = respond(&head);
+            _ = tx.send(head);
+            _ = conn.write_all(&response).await;
+        }
+    });
+
+    (addr, rx)
+}
@@ -18,7 +18,7 @@ impl Action {
     #[must_use]
     pub fn seek(mut self, time: Duration) -> Self {
         let (callback, _) = flume::bounded(1);
-        self.seek_point = Some(SeekRequest { time, callback });
+        self.seek_point = Some(SeekRequest::time(time, callback));
 
         self
     }
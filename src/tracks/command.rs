@@ -1,5 +1,5 @@
 use super::*;
-use crate::events::EventData;
+use crate::{events::EventData, input::Input};
 use flume::Sender;
 use std::{
     fmt::{Debug, Formatter, Result as FmtResult},
@@ -33,8 +33,14 @@ pub enum TrackCommand {
     Request(Sender<TrackState>),
     /// Change the loop count/strategy of this track.
     Loop(LoopState),
+    /// Set or clear the region of this track which loops are confined to.
+    LoopRegion(Option<(Duration, Duration)>),
     /// Prompts a track's input to become live and usable, if it is not already.
     MakePlayable(Sender<Result<(), PlayError>>),
+    /// Replace the audio source of this track, keeping all other state.
+    ReplaceInput(Input),
+    /// Change this track's role in sidechain ducking.
+    Ducking(Option<DuckRole>),
 }
 
 impl Debug for TrackCommand {
@@ -47,12 +53,15 @@ impl Debug for TrackCommand {
                 Self::Pause => "Pause".to_string(),
                 Self::Stop => "Stop".to_string(),
                 Self::Volume(vol) => format!("Volume({vol})"),
-                Self::Seek(s) => format!("Seek({:?})", s.time),
+                Self::Seek(s) => format!("Seek({:?})", s.target),
                 Self::AddEvent(evt) => format!("AddEvent({evt:?})"),
                 Self::Do(_f) => "Do([function])".to_string(),
                 Self::Request(tx) => format!("Request({tx:?})"),
                 Self::Loop(loops) => format!("Loop({loops:?})"),
+                Self::LoopRegion(region) => format!("LoopRegion({region:?})"),
                 Self::MakePlayable(_) => "MakePlayable".to_string(),
+                Self::ReplaceInput(_) => "ReplaceInput([input])".to_string(),
+                Self::Ducking(role) => format!("Ducking({role:?})"),
             }
         )
     }
@@ -60,6 +69,51 @@ impl Debug for TrackCommand {
 
 #[derive(Clone, Debug)]
 pub struct SeekRequest {
-    pub time: Duration,
-    pub callback: Sender<Result<Duration, PlayError>>,
+    pub target: SeekTarget,
+    pub callback: SeekCallback,
+}
+
+impl SeekRequest {
+    pub fn time(time: Duration, callback: Sender<Result<Duration, PlayError>>) -> Self {
+        Self {
+            target: SeekTarget::Time(time),
+            callback: SeekCallback::Time(callback),
+        }
+    }
+
+    pub fn sample(ts: u64, callback: Sender<Result<u64, PlayError>>) -> Self {
+        Self {
+            target: SeekTarget::Sample(ts),
+            callback: SeekCallback::Sample(callback),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SeekTarget {
+    Time(Duration),
+    /// A timestamp in the units of the input's time base.
+    Sample(u64),
+}
+
+#[derive(Clone, Debug)]
+pub enum SeekCallback {
+    Time(Sender<Result<Duration, PlayError>>),
+    Sample(Sender<Result<u64, PlayError>>),
+}
+
+impl SeekCallback {
+    pub fn seeked(self, time: Duration, ts: u64) {
+        match self {
+            Self::Time(tx) => drop(tx.send(Ok(time))),
+            Self::Sample(tx) => drop(tx.send(Ok(ts))),
+        }
+    }
+
+    pub fn failed(self, err: PlayError) {
+        match self {
+            Self::Time(tx) => drop(tx.send(Err(err))),
+            Self::Sample(tx) => drop(tx.send(Err(err))),
+        }
+    }
 }
@@ -0,0 +1,28 @@
+use super::TrackState;
+
+/// Why the driver's mixer removed a [`Track`].
+///
+/// [`Track`]: super::Track
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum RemovalReason {
+    /// The track stopped, ended, or errored.
+    ///
+    /// Which of these occurred is given by the [`playing`] field of the track's final state.
+    ///
+    /// [`playing`]: TrackState::playing
+    Finished,
+    /// All tracks were removed at once, such as by [`Driver::stop`], [`Driver::play_only`],
+    /// a fade out, or the driver being dropped.
+    ///
+    /// [`Driver::stop`]: crate::driver::Driver::stop
+    /// [`Driver::play_only`]: crate::driver::Driver::play_only
+    Cleared,
+}
+
+/// A callback run once a track's resources have been released.
+///
+/// See [`Track::on_dispose`] for when this is called.
+///
+/// [`Track::on_dispose`]: super::Track::on_dispose
+pub type DisposalHook = Box<dyn FnOnce(TrackState, RemovalReason) + Send>;
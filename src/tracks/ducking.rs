@@ -0,0 +1,180 @@
+use crate::constants::TIMESTEP_LENGTH;
+use std::time::Duration;
+
+/// How a [`Track`] takes part in sidechain ducking.
+///
+/// Ducking lowers the volume of background tracks (e.g., music) while foreground
+/// tracks (e.g., speech or TTS) in the same group are audible. Each time it is mixed,
+/// the RMS level of every playing controller in a group is measured. Tracks ducked by
+/// that group then move towards their reduced volume if any controller exceeds their
+/// [`threshold`], and back towards full volume otherwise.
+///
+/// Ducked tracks respond to the level of their controllers in the previous 20ms frame.
+/// Controllers whose audio is passed through without decoding are not measured.
+///
+/// [`Track`]: super::Track
+/// [`threshold`]: Ducking::threshold
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum DuckRole {
+    /// This track's audio controls the ducking of the given group.
+    Controller(u32),
+    /// This track is ducked by the controllers of the given group.
+    Ducked(u32, Ducking),
+}
+
+/// How far, and how quickly, a track's volume is lowered while it is ducked.
+///
+/// Gain changes linearly over the `attack` and `release` times: lengthening these
+/// avoids audible "pumping" when controllers are only briefly active.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Ducking {
+    /// RMS level, relative to full scale, above which a controller engages ducking.
+    ///
+    /// Defaults to `0.02` (around -34 dBFS).
+    pub threshold: f32,
+    /// Fraction of this track's volume kept while fully ducked, between `0.0` and `1.0`.
+    ///
+    /// Defaults to `0.25` (around -12 dB).
+    pub ratio: f32,
+    /// Time taken to move from full volume to fully ducked.
+    ///
+    /// Defaults to 100ms.
+    pub attack: Duration,
+    /// Time taken to return to full volume once no controller is active.
+    ///
+    /// Defaults to 500ms.
+    pub release: Duration,
+}
+
+impl Default for Ducking {
+    fn default() -> Self {
+        Self {
+            threshold: 0.02,
+            ratio: 0.25,
+            attack: Duration::from_millis(100),
+            release: Duration::from_millis(500),
+        }
+    }
+}
+
+impl Ducking {
+    /// Sets the controller level at which ducking engages.
+    #[must_use]
+    pub fn threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Sets the fraction of volume kept while fully ducked.
+    #[must_use]
+    pub fn ratio(mut self, ratio: f32) -> Self {
+        self.ratio = ratio;
+        self
+    }
+
+    /// Sets the time taken to fully duck.
+    #[must_use]
+    pub fn attack(mut self, attack: Duration) -> Self {
+        self.attack = attack;
+        self
+    }
+
+    /// Sets the time taken to return to full volume.
+    #[must_use]
+    pub fn release(mut self, release: Duration) -> Self {
+        self.release = release;
+        self
+    }
+
+    /// Moves a ducked track's `gain` one frame towards its target, given the
+    /// loudest controller `level` in its group.
+    pub(crate) fn step(&self, gain: f32, level: f32) -> f32 {
+        let ratio = self.ratio.clamp(0.0, 1.0);
+        let depth = 1.0 - ratio;
+
+        if level >= self.threshold {
+            (gain - frame_step(depth, self.attack)).max(ratio)
+        } else {
+            (gain + frame_step(depth, self.release)).min(1.0)
+        }
+    }
+}
+
+/// Gain change per frame needed to cover `depth` over `time`.
+fn frame_step(depth: f32, time: Duration) -> f32 {
+    if time <= TIMESTEP_LENGTH {
+        depth
+    } else {
+        depth * TIMESTEP_LENGTH.as_secs_f32() / time.as_secs_f32()
+    }
+}
+
+/// Loudest controller level measured in each ducking group during one frame.
+#[derive(Debug, Default)]
+pub(crate) struct DuckLevels(Vec<(u32, f32)>);
+
+impl DuckLevels {
+    pub(crate) fn get(&self, group: u32) -> f32 {
+        self.0
+            .iter()
+            .find_map(|&(g, level)| (g == group).then_some(level))
+            .unwrap_or(0.0)
+    }
+
+    pub(crate) fn record(&mut self, group: u32, level: f32) {
+        match self.0.iter_mut().find(|(g, _)| *g == group) {
+            Some((_, old)) => *old = old.max(level),
+            None => self.0.push((group, level)),
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gain_ramps_between_ratio_and_unity() {
+        let ducking = Ducking::default()
+            .ratio(0.5)
+            .attack(Duration::from_millis(40))
+            .release(Duration::from_millis(100));
+
+        // Attack: 2 frames to cover 0.5.
+        let mut gain = ducking.step(1.0, 0.1);
+        assert!((gain - 0.75).abs() < 1e-6);
+        gain = ducking.step(gain, 0.1);
+        assert!((gain - 0.5).abs() < 1e-6);
+        gain = ducking.step(gain, 0.1);
+        assert!((gain - 0.5).abs() < 1e-6);
+
+        // Release: 5 frames to cover 0.5.
+        gain = ducking.step(gain, 0.0);
+        assert!((gain - 0.6).abs() < 1e-6);
+        for _ in 0..5 {
+            gain = ducking.step(gain, 0.01);
+        }
+        assert!((gain - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn levels_keep_loudest_controller() {
+        let mut levels = DuckLevels::default();
+        levels.record(1, 0.2);
+        levels.record(1, 0.1);
+        levels.record(2, 0.05);
+
+        assert!((levels.get(1) - 0.2).abs() < f32::EPSILON);
+        assert!((levels.get(2) - 0.05).abs() < f32::EPSILON);
+        assert!(levels.get(3).abs() < f32::EPSILON);
+
+        levels.clear();
+        assert!(levels.get(1).abs() < f32::EPSILON);
+    }
+}
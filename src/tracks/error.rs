@@ -26,6 +26,8 @@ pub enum ControlError {
     Play(PlayError),
     /// Another `seek`/`make_playable` request was made, and so this callback handler was dropped.
     Dropped,
+    /// A loop region was given whose start is not before its end.
+    InvalidLoopRegion,
 }
 
 impl Display for ControlError {
@@ -40,6 +42,7 @@ impl Display for ControlError {
                 write!(f, "i/o request on track failed: {p}")
             },
             ControlError::Dropped => write!(f, "request was replaced by another of same type"),
+            ControlError::InvalidLoopRegion => write!(f, "loop region must start before it ends"),
         }
     }
 }
@@ -77,6 +80,17 @@ pub enum PlayError {
     Decode(Arc<SymphoniaError>),
     /// Failed to seek to the requested location.
     Seek(Arc<SymphoniaError>),
+    /// The track was added to a driver which already held its maximum number
+    /// of tracks.
+    ///
+    /// See [`Config::max_tracks`].
+    ///
+    /// [`Config::max_tracks`]: crate::Config::max_tracks
+    TrackLimit,
+    /// The track's input did not become ready within [`Config::input_ready_timeout`].
+    ///
+    /// [`Config::input_ready_timeout`]: crate::Config::input_ready_timeout
+    ReadyTimeout,
 }
 
 impl Display for PlayError {
@@ -103,6 +117,8 @@ impl Display for PlayError {
                 f.write_fmt(format_args!("{}", &s))?;
                 f.write_str("]")
             },
+            Self::TrackLimit => f.write_str("driver track limit reached"),
+            Self::ReadyTimeout => f.write_str("input took too long to become ready"),
         }
     }
 }
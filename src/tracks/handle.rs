@@ -1,7 +1,10 @@
 use super::*;
-use crate::events::{Event, EventData, EventHandler};
+use crate::{
+    events::{Event, EventContext, EventData, EventHandler, TrackEvent},
+    input::Input,
+};
 use flume::{Receiver, Sender};
-use std::{fmt, sync::Arc, time::Duration};
+use std::{any::Any, fmt, sync::Arc, time::Duration};
 use tokio::sync::RwLock;
 use typemap_rev::TypeMap;
 use uuid::Uuid;
@@ -23,6 +26,7 @@ struct InnerHandle {
     command_channel: Sender<TrackCommand>,
     uuid: Uuid,
     typemap: RwLock<TypeMap>,
+    user_data: Arc<dyn Any + Send + Sync>,
 }
 
 impl fmt::Debug for InnerHandle {
@@ -31,6 +35,7 @@ impl fmt::Debug for InnerHandle {
             .field("command_channel", &self.command_channel)
             .field("uuid", &self.uuid)
             .field("typemap", &"<LOCK>")
+            .field("user_data", &"<DATA>")
             .finish()
     }
 }
@@ -40,11 +45,16 @@ impl TrackHandle {
     ///
     /// [`Input`]: crate::input::Input
     #[must_use]
-    pub(crate) fn new(command_channel: Sender<TrackCommand>, uuid: Uuid) -> Self {
+    pub(crate) fn new(
+        command_channel: Sender<TrackCommand>,
+        uuid: Uuid,
+        user_data: Arc<dyn Any + Send + Sync>,
+    ) -> Self {
         let inner = Arc::new(InnerHandle {
             command_channel,
             uuid,
             typemap: RwLock::new(TypeMap::new()),
+            user_data,
         });
 
         Self { inner }
@@ -75,6 +85,14 @@ impl TrackHandle {
         self.send(TrackCommand::Volume(volume))
     }
 
+    /// Sets, or clears, this track's role in sidechain ducking.
+    ///
+    /// A track which stops being ducked returns to full volume immediately.
+    /// See [`DuckRole`] for how ducking is applied.
+    pub fn set_ducking(&self, role: Option<DuckRole>) -> TrackResult<()> {
+        self.send(TrackCommand::Ducking(role))
+    }
+
     #[must_use]
     /// Ready a track for playing if it is lazily initialised.
     ///
@@ -107,10 +125,7 @@ impl TrackHandle {
     pub fn seek(&self, position: Duration) -> TrackCallback<Duration> {
         let (tx, rx) = flume::bounded(1);
         let fail = self
-            .send(TrackCommand::Seek(SeekRequest {
-                time: position,
-                callback: tx,
-            }))
+            .send(TrackCommand::Seek(SeekRequest::time(position, tx)))
             .is_err();
 
         TrackCallback { fail, rx }
@@ -124,6 +139,51 @@ impl TrackHandle {
         self.seek(position).result_async().await
     }
 
+    #[must_use]
+    /// Seeks along the track to the specified timestamp, measured in the
+    /// time base of the underlying [`Input`].
+    ///
+    /// For most formats, this is the index of the target sample (per channel)
+    /// at the source's native sample rate. This allows sample-accurate cueing
+    /// without the rounding of [`Self::seek`]. The returned callback holds the
+    /// timestamp which the seek actually landed on, which may differ from the
+    /// requested one depending on the format.
+    ///
+    /// Seeks behave as described in [`Self::seek`] when the [`Input`]
+    /// does not support seeking.
+    ///
+    /// [`Input`]: crate::input::Input
+    pub fn seek_to_sample(&self, sample: u64) -> TrackCallback<u64> {
+        let (tx, rx) = flume::bounded(1);
+        let fail = self
+            .send(TrackCommand::Seek(SeekRequest::sample(sample, tx)))
+            .is_err();
+
+        TrackCallback { fail, rx }
+    }
+
+    /// Seeks along the track to the specified timestamp.
+    ///
+    /// This folds [`Self::seek_to_sample`] into a single `async` result, but must
+    /// be awaited for the command to be sent.
+    pub async fn seek_to_sample_async(&self, sample: u64) -> TrackResult<u64> {
+        self.seek_to_sample(sample).result_async().await
+    }
+
+    /// Replaces the audio source played by this track.
+    ///
+    /// The track keeps its handle, [`Uuid`], events, play mode, volume, and loop state,
+    /// while its position is reset to the start of the new input. The new input is
+    /// readied in the same way as when a track is first played, and any seek which was
+    /// in progress on the old input is cancelled.
+    ///
+    /// The old input is discarded immediately. If the new input then fails to become
+    /// playable, the track ends with [`PlayMode::Errored`], as though it had been
+    /// created with that input.
+    pub fn replace_input(&self, input: impl Into<Input>) -> TrackResult<()> {
+        self.send(TrackCommand::ReplaceInput(input.into()))
+    }
+
     /// Attach an event handler to an audio track. These will receive [`EventContext::Track`].
     ///
     /// Events which can only be fired by the global context return [`ControlError::InvalidTrackEvent`]
@@ -163,6 +223,31 @@ impl TrackHandle {
         rx.recv_async().await.map_err(|_| ControlError::Finished)
     }
 
+    /// Waits until this track has finished, returning its final state.
+    ///
+    /// This resolves once the track ends naturally, is stopped, or encounters an error:
+    /// the reason is given by the returned state's [`playing`] field (i.e.,
+    /// [`PlayMode::End`], [`PlayMode::Stop`], or [`PlayMode::Errored`]). If the track
+    /// has already finished but is still held by the driver, this resolves immediately.
+    ///
+    /// Returns [`ControlError::Finished`] if the track has already been removed from the
+    /// driver, or is removed without finishing (e.g., when the driver is dropped).
+    ///
+    /// [`playing`]: TrackState::playing
+    pub async fn wait_until_end(&self) -> TrackResult<TrackState> {
+        let (tx, rx) = flume::bounded(1);
+        self.add_event(Event::Track(TrackEvent::End), TrackEnded { tx })?;
+
+        // Commands are handled in order: if the track is live after this request,
+        // then the handler above is guaranteed to see it end.
+        let state = self.get_info().await?;
+        if state.playing.is_done() {
+            return Ok(state);
+        }
+
+        rx.recv_async().await.map_err(|_| ControlError::Finished)
+    }
+
     /// Set an audio track to loop indefinitely.
     ///
     /// This requires either a [`Compose`] to be present or for the
@@ -192,6 +277,33 @@ impl TrackHandle {
         self.send(TrackCommand::Loop(LoopState::Finite(count)))
     }
 
+    /// Confine an audio track's loops to the region between two timestamps,
+    /// or loop over the whole track again if `None`.
+    ///
+    /// When playback reaches the end of the region, the track seeks back to its
+    /// start, counting as one loop as set by [`enable_loop`] or [`loop_for`].
+    /// Once no loops remain, playback continues past the region as normal.
+    /// If the track ends before the region does, the end of the track is treated
+    /// as the end of the region. Regions starting after the end of the track will
+    /// cause it to end when looped.
+    ///
+    /// The region only loops when playback crosses its end: manually seeking past
+    /// the region lets the track play on, while seeking before it will loop once
+    /// the region is reached.
+    ///
+    /// This follows the same rules as [`enable_loop`], and fails with
+    /// [`ControlError::InvalidLoopRegion`] if the region's start is not before its end.
+    ///
+    /// [`enable_loop`]: Self::enable_loop
+    /// [`loop_for`]: Self::loop_for
+    pub fn set_loop_region(&self, region: Option<(Duration, Duration)>) -> TrackResult<()> {
+        if matches!(region, Some((start, end)) if start >= end) {
+            return Err(ControlError::InvalidLoopRegion);
+        }
+
+        self.send(TrackCommand::LoopRegion(region))
+    }
+
     /// Returns this handle's (and track's) unique identifier.
     #[must_use]
     pub fn uuid(&self) -> Uuid {
@@ -210,6 +322,18 @@ impl TrackHandle {
         &self.inner.typemap
     }
 
+    /// Returns the user data attached to this track's [`Track::user_data`],
+    /// if it is of type `T`.
+    ///
+    /// Unlike [`typemap`], this requires no locking, and so may be cheaply used
+    /// to identify a track from within an event handler.
+    ///
+    /// [`typemap`]: Self::typemap
+    #[must_use]
+    pub fn data<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.inner.user_data.clone().downcast().ok()
+    }
+
     #[inline]
     /// Send a raw command to the [`Track`] object.
     ///
@@ -224,6 +348,22 @@ impl TrackHandle {
     }
 }
 
+/// Reports a track's final state to [`TrackHandle::wait_until_end`].
+struct TrackEnded {
+    tx: Sender<TrackState>,
+}
+
+#[async_trait::async_trait]
+impl EventHandler for TrackEnded {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        if let EventContext::Track(&[(state, _)]) = ctx {
+            _ = self.tx.send(state.clone());
+        }
+
+        Some(Event::Cancel)
+    }
+}
+
 /// Asynchronous reply for an operation applied to a [`TrackHandle`].
 ///
 /// This object does not need to be `.await`ed for the driver to perform an action.
@@ -265,13 +405,49 @@ impl<T> TrackCallback<T> {
 mod tests {
     use super::*;
     use crate::{
-        constants::test_data::FILE_WAV_TARGET,
+        constants::test_data::{FILE_SHORT_MP3_TARGET, FILE_WAV_TARGET, FILE_WEBM_TARGET},
         driver::Driver,
         input::File,
         tracks::Track,
         Config,
+        EventContext,
+        TrackEvent,
     };
 
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn wait_until_end_reports_natural_end() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.clone());
+
+        let handle = driver.play(Track::from(File::new(FILE_SHORT_MP3_TARGET)));
+        t_handle.spawn_ticker();
+
+        let state = handle.wait_until_end().await.unwrap();
+        assert!(matches!(state.playing, PlayMode::End));
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn wait_until_end_reports_stop() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.clone());
+
+        let handle = driver.play(Track::from(File::new(FILE_WAV_TARGET)));
+        t_handle.ready_track(&handle, None).await;
+        t_handle.spawn_ticker();
+
+        let waiter = tokio::spawn({
+            let handle = handle.clone();
+            async move { handle.wait_until_end().await }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        handle.stop().unwrap();
+
+        let state = waiter.await.unwrap().unwrap();
+        assert!(matches!(state.playing, PlayMode::Stop));
+    }
+
     #[tokio::test]
     #[ntest::timeout(10_000)]
     async fn make_playable_callback_fires() {
@@ -305,4 +481,276 @@ mod tests {
         let delta = Duration::from_millis(100);
         assert!(answer > target - delta && answer < target + delta);
     }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn seek_to_sample_reports_landed_timestamp() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.clone());
+
+        // WAV timestamps are counted in frames at the file's 44.1kHz sample rate.
+        let file = File::new(FILE_WAV_TARGET);
+        let handle = driver.play(Track::from(file).pause());
+
+        let callback = handle.seek_to_sample(44_100);
+        t_handle.spawn_ticker();
+
+        let answer = callback.result_async().await.unwrap();
+        assert!(answer.abs_diff(44_100) < 4_410);
+
+        let position = handle.get_info().await.unwrap().position;
+        assert!(position > Duration::from_millis(900) && position < Duration::from_millis(1100));
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn queued_seek_fires_playable_once() {
+        struct CountPlayable {
+            tx: Sender<ReadyState>,
+        }
+
+        #[async_trait::async_trait]
+        impl EventHandler for CountPlayable {
+            async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+                if let EventContext::Track(&[(state, _)]) = ctx {
+                    _ = self.tx.send(state.ready);
+                }
+
+                None
+            }
+        }
+
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.clone());
+
+        let file = File::new(FILE_WAV_TARGET);
+        let handle = driver.play(Track::from(file).pause());
+
+        let (tx, rx) = flume::unbounded();
+        handle
+            .add_event(Event::Track(TrackEvent::Playable), CountPlayable { tx })
+            .unwrap();
+
+        // Begin readying the track, then request a seek while it is still preparing.
+        drop(handle.make_playable());
+        t_handle.skip(1).await;
+        let callback = handle.seek(Duration::from_millis(500));
+        t_handle.spawn_ticker();
+
+        assert!(callback.result_async().await.is_ok());
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert_eq!(rx.drain().collect::<Vec<_>>(), vec![ReadyState::Playable]);
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn start_at_seeks_before_playback() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.clone());
+
+        let target = Duration::from_millis(500);
+        let file = File::new(FILE_WAV_TARGET);
+        let handle = driver.play(Track::from(file).pause().start_at(target));
+
+        let state = handle.get_info();
+        t_handle.spawn_ticker();
+        let state = state.await.unwrap();
+        assert_eq!(state.position, target);
+
+        assert!(handle.make_playable_async().await.is_ok());
+
+        let state = handle.get_info().await.unwrap();
+        let delta = Duration::from_millis(100);
+        assert_eq!(state.ready, ReadyState::Playable);
+        assert_eq!(state.play_time, Duration::ZERO);
+        assert!(state.position > target.saturating_sub(delta) && state.position < target + delta);
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn driver_uses_configured_registries() {
+        let probe = Box::leak(Box::new(symphonia_core::probe::Probe::default()));
+        let (t_handle, config) = Config::test_cfg(true);
+        let config = config.with_codecs(&crate::input::codecs::CODEC_REGISTRY, probe);
+        let mut driver = Driver::new(config);
+
+        // No formats are registered, so the file cannot be parsed.
+        let file = File::new(FILE_WAV_TARGET);
+        let handle = driver.play(Track::from(file).pause());
+
+        let callback = handle.make_playable();
+        t_handle.spawn_ticker();
+        assert!(matches!(
+            callback.result_async().await,
+            Err(ControlError::Play(PlayError::Parse(_)))
+        ));
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn replace_input_keeps_track_state() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.clone());
+
+        let file = File::new(FILE_WAV_TARGET);
+        let handle = driver.play(Track::from(file).volume(0.5));
+
+        t_handle.ready_track(&handle, None).await;
+        t_handle.skip(50).await;
+        handle.pause().unwrap();
+
+        handle
+            .replace_input(File::new(FILE_SHORT_MP3_TARGET))
+            .unwrap();
+        let state = handle.get_info();
+        t_handle.spawn_ticker();
+        let state = state.await.unwrap();
+
+        assert_eq!(state.ready, ReadyState::Uninitialised);
+        assert_eq!(state.position, Duration::ZERO);
+
+        assert!(handle.make_playable_async().await.is_ok());
+        let state = handle.get_info().await.unwrap();
+
+        assert_eq!(state.ready, ReadyState::Playable);
+        assert_eq!(state.playing, PlayMode::Pause);
+        assert!((state.volume - 0.5).abs() < f32::EPSILON);
+        assert!(state.play_time >= Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn preloaded_input_is_immediately_playable() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.clone());
+
+        let input = driver
+            .preload(File::new(FILE_WAV_TARGET).into())
+            .ready()
+            .await
+            .unwrap();
+        assert!(input.is_playable());
+
+        let handle = driver.play_input(input);
+        let state = handle.get_info();
+        t_handle.tick(1);
+        let state = state.await.unwrap();
+
+        assert_eq!(state.ready, ReadyState::Playable);
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn mix_cost_is_reported_per_track() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.clone());
+
+        let playing = driver.play(Track::from(File::new(FILE_WAV_TARGET)));
+        let paused = driver.play(Track::from(File::new(FILE_WAV_TARGET)).pause());
+        t_handle.ready_track(&playing, None).await;
+        t_handle.skip(2).await;
+
+        let playing_state = playing.get_info();
+        let paused_state = paused.get_info();
+        t_handle.skip(1).await;
+
+        assert!(playing_state.await.unwrap().mix_cost > Duration::ZERO);
+        assert_eq!(paused_state.await.unwrap().mix_cost, Duration::ZERO);
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn passthrough_is_reported_per_track() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.clone());
+
+        let handle = driver.play(Track::from(File::new(FILE_WEBM_TARGET)));
+        let callback = handle.make_playable();
+        t_handle.spawn_ticker();
+        callback.result_async().await.unwrap();
+
+        wait_for_passthrough(&handle, Passthrough::Active).await;
+
+        handle.set_volume(0.5).unwrap();
+        wait_for_passthrough(&handle, Passthrough::Inactive).await;
+    }
+
+    /// Polls `handle` until its passthrough state changes to `target`, as volume changes
+    /// are only reflected once the mixer next mixes the track.
+    async fn wait_for_passthrough(handle: &TrackHandle, target: Passthrough) {
+        while handle.get_info().await.unwrap().passthrough != target {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn pause_all_halts_track_positions() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.clone());
+
+        let handle =
+            driver.play(Track::from(File::new(FILE_WAV_TARGET)).loops(LoopState::Infinite));
+        t_handle.ready_track(&handle, None).await;
+        t_handle.skip(5).await;
+
+        driver.pause_all();
+        t_handle.skip(1).await;
+
+        let state = handle.get_info();
+        t_handle.skip(1).await;
+        let paused = state.await.unwrap();
+        assert_eq!(paused.playing, PlayMode::Pause);
+
+        t_handle.skip(10).await;
+        let state = handle.get_info();
+        t_handle.skip(1).await;
+        assert_eq!(state.await.unwrap().position, paused.position);
+
+        driver.resume_all();
+        t_handle.skip(5).await;
+
+        let state = handle.get_info();
+        t_handle.skip(1).await;
+        let resumed = state.await.unwrap();
+        assert_eq!(resumed.playing, PlayMode::Play);
+        assert!(resumed.position > paused.position);
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn user_data_is_visible_in_events() {
+        struct SongId(u64);
+
+        struct SendSongId {
+            tx: Sender<Option<u64>>,
+        }
+
+        #[async_trait::async_trait]
+        impl EventHandler for SendSongId {
+            async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+                if let EventContext::Track(&[(_, handle)]) = ctx {
+                    _ = self.tx.send(handle.data::<SongId>().map(|id| id.0));
+                }
+
+                None
+            }
+        }
+
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.clone());
+
+        let track = Track::new_with_data(File::new(FILE_WAV_TARGET).into(), Arc::new(SongId(7)));
+        let handle = driver.play(track);
+        assert!(handle.data::<u64>().is_none());
+
+        let (tx, rx) = flume::unbounded();
+        handle
+            .add_event(Event::Track(TrackEvent::Playable), SendSongId { tx })
+            .unwrap();
+        t_handle.spawn_ticker();
+
+        assert_eq!(rx.recv_async().await.unwrap(), Some(7));
+    }
 }
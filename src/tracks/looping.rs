@@ -88,6 +88,30 @@ mod tests {
         assert!(ended.play_time > 2 * ended.position);
     }
 
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn final_loop_fires_once() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.clone());
+
+        let file = File::new(FILE_WAV_TARGET);
+        let handle = driver.play(Track::from(file).loops(LoopState::Finite(2)));
+
+        let (f_tx, f_rx) = flume::unbounded();
+        let (e_tx, e_rx) = flume::unbounded();
+        let _ = handle.add_event(Event::Track(TrackEvent::FinalLoop), Looper { tx: f_tx });
+        let _ = handle.add_event(Event::Track(TrackEvent::End), Looper { tx: e_tx });
+
+        t_handle.spawn_ticker();
+
+        assert_eq!(
+            f_rx.recv_async().await.map(|v| v.loops),
+            Ok(LoopState::Finite(0))
+        );
+        assert!(e_rx.recv_async().await.is_ok());
+        assert!(f_rx.is_empty());
+    }
+
     #[tokio::test]
     #[ntest::timeout(10_000)]
     async fn infinite_track_loops_work() {
@@ -0,0 +1,191 @@
+use crate::input::Parsed;
+use symphonia_core::meta::{MetadataRevision, StandardTagKey};
+
+/// Reference loudness of Replay Gain 2.0 gain values, in LUFS.
+const REPLAYGAIN_REFERENCE_LUFS: f32 = -18.0;
+
+/// Loudness normalisation applied to a [`Track`], to give consistent perceived
+/// loudness across tracks from different sources.
+///
+/// Gain is computed from the Replay Gain tags embedded in a track's metadata once its
+/// input has been parsed, and is applied in addition to the track's volume. Where
+/// Replay Gain peak values are present, gain is limited so that the track's peak does
+/// not clip. If a track has no Replay Gain tags, no gain is applied.
+///
+/// [`Track`]: super::Track
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum TargetLoudness {
+    /// Applies the track's Replay Gain track gain.
+    ReplayGainTrack,
+    /// Applies the track's Replay Gain album gain, falling back to its track gain.
+    ///
+    /// This preserves the relative loudness of tracks within the same album.
+    ReplayGainAlbum,
+    /// Normalises a track to the given integrated loudness, in LUFS.
+    ///
+    /// This is derived from the track's Replay Gain track gain, which targets -18 LUFS.
+    Lufs(f32),
+}
+
+impl TargetLoudness {
+    /// Computes the linear gain needed to reach this target from a parsed input's tags.
+    ///
+    /// Format-level tags take precedence over those found while probing the file.
+    pub(crate) fn gain(self, parsed: &mut Parsed) -> Option<f32> {
+        let mut tags = ReplayGainTags::default();
+
+        if let Some(meta) = parsed.meta.get() {
+            tags.apply_revision(meta.current());
+        }
+
+        tags.apply_revision(parsed.format.metadata().current());
+
+        tags.gain(self)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct ReplayGainTags {
+    track_gain: Option<f32>,
+    track_peak: Option<f32>,
+    album_gain: Option<f32>,
+    album_peak: Option<f32>,
+}
+
+impl ReplayGainTags {
+    fn apply_revision(&mut self, revision: Option<&MetadataRevision>) {
+        let Some(revision) = revision else {
+            return;
+        };
+
+        for tag in revision.tags() {
+            let dest = match (tag.std_key, tag.key.to_ascii_uppercase().as_str()) {
+                (Some(StandardTagKey::ReplayGainTrackGain), _) | (_, "REPLAYGAIN_TRACK_GAIN") =>
+                    &mut self.track_gain,
+                (Some(StandardTagKey::ReplayGainTrackPeak), _) | (_, "REPLAYGAIN_TRACK_PEAK") =>
+                    &mut self.track_peak,
+                (Some(StandardTagKey::ReplayGainAlbumGain), _) | (_, "REPLAYGAIN_ALBUM_GAIN") =>
+                    &mut self.album_gain,
+                (Some(StandardTagKey::ReplayGainAlbumPeak), _) | (_, "REPLAYGAIN_ALBUM_PEAK") =>
+                    &mut self.album_peak,
+                _ => continue,
+            };
+
+            if let Some(val) = parse_tag_value(&tag.value.to_string()) {
+                *dest = Some(val);
+            }
+        }
+    }
+
+    fn gain(self, target: TargetLoudness) -> Option<f32> {
+        let (gain_db, peak) = match target {
+            TargetLoudness::ReplayGainTrack => (self.track_gain?, self.track_peak),
+            TargetLoudness::ReplayGainAlbum => match self.album_gain {
+                Some(gain) => (gain, self.album_peak.or(self.track_peak)),
+                None => (self.track_gain?, self.track_peak),
+            },
+            TargetLoudness::Lufs(lufs) => (
+                self.track_gain? + (lufs - REPLAYGAIN_REFERENCE_LUFS),
+                self.track_peak,
+            ),
+        };
+
+        let gain = 10f32.powf(gain_db / 20.0);
+
+        Some(match peak {
+            Some(peak) if peak > 0.0 => gain.min(peak.recip()),
+            _ => gain,
+        })
+    }
+}
+
+/// Parses a Replay Gain value such as `-6.20 dB` or `0.988553`.
+fn parse_tag_value(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let value = value
+        .strip_suffix("dB")
+        .or_else(|| value.strip_suffix("db"))
+        .or_else(|| value.strip_suffix("DB"))
+        .unwrap_or(value);
+
+    value.trim().parse::<f32>().ok().filter(|v| v.is_finite())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use symphonia_core::meta::{MetadataBuilder, Tag, Value};
+
+    fn tags(pairs: &[(Option<StandardTagKey>, &str, &str)]) -> ReplayGainTags {
+        let mut builder = MetadataBuilder::new();
+        for (std_key, key, value) in pairs {
+            builder.add_tag(Tag::new(*std_key, key, Value::from(*value)));
+        }
+
+        let mut out = ReplayGainTags::default();
+        out.apply_revision(Some(&builder.metadata()));
+        out
+    }
+
+    fn approx_eq(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    #[test]
+    fn replaygain_tags_are_parsed_by_key() {
+        let parsed = tags(&[
+            (
+                Some(StandardTagKey::ReplayGainTrackGain),
+                "REPLAYGAIN_TRACK_GAIN",
+                "-6.02 dB",
+            ),
+            (None, "replaygain_album_gain", "+2.5 dB"),
+            (None, "REPLAYGAIN_TRACK_PEAK", "0.25"),
+            (None, "COMMENT", "-100 dB"),
+        ]);
+
+        assert!(approx_eq(parsed.track_gain.unwrap(), -6.02));
+        assert!(approx_eq(parsed.album_gain.unwrap(), 2.5));
+        assert!(approx_eq(parsed.track_peak.unwrap(), 0.25));
+        assert!(parsed.album_peak.is_none());
+    }
+
+    #[test]
+    fn gain_targets_use_appropriate_tags() {
+        let parsed = tags(&[
+            (None, "REPLAYGAIN_TRACK_GAIN", "-6.0206 dB"),
+            (None, "REPLAYGAIN_ALBUM_GAIN", "0 dB"),
+        ]);
+
+        assert!(approx_eq(
+            parsed.gain(TargetLoudness::ReplayGainTrack).unwrap(),
+            0.5
+        ));
+        assert!(approx_eq(
+            parsed.gain(TargetLoudness::ReplayGainAlbum).unwrap(),
+            1.0
+        ));
+        // -24 LUFS is 6dB quieter than the Replay Gain reference.
+        assert!(approx_eq(
+            parsed.gain(TargetLoudness::Lufs(-24.0)).unwrap(),
+            10f32.powf(-12.0206 / 20.0)
+        ));
+    }
+
+    #[test]
+    fn gain_is_limited_by_peak_and_absent_without_tags() {
+        let parsed = tags(&[
+            (None, "REPLAYGAIN_TRACK_GAIN", "+12 dB"),
+            (None, "REPLAYGAIN_TRACK_PEAK", "0.5"),
+        ]);
+        assert!(approx_eq(
+            parsed.gain(TargetLoudness::ReplayGainTrack).unwrap(),
+            2.0
+        ));
+
+        let empty = tags(&[(None, "TITLE", "a song")]);
+        assert!(empty.gain(TargetLoudness::ReplayGainAlbum).is_none());
+        assert!(empty.gain(TargetLoudness::Lufs(-14.0)).is_none());
+    }
+}
@@ -18,10 +18,14 @@
 
 mod action;
 mod command;
+mod disposal;
+mod ducking;
 mod error;
 mod handle;
 mod looping;
+mod loudness;
 mod mode;
+mod passthrough;
 mod queue;
 mod ready;
 mod state;
@@ -29,10 +33,14 @@ mod view;
 
 pub use self::{
     action::*,
+    disposal::*,
+    ducking::*,
     error::*,
     handle::*,
     looping::*,
+    loudness::*,
     mode::*,
+    passthrough::*,
     queue::*,
     ready::*,
     state::*,
@@ -40,8 +48,13 @@ pub use self::{
 };
 pub(crate) use command::*;
 
-use crate::{constants::*, driver::tasks::message::*, events::EventStore, input::Input};
-use std::time::Duration;
+use crate::{
+    constants::*,
+    driver::tasks::message::*,
+    events::EventStore,
+    input::{AuxMetadata, AuxMetadataError, Input},
+};
+use std::{any::Any, sync::Arc, time::Duration};
 use uuid::Uuid;
 
 /// Initial state for audio playback.
@@ -104,6 +117,62 @@ pub struct Track {
     ///
     /// Defaults to a random 128-bit number.
     pub uuid: Uuid,
+
+    /// Position in the input at which playback should begin.
+    ///
+    /// When the driver first readies this track's input, it will seek to this
+    /// position before any audio is mixed. Seek failures (e.g., on inputs which
+    /// cannot seek) are reported in the same way as [`TrackHandle::seek`].
+    /// Loops restart from the beginning of the input, not this position.
+    ///
+    /// Defaults to [`Duration::ZERO`].
+    pub start_at: Duration,
+
+    /// Loudness normalisation applied to this track, on top of its [`volume`].
+    ///
+    /// The required gain is computed once the track's input has been parsed. If the
+    /// input lacks the metadata needed to reach this target, no gain is applied.
+    ///
+    /// Defaults to `None`.
+    ///
+    /// [`volume`]: Track::volume
+    pub gain_target: Option<TargetLoudness>,
+
+    /// This track's role in sidechain ducking, if any.
+    ///
+    /// Defaults to `None`.
+    pub ducking: Option<DuckRole>,
+
+    /// Maximum amount of audio to play from this track before it ends.
+    ///
+    /// This limits the track's [`play_time`], rather than its position or the wall-clock
+    /// time since it was played: time spent paused or readying the input does not count,
+    /// seeks neither add nor remove played time, and all loops count towards a single total.
+    /// When the limit is reached, the track ends and fires [`TrackEvent::End`].
+    ///
+    /// Defaults to `None`.
+    ///
+    /// [`play_time`]: TrackState::play_time
+    /// [`TrackEvent::End`]: crate::events::TrackEvent::End
+    pub max_duration: Option<Duration>,
+
+    /// User-defined data attached to this track.
+    ///
+    /// This is made available to every [`TrackHandle`] for this track, including those
+    /// passed to event handlers, via [`TrackHandle::data`]. Unlike the handle's
+    /// [`typemap`], this can be set before the track is played.
+    ///
+    /// Defaults to `()`.
+    ///
+    /// [`typemap`]: TrackHandle::typemap
+    pub user_data: Arc<dyn Any + Send + Sync>,
+
+    /// Callback run after the driver has removed this track and released its input.
+    ///
+    /// Defaults to `None`.
+    ///
+    /// See [`Track::on_dispose`] for when this is called.
+    pub disposal_hook: Option<DisposalHook>,
 }
 
 impl Track {
@@ -125,9 +194,45 @@ impl Track {
             events: EventStore::new_local(),
             loops: LoopState::Finite(0),
             uuid,
+            start_at: Duration::ZERO,
+            gain_target: None,
+            ducking: None,
+            max_duration: None,
+            user_data: Arc::new(()),
+            disposal_hook: None,
         }
     }
 
+    /// Create a new track directly from an [`Input`] and a random [`Uuid`], with
+    /// attached user data.
+    #[must_use]
+    pub fn new_with_data(input: Input, user_data: Arc<dyn Any + Send + Sync>) -> Self {
+        Self::new(input).user_data(user_data)
+    }
+
+    /// Create a new track from an [`Input`], after fetching its [`AuxMetadata`].
+    ///
+    /// The metadata is returned, and also attached as the track's [`user_data`]
+    /// so that it can later be read via [`TrackHandle::data`]. This requires that
+    /// the input has a [`Compose`], such as a [`YoutubeDl`] or [`HttpRequest`].
+    ///
+    /// [`user_data`]: Track::user_data
+    /// [`Compose`]: crate::input::Compose
+    /// [`YoutubeDl`]: crate::input::YoutubeDl
+    /// [`HttpRequest`]: crate::input::HttpRequest
+    ///
+    /// # Errors
+    ///
+    /// Fails if metadata could not be fetched, as in [`Input::aux_metadata`].
+    pub async fn with_aux_metadata(
+        mut input: Input,
+    ) -> Result<(Self, AuxMetadata), AuxMetadataError> {
+        let metadata = input.aux_metadata().await?;
+        let track = Self::new_with_data(input, Arc::new(metadata.clone()));
+
+        Ok((track, metadata))
+    }
+
     #[must_use]
     /// Sets a track to playing if it is paused.
     pub fn play(mut self) -> Self {
@@ -178,9 +283,90 @@ impl Track {
         self
     }
 
+    #[must_use]
+    /// Sets the position in the input at which playback should begin.
+    pub fn start_at(mut self, start_at: Duration) -> Self {
+        self.start_at = start_at;
+
+        self
+    }
+
+    #[must_use]
+    /// Normalises this track's loudness towards the given target.
+    ///
+    /// See [`TargetLoudness`] for how the required gain is found.
+    pub fn with_gain_target(mut self, target: TargetLoudness) -> Self {
+        self.gain_target = Some(target);
+
+        self
+    }
+
+    #[must_use]
+    /// Sets this track's role in sidechain ducking.
+    ///
+    /// See [`DuckRole`] for how ducking is applied.
+    pub fn ducking(mut self, role: DuckRole) -> Self {
+        self.ducking = Some(role);
+
+        self
+    }
+
+    #[must_use]
+    /// Ends this track once the given amount of audio has been played.
+    ///
+    /// See [`max_duration`] for how this is measured.
+    ///
+    /// [`max_duration`]: Track::max_duration
+    pub fn max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+
+        self
+    }
+
+    #[must_use]
+    /// Sets [`user_data`] in a manner that allows method chaining.
+    ///
+    /// [`user_data`]: Track::user_data
+    pub fn user_data(mut self, user_data: Arc<dyn Any + Send + Sync>) -> Self {
+        self.user_data = user_data;
+
+        self
+    }
+
+    #[must_use]
+    /// Runs `hook` once the driver has removed this track and released its resources.
+    ///
+    /// Tracks are removed by the mixer once they stop, end, or error, or when all
+    /// tracks are cleared (see [`RemovalReason`]). The hook is then given the track's
+    /// final state on the driver's disposal thread, after the track's [`Input`] has
+    /// been dropped: any files, network streams, or child processes it held are closed.
+    /// This makes it suited to cleanup which must follow the real end of the track,
+    /// such as deleting a temporary file. The hook should not block for long, as it
+    /// delays the disposal of other tracks.
+    ///
+    /// # Ordering
+    ///
+    /// A track is queued for disposal before the mixer reports its end to the event
+    /// thread, but the two threads then run independently. The hook may therefore
+    /// run before, during, or after any [`TrackEvent::End`] (or [`TrackEvent::Error`])
+    /// handlers, and must not rely on them having completed. Conversely, those
+    /// handlers may run while the track's input is still open. The hook is called
+    /// at most once, and is not called for tracks which are never given to a driver.
+    ///
+    /// [`TrackEvent::End`]: crate::events::TrackEvent::End
+    /// [`TrackEvent::Error`]: crate::events::TrackEvent::Error
+    pub fn on_dispose<F>(mut self, hook: F) -> Self
+    where
+        F: FnOnce(TrackState, RemovalReason) + Send + 'static,
+    {
+        self.disposal_hook = Some(Box::new(hook));
+
+        self
+    }
+
     pub(crate) fn into_context(self) -> (TrackHandle, TrackContext) {
         let (tx, receiver) = flume::unbounded();
-        let handle = TrackHandle::new(tx, self.uuid);
+        let handle = TrackHandle::new(tx, self.uuid, self.user_data.clone());
 
         let context = TrackContext {
             handle: handle.clone(),
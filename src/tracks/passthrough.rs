@@ -0,0 +1,39 @@
+/// Whether a track's Opus packets are being sent directly to Discord, without
+/// being decoded, mixed, and re-encoded.
+///
+/// The driver passes audio through when a single track is playing at volume `1.0`,
+/// from an Opus source with 20ms frames. This avoids nearly all of the CPU cost of
+/// mixing and encoding audio.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Passthrough {
+    /// This track's most recent audio frame was passed through.
+    Active,
+
+    /// This track is being mixed, or has not yet been played.
+    #[default]
+    Inactive,
+
+    /// This track contains Opus frames which cannot be passed through (e.g., frames
+    /// which are not 20ms long), and will always be decoded and mixed.
+    ///
+    /// [`TrackEvent::PassthroughBlocked`] fires when a track becomes blocked.
+    ///
+    /// [`TrackEvent::PassthroughBlocked`]: crate::events::TrackEvent::PassthroughBlocked
+    Blocked,
+}
+
+/// The Opus frame which caused a track's [`Passthrough`] to become [`Blocked`].
+///
+/// [`Blocked`]: Passthrough::Blocked
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct PassthroughBlock {
+    /// Length of the frame, in bytes.
+    pub len: usize,
+    /// Number of samples (per channel, at 48kHz) held in the frame.
+    ///
+    /// Frames are only passed through if they hold exactly 960 samples (20ms).
+    /// This is `None` if the frame is not a valid Opus packet.
+    pub samples: Option<usize>,
+}
@@ -122,16 +122,7 @@ impl EventHandler for QueueHandler {
         info!("Queued track ended: {:?}.", ctx);
         info!("{} tracks remain.", inner.tracks.len());
 
-        // Keep going until we find one track which works, or we run out.
-        while let Some(new) = inner.tracks.front() {
-            if new.play().is_err() {
-                // Discard files which cannot be used for whatever reason.
-                warn!("Track in Queue couldn't be played...");
-                inner.tracks.pop_front();
-            } else {
-                break;
-            }
-        }
+        inner.play_current();
 
         None
     }
@@ -272,6 +263,59 @@ impl TrackQueue {
         self.modify_queue(|vq| vq.remove(index))
     }
 
+    /// Removes and stops the track at the specified index, returning its handle.
+    ///
+    /// If this was the currently playing track, the next track in the queue
+    /// begins playing.
+    #[must_use]
+    pub fn remove(&self, index: usize) -> Option<TrackHandle> {
+        let mut inner = self.inner.lock();
+
+        let removed = inner.tracks.remove(index)?;
+        drop(removed.stop());
+
+        if index == 0 {
+            inner.play_current();
+        }
+
+        Some(removed.handle())
+    }
+
+    /// Moves the track at index `from` to index `to`, shifting all tracks in between.
+    ///
+    /// If this changes the head of the queue, the previously playing track is
+    /// paused and the new head begins playing. Returns `false` and leaves the
+    /// queue untouched if either index is out of bounds.
+    #[must_use]
+    pub fn move_track(&self, from: usize, to: usize) -> bool {
+        let mut inner = self.inner.lock();
+
+        if from >= inner.tracks.len() || to >= inner.tracks.len() {
+            return false;
+        }
+
+        if from == to {
+            return true;
+        }
+
+        let head_changed = from == 0 || to == 0;
+        if head_changed {
+            if let Some(old_head) = inner.tracks.front() {
+                drop(old_head.pause());
+            }
+        }
+
+        if let Some(track) = inner.tracks.remove(from) {
+            inner.tracks.insert(to, track);
+        }
+
+        if head_changed {
+            inner.play_current();
+        }
+
+        true
+    }
+
     /// Returns the number of tracks currently in the queue.
     #[must_use]
     pub fn len(&self) -> usize {
@@ -356,6 +400,21 @@ impl TrackQueue {
 }
 
 impl TrackQueueCore {
+    /// Play the track at the head of the queue.
+    ///
+    /// Keeps going until we find one track which works, or we run out.
+    fn play_current(&mut self) {
+        while let Some(new) = self.tracks.front() {
+            if new.play().is_err() {
+                // Discard files which cannot be used for whatever reason.
+                warn!("Track in Queue couldn't be played...");
+                self.tracks.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
     /// Skip to the next track in the queue, if it exists.
     fn stop_current(&self) -> TrackResult<()> {
         if let Some(handle) = self.tracks.front() {
@@ -371,7 +430,7 @@ mod tests {
     use crate::{
         driver::Driver,
         input::{File, HttpRequest},
-        tracks::PlayMode,
+        tracks::{PlayMode, TrackHandle},
         Config,
     };
     use reqwest::Client;
@@ -496,4 +555,51 @@ mod tests {
         assert!(h1a.await.is_err());
         assert_eq!(h2a.await.unwrap().playing, PlayMode::Play);
     }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn reordering_changes_current_track() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.clone());
+
+        let file1 = File::new("resources/ting.wav");
+        let file2 = file1.clone();
+        let file3 = file1.clone();
+
+        let h1 = driver.enqueue_input(file1.into()).await;
+        let h2 = driver.enqueue_input(file2.into()).await;
+        let h3 = driver.enqueue_input(file3.into()).await;
+
+        // Get h1 in place, playing. Wait for IO to ready.
+        t_handle
+            .ready_track(&h1, Some(Duration::from_millis(1)))
+            .await;
+
+        let queue = driver.queue();
+        assert!(!queue.move_track(0, 3));
+        assert!(queue.move_track(2, 0));
+        let uuids: Vec<_> = queue
+            .current_queue()
+            .iter()
+            .map(TrackHandle::uuid)
+            .collect();
+        assert_eq!(uuids, [h3.uuid(), h1.uuid(), h2.uuid()]);
+
+        assert_eq!(queue.remove(1).map(|h| h.uuid()), Some(h1.uuid()));
+        assert_eq!(queue.len(), 2);
+
+        let h1a = h1.get_info();
+        let h3_state = t_handle
+            .ready_track(&h3, Some(Duration::from_millis(1)))
+            .await;
+
+        // post-conditions:
+        // 1) track 1 was stopped on removal (commands fail).
+        // 2) track 3 is playing as the new head.
+        assert!(h1a.await.is_err());
+        assert_eq!(h3_state.playing, PlayMode::Play);
+
+        assert_eq!(queue.remove(0).map(|h| h.uuid()), Some(h3.uuid()));
+        assert_eq!(queue.current().map(|h| h.uuid()), Some(h2.uuid()));
+    }
 }
@@ -17,9 +17,22 @@ pub struct TrackState {
     ///
     /// This is altered by loops and seeks, and represents this track's
     /// position in its underlying input stream.
+    ///
+    /// Positions advance in steps of one 20ms audio frame. When passed to a
+    /// timed event handler, this is the position at the end of the frame on
+    /// which the event fired.
+    ///
+    /// Tracks are held by the driver rather than its voice connection, and do not
+    /// advance while disconnected: playback resumes from this position after any
+    /// reconnect. Inputs are never recreated on reconnect, so this holds for
+    /// non-seekable inputs too.
     pub position: Duration,
 
     /// Total playback time, increasing monotonically.
+    ///
+    /// As with [`position`], this has a precision of one 20ms audio frame.
+    ///
+    /// [`position`]: Self::position
     pub play_time: Duration,
 
     /// Remaining loops on this track.
@@ -28,6 +41,32 @@ pub struct TrackState {
     /// Whether this track has been made live, is being processed, or is
     /// currently uninitialised.
     pub ready: ReadyState,
+
+    /// Time taken to decode, resample, and mix this track's audio in the last tick.
+    ///
+    /// This is zero if the track was not mixed (e.g., it is paused or still being readied).
+    /// Tracks whose Opus frames are passed directly to the driver are very cheap, as they
+    /// are not decoded. This is only measured by the driver, and so is available via
+    /// [`TrackHandle::get_info`] rather than in event handlers.
+    ///
+    /// [`TrackHandle::get_info`]: TrackHandle::get_info
+    pub mix_cost: Duration,
+
+    /// Whether this track's Opus frames are being passed directly to Discord,
+    /// rather than being decoded and mixed.
+    ///
+    /// As with [`mix_cost`], this is only available via [`TrackHandle::get_info`].
+    ///
+    /// [`mix_cost`]: Self::mix_cost
+    /// [`TrackHandle::get_info`]: TrackHandle::get_info
+    pub passthrough: Passthrough,
+
+    /// The Opus frame which blocked this track from being passed through, if any.
+    ///
+    /// This is set when [`TrackEvent::PassthroughBlocked`] fires.
+    ///
+    /// [`TrackEvent::PassthroughBlocked`]: crate::events::TrackEvent::PassthroughBlocked
+    pub passthrough_block: Option<PassthroughBlock>,
 }
 
 impl TrackState {
@@ -41,12 +80,14 @@ impl TrackState {
 mod tests {
     use super::*;
     use crate::{
-        constants::test_data::YTDL_TARGET,
+        constants::test_data::{FILE_WEBM_TARGET, YTDL_TARGET},
         driver::Driver,
-        input::YoutubeDl,
+        events::{Event, EventContext, EventHandler},
+        input::{File, YoutubeDl},
         tracks::Track,
         Config,
     };
+    use flume::Sender;
     use reqwest::Client;
 
     #[tokio::test]
@@ -67,4 +108,43 @@ mod tests {
         assert_eq!(state.position, Duration::from_millis(20));
         assert_eq!(state.play_time, Duration::from_millis(20));
     }
+
+    struct PositionReporter(Sender<Duration>);
+
+    #[async_trait::async_trait]
+    impl EventHandler for PositionReporter {
+        async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+            if let EventContext::Track(&[(state, _)]) = ctx {
+                _ = self.0.send(state.position);
+            }
+            None
+        }
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10_000)]
+    async fn periodic_events_report_position_when_fired() {
+        let (t_handle, config) = Config::test_cfg(true);
+        let mut driver = Driver::new(config.clone());
+        let (tx, rx) = flume::unbounded();
+
+        let handle = driver.play(File::new(FILE_WEBM_TARGET).into());
+        handle
+            .add_event(
+                Event::Periodic(Duration::from_secs(1), None),
+                PositionReporter(tx),
+            )
+            .unwrap();
+        t_handle.ready_track(&handle, None).await;
+
+        t_handle.tick(160);
+        for expected in 1..=3 {
+            let expected = Duration::from_secs(expected);
+            let position = rx.recv_async().await.unwrap();
+            let error = position
+                .saturating_sub(expected)
+                .max(expected.saturating_sub(position));
+            assert!(error <= TIMESTEP_LENGTH);
+        }
+    }
 }
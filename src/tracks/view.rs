@@ -28,4 +28,8 @@ pub struct View<'a> {
 
     /// The number of remaning loops on this track.
     pub loops: &'a mut LoopState,
+
+    /// Whether this track's Opus frames are being passed directly to Discord,
+    /// rather than being decoded and mixed.
+    pub passthrough: Passthrough,
 }
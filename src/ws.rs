@@ -1,4 +1,4 @@
-use crate::{error::JsonError, model::Event};
+use crate::{driver::Proxy, error::JsonError, model::Event};
 
 use futures::{SinkExt, StreamExt, TryStreamExt};
 use tokio::{
@@ -21,17 +21,23 @@ pub struct WsStream(WebSocketStream<MaybeTlsStream<TcpStream>>);
 
 impl WsStream {
     #[instrument]
-    pub(crate) async fn connect(url: Url) -> Result<Self> {
-        let (stream, _) = tokio_tungstenite::connect_async_with_config::<Url>(
-            url,
-            Some(Config {
-                max_message_size: None,
-                max_frame_size: None,
-                ..Default::default()
-            }),
-            true,
-        )
-        .await?;
+    pub(crate) async fn connect(url: Url, proxy: Option<&Proxy>) -> Result<Self> {
+        let config = Some(Config {
+            max_message_size: None,
+            max_frame_size: None,
+            ..Default::default()
+        });
+
+        let (stream, _) = if let Some(proxy) = proxy {
+            let host = url.host_str().unwrap_or_default();
+            let port = url.port_or_known_default().unwrap_or(443);
+            let tcp = proxy.connect(host, port).await.map_err(Error::Proxy)?;
+            tcp.set_nodelay(true).map_err(Error::Proxy)?;
+
+            tokio_tungstenite::client_async_tls_with_config(url, tcp, config, None).await?
+        } else {
+            tokio_tungstenite::connect_async_with_config::<Url>(url, config, true).await?
+        };
 
         Ok(Self(stream))
     }
@@ -39,10 +45,13 @@ impl WsStream {
     pub(crate) async fn recv_json(&mut self) -> Result<Option<Event>> {
         const TIMEOUT: Duration = Duration::from_millis(500);
 
+        // A closed socket must fail here, rather than being retried until the caller's
+        // own timeout (e.g., when the server rejects a resume).
         let ws_message = match timeout(TIMEOUT, self.0.next()).await {
+            Ok(Some(Ok(Message::Close(None))) | None) => return Err(Error::WsClosed(None)),
             Ok(Some(Ok(v))) => Some(v),
             Ok(Some(Err(e))) => return Err(e.into()),
-            Ok(None) | Err(_) => None,
+            Err(_) => None,
         };
 
         convert_ws_message(ws_message)
@@ -71,7 +80,10 @@ pub enum Error {
     /// As a result, only text messages are expected.
     UnexpectedBinaryMessage(Vec<u8>),
 
-    Ws(TungsteniteError),
+    /// The websocket could not be dialed through the configured [`Proxy`].
+    Proxy(std::io::Error),
+
+    Ws(Box<TungsteniteError>),
 
     WsClosed(Option<CloseFrame<'static>>),
 }
@@ -84,7 +96,7 @@ impl From<JsonError> for Error {
 
 impl From<TungsteniteError> for Error {
     fn from(e: TungsteniteError) -> Error {
-        Error::Ws(e)
+        Error::Ws(Box::new(e))
     }
 }
 